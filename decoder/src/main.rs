@@ -0,0 +1,173 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use protocol::*;
+use qwer::{CaptureDirection, CaptureReader, OctData, TypeSchema, CLIENT_PROP_FLAG};
+use serde_json::json;
+
+const USAGE: &str = "usage:
+    nap-decode <protocol id> <hex|base64 payload> [--ret]
+    nap-decode blob <player|account> <hex|base64 stream> [property tag]
+    nap-decode capture <capture file>
+    nap-decode schema [protocol id]";
+
+fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["capture", path] => decode_capture(path),
+        ["schema"] => export_schema(None),
+        ["schema", protocol_id] => export_schema(Some(protocol_id.parse()?)),
+        ["blob", kind, stream] => decode_blob(kind, &parse_bytes(stream)?, CLIENT_PROP_FLAG),
+        ["blob", kind, stream, tag] => decode_blob(kind, &parse_bytes(stream)?, tag.parse()?),
+        [protocol_id, payload] => {
+            decode_payload(protocol_id.parse()?, &parse_bytes(payload)?, false)
+        }
+        [protocol_id, payload, "--ret"] => {
+            decode_payload(protocol_id.parse()?, &parse_bytes(payload)?, true)
+        }
+        _ => bail!(USAGE),
+    }
+}
+
+fn parse_bytes(input: &str) -> Result<Vec<u8>> {
+    let input = input.trim();
+    hex::decode(input)
+        .or_else(|_| base64::engine::general_purpose::STANDARD.decode(input))
+        .map_err(|_| anyhow!("payload is neither valid hex nor base64"))
+}
+
+fn decode_payload(protocol_id: u16, payload: &[u8], is_ret: bool) -> Result<()> {
+    let entry = lookup_protocol(protocol_id)
+        .with_context(|| format!("protocol id {protocol_id} isn't registered"))?;
+
+    let decoder = if is_ret {
+        entry
+            .decode_ret
+            .with_context(|| format!("{} has no rpc ret", entry.name))?
+    } else {
+        entry.decode_arg
+    };
+
+    let suffix = if is_ret { "Ret" } else { "Arg" };
+    println!("{}{suffix} ({protocol_id}):", entry.name);
+    print!("{}", decoder(payload)?);
+
+    if is_ret {
+        expand_blobs(protocol_id, payload)?;
+    }
+
+    Ok(())
+}
+
+// Rets carrying a PropertyBlob are decoded a second time to show the blob contents.
+fn expand_blobs(protocol_id: u16, payload: &[u8]) -> Result<()> {
+    let (kind, blob) = match protocol_id {
+        RPC_LOGIN_ID => ("account", unmarshal::<RpcLoginRet>(payload)?.account_info),
+        RPC_ENTER_WORLD_ID => (
+            "player",
+            unmarshal::<RpcEnterWorldRet>(payload)?.player_info,
+        ),
+        RPC_REENTER_WORLD_ID => (
+            "player",
+            unmarshal::<RpcReenterWorldRet>(payload)?.player_info,
+        ),
+        _ => return Ok(()),
+    };
+
+    if !blob.stream.is_empty() {
+        decode_blob(kind, &blob.stream, CLIENT_PROP_FLAG)?;
+    }
+
+    Ok(())
+}
+
+fn decode_blob(kind: &str, stream: &[u8], bt_property_tag: u16) -> Result<()> {
+    let mut stream = stream;
+    match kind {
+        "player" => {
+            println!("PlayerInfo (tag {bt_property_tag}):");
+            print!(
+                "{}",
+                PlayerInfo::unmarshal_from(&mut stream, bt_property_tag)?
+            );
+        }
+        "account" => {
+            println!("AccountInfo (tag {bt_property_tag}):");
+            print!(
+                "{}",
+                AccountInfo::unmarshal_from(&mut stream, bt_property_tag)?
+            );
+        }
+        _ => bail!("unknown blob kind {kind}, expected player or account"),
+    }
+
+    Ok(())
+}
+
+fn decode_capture(path: &str) -> Result<()> {
+    let reader = CaptureReader::new(BufReader::new(
+        File::open(path).with_context(|| format!("failed to open {path}"))?,
+    ))?;
+    println!("capture on channel {}", reader.channel_id());
+
+    for record in reader {
+        let record = record?;
+        let arrow = match record.direction {
+            CaptureDirection::Inbound => "->",
+            CaptureDirection::Outbound => "<-",
+        };
+
+        println!("\n{arrow} [{}] {:?}", record.timestamp_ms, record.header);
+
+        let is_ret = record.direction == CaptureDirection::Outbound && record.header.is_rpc_ret;
+        if let Err(err) = decode_payload(record.protocol_id, &record.payload, is_ret) {
+            println!("failed to decode: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+// Prints the protocols and every type they reference as JSON, for client-side tooling.
+fn export_schema(protocol_id: Option<u16>) -> Result<()> {
+    let entries =
+        match protocol_id {
+            Some(id) => vec![lookup_protocol(id)
+                .with_context(|| format!("protocol id {id} isn't registered"))?],
+            None => PROTOCOL_REGISTRY.iter().collect(),
+        };
+
+    let mut types: Vec<&'static TypeSchema> = Vec::new();
+    let mut protocols = Vec::new();
+    for entry in entries {
+        let arg = (entry.arg_schema)();
+        let ret = entry.ret_schema.map(|schema| schema());
+
+        for schema in std::iter::once(arg).chain(ret) {
+            for schema in schema.walk() {
+                if !types.iter().any(|seen| std::ptr::eq(*seen, schema)) {
+                    types.push(schema);
+                }
+            }
+        }
+
+        protocols.push(json!({
+            "id": entry.id,
+            "name": entry.name,
+            "arg": arg.name(),
+            "ret": ret.map(TypeSchema::name),
+        }));
+    }
+
+    let spec = json!({ "protocols": protocols, "types": types });
+    println!("{}", serde_json::to_string_pretty(&spec)?);
+    Ok(())
+}
+
+fn unmarshal<T: OctData>(payload: &[u8]) -> std::io::Result<T> {
+    T::unmarshal_from(&mut &payload[..], 0)
+}
@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use protocol::*;
+use qwer::OctData;
+
+fn unmarshal<T: OctData>(data: &[u8], bt_property_tag: u16) {
+    let _ = T::unmarshal_from(&mut &data[..], bt_property_tag);
+}
+
+fuzz_target!(|data: &[u8]| {
+    // The first byte selects which property tag to decode with, the rest is the payload.
+    let Some((&tag, data)) = data.split_first() else {
+        return;
+    };
+    let bt_property_tag = u16::from(tag & 1);
+
+    unmarshal::<AccountInfo>(data, bt_property_tag);
+    unmarshal::<PlayerInfo>(data, bt_property_tag);
+    unmarshal::<PropertyBlob>(data, bt_property_tag);
+    unmarshal::<RpcLoginArg>(data, 0);
+    unmarshal::<RpcHollowMoveArg>(data, 0);
+    unmarshal::<RpcEndBattleArg>(data, 0);
+    unmarshal::<RpcRunHollowEventGraphArg>(data, 0);
+    unmarshal::<RpcStartHollowQuestArg>(data, 0);
+    unmarshal::<RpcFinishEventGraphPerformShowArg>(data, 0);
+    unmarshal::<PtcPlayerInfoChangedArg>(data, 0);
+});
@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qwer::{ProtocolHeader, RequestBody, ResponseBody};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = ProtocolHeader::try_from(data);
+    let _ = ResponseBody::try_from(data);
+
+    if let Ok(request) = RequestBody::try_from(data) {
+        let protocol_id = request.protocol_id;
+        let payload = request.payload.clone();
+        let middlewares = request.middlewares.clone();
+
+        let encoded = Vec::try_from(request).unwrap();
+        let decoded = RequestBody::try_from(&encoded[..]).unwrap();
+
+        assert_eq!(decoded.protocol_id, protocol_id);
+        assert_eq!(decoded.payload, payload);
+        assert_eq!(decoded.middlewares, middlewares);
+    }
+});
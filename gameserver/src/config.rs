@@ -1,6 +1,8 @@
+use std::collections::HashMap;
 use std::sync::LazyLock;
 
 use common::util::load_or_create_config;
+use qwer::GatewayCodec;
 use serde::Deserialize;
 
 const DEFAULT_CONFIG: &str = include_str!("../gameserver.default.json");
@@ -10,6 +12,34 @@ pub struct GameServerConfig {
     pub gateway_endpoint: String,
     pub skip_tutorial: bool,
     pub system_resources_logging: bool,
+    #[serde(default)]
+    pub packet_limits: PacketLimits,
+    #[serde(default)]
+    pub capture_dir: Option<String>,
+    /// Client protocol signs mapped to the protocol version of their build.
+    #[serde(default)]
+    pub client_versions: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+pub struct PacketLimits {
+    pub max_header_size: usize,
+    pub max_body_size: usize,
+}
+
+impl Default for PacketLimits {
+    fn default() -> Self {
+        Self {
+            max_header_size: 64,
+            max_body_size: 1024 * 1024,
+        }
+    }
+}
+
+impl PacketLimits {
+    pub const fn codec(&self) -> GatewayCodec {
+        GatewayCodec::new(self.max_header_size, self.max_body_size)
+    }
 }
 
 pub static CONFIGURATION: LazyLock<GameServerConfig> = LazyLock::new(|| {
@@ -0,0 +1,877 @@
+#![allow(unused)]
+
+use std::collections::HashSet;
+
+use protocol::{HollowEventType, NodeState, NodeVisible};
+use serde::Deserialize;
+
+use super::{ConfigPredicate, ConfigValue, HollowPositionOffsetType};
+
+#[derive(Deserialize, Default)]
+#[serde(tag = "$type")]
+pub enum ConfigAction {
+    #[serde(rename = "Share.CConfigJump")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigJump {
+        jump: String,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        weight: Option<i32>,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+        #[serde(default)]
+        can_control: bool,
+        delay: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigSetSpecial")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSetSpecial {
+        special_name: String,
+        special_value: ConfigValue,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigSetHollowVariable")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSetHollowVariable {
+        key: String,
+        value: ConfigValue,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigEventModification")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventModification {
+        modification_num: Option<ConfigValue>,
+        modification_type: Option<i32>,
+        #[serde(default)]
+        index_list: Vec<ConfigValue>,
+        target_core_node: Option<i32>,
+        target_event_type: Option<i32>,
+        #[serde(default)]
+        #[serde(rename = "EventID")]
+        event_id: Vec<ConfigValue>,
+        event_state: Option<NodeState>,
+        visible_state: Option<NodeVisible>,
+        x: Option<ConfigValue>,
+        y: Option<ConfigValue>,
+        #[serde(default)]
+        position: HollowPositionOffsetType,
+        radius: Option<ConfigValue>,
+        #[serde(default)]
+        #[serde(rename = "TargetEventID")]
+        target_event_id: Vec<ConfigValue>,
+        #[serde(default)]
+        target_event_state: Vec<NodeState>,
+        #[serde(default)]
+        target_visible_state: Vec<NodeVisible>,
+        #[serde(rename = "ModifyID")]
+        modify_id: Option<i32>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(default)]
+        exclude_player_pos: bool,
+        #[serde(default)]
+        #[serde(rename = "ExcludeEventID")]
+        exclude_event_id: Vec<i32>,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigWaitSeconds")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigWaitSeconds {
+        time: ConfigValue,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigSetMapState")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSetMapState {
+        node_type: Option<String>,
+        #[serde(default)]
+        index_list: Vec<ConfigValue>,
+        count: Option<ConfigValue>,
+        #[serde(default)]
+        to_state: Vec<NodeState>,
+        #[serde(default)]
+        to_visible_state: Vec<NodeVisible>,
+        #[serde(default)]
+        x: ConfigValue,
+        #[serde(default)]
+        y: ConfigValue,
+        #[serde(default)]
+        position: HollowPositionOffsetType,
+        radius: Option<ConfigValue>,
+        #[serde(default)]
+        from_visible_state: HashSet<NodeVisible>,
+        #[serde(default)]
+        from_state: HashSet<NodeState>,
+        #[serde(default)]
+        r#type: HashSet<HollowEventType>,
+        #[serde(default)]
+        exclude_player_pos: bool,
+        #[serde(default)]
+        use_perform: bool,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+        event_type_tag: Option<String>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigFinishEvent")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigFinishEvent {
+        #[serde(default)]
+        can_trigger_again: bool,
+    },
+    #[serde(rename = "Share.CConfigLogText")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigLogText {
+        #[serde(default)]
+        messages: Vec<String>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigCloseDialog")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigCloseDialog {
+        #[serde(default)]
+        camera_move: bool,
+        #[serde(default)]
+        need_reset_center: bool,
+    },
+    #[serde(rename = "Share.CConfigChoiceServer")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigChoiceServer {
+        options_mode: String,
+        show_mode: Option<String>,
+        #[serde(default)]
+        choices: Vec<ConfigChoice>,
+        title: Option<String>,
+        description: Option<String>,
+        question_description: Option<String>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        random_num: Option<i32>,
+        #[serde(default)]
+        garbage: bool,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigDropPool")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigDropPool {
+        r#type: Option<String>,
+        #[serde(rename = "ItemID")]
+        item_id: Option<ConfigValue>,
+        num: Option<i32>,
+        #[serde(default)]
+        rare: Vec<i32>,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigShowTip")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigShowTip {
+        #[serde(rename = "TipID")]
+        tip_id: ConfigValue,
+        delay: Option<i32>,
+        r#type: Option<String>,
+        duration: Option<i32>,
+        #[serde(default)]
+        next_tips: Vec<ConfigAction>,
+        #[serde(default)]
+        need_click: bool,
+        #[serde(default)]
+        click_guide: bool,
+        show_page: Option<i32>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigOpenDialog")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigOpenDialog {
+        #[serde(default)]
+        camera_move: bool,
+        #[serde(default)]
+        open_event: bool,
+    },
+    #[serde(rename = "Share.CConfigCameraMove")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigCameraMove {
+        r#type: String,
+        stretch_key: Option<String>,
+        #[serde(default)]
+        can_control: bool,
+        bound_index_x: Option<ConfigValue>,
+        bound_index_y: Option<ConfigValue>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        bound_half_w: Option<ConfigValue>,
+        bound_half_h: Option<ConfigValue>,
+        #[serde(default)]
+        use_stretch_z: bool,
+    },
+    #[serde(rename = "Share.CConfigSetConditionProgress")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSetConditionProgress {
+        #[serde(rename = "ConditionID")]
+        condition_id: i32,
+        progress: i32,
+        modify_type: ConfigModifyType,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigAddItem")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigAddItem {
+        #[serde(rename = "ItemID")]
+        item_id: ConfigValue,
+        count: ConfigValue,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigModifySceneProperty")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigModifySceneProperty {
+        property: String,
+        modify_type: Option<ConfigModifyType>,
+        count: ConfigValue,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigSwitchServer")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSwitchServer {
+        #[serde(default)]
+        switch: Vec<ConfigSwitchCase>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigTriggerBattle")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigTriggerBattle {
+        #[serde(rename = "BattleID")]
+        battle_id: ConfigValue,
+        on_success: Option<String>,
+        #[serde(default)]
+        end_hollow: bool,
+        #[serde(default)]
+        goto_next_hollow: bool,
+        on_failure: Option<String>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigPreset")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigPreset { perform_key: String },
+    #[serde(rename = "Share.CConfigEmpty")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEmpty {
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigModifyProperty")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigModifyProperty {
+        property: String,
+        count: ConfigValue,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        modify_type: Option<ConfigModifyType>,
+        target: Option<String>,
+    },
+    #[serde(rename = "Share.CConfigIf")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigIf {
+        on_success: String,
+        on_failure: String,
+        #[serde(default)]
+        conditions: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigCameraMoveV2")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigCameraMoveV2 {
+        stretch_key: String,
+        r#type: String,
+        #[serde(default)]
+        can_control: bool,
+        #[serde(default)]
+        use_stretch_z: bool,
+        #[serde(default)]
+        freeze_z: bool,
+        #[serde(default)]
+        parallel: bool,
+        bound_index_x: Option<ConfigValue>,
+        bound_index_y: Option<ConfigValue>,
+        #[serde(default)]
+        position_offset_type: HollowPositionOffsetType,
+        position_offset_x: Option<ConfigValue>,
+        position_offset_y: Option<ConfigValue>,
+        radius_x: Option<i32>,
+        radius_y: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigRandomDropID")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigRandomDropID {
+        r#type: String,
+        #[serde(default)]
+        special_name: Vec<String>,
+        #[serde(default)]
+        rare: Vec<ConfigValue>,
+    },
+    #[serde(rename = "Share.CConfigPlayAnim")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigPlayAnim {
+        #[serde(rename = "AnimID")]
+        anim_id: Option<i32>,
+        waiting_time: Option<ConfigValue>,
+        #[serde(default)]
+        indexes: Vec<ConfigValue>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigGoto")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigGoto {
+        #[serde(rename = "GotoID")]
+        goto_id: ConfigValue,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigDropBattleID")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigDropBattleID {
+        #[serde(default)]
+        r#type: Vec<ConfigValue>,
+        special_name: String,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CActionChangeHollowEventWeightCfg")]
+    #[serde(rename_all = "PascalCase")]
+    ActionChangeHollowEventWeightCfg {
+        #[serde(rename = "EventID")]
+        event_id: i32,
+        weight_factor: i32,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigGotoNextHollow")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigGotoNextHollow {
+        #[serde(rename = "SectionID")]
+        section_id: Option<ConfigValue>,
+        #[serde(default)]
+        reward: bool,
+        initial_pos: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigFinishHollow")]
+    ConfigFinishHollow,
+    #[serde(rename = "Share.CConfigLog")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigLog {
+        show_variables: Option<String>,
+        message: Option<String>,
+    },
+    #[serde(rename = "Share.CConfigSetHollowItem")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSetHollowItem {
+        #[serde(rename = "EPCharge")]
+        ep_charge: Option<ConfigValue>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(rename = "HollowItemID")]
+        hollow_item_id: Option<ConfigValue>,
+    },
+    #[serde(rename = "Share.CConfigSetChallenge")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSetChallenge {
+        #[serde(rename = "ChallengeID")]
+        challenge_id: ConfigValue,
+    },
+    #[serde(rename = "Share.CConfigPrepareNextHollow")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigPrepareNextHollow {
+        #[serde(rename = "SectionID")]
+        section_id: Option<ConfigValue>,
+        #[serde(default)]
+        show_other: Vec<ConfigPredicate>,
+        initial_pos: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigChangeCharacter")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigChangeCharacter {
+        mode: i32,
+        change_from: i32,
+        #[serde(default)]
+        change_to: Vec<i32>,
+    },
+    #[serde(rename = "Share.CConfigTransfer")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigTransfer {
+        x: Option<ConfigValue>,
+        y: Option<ConfigValue>,
+        #[serde(default)]
+        random: bool,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(rename = "EventID")]
+        event_id: Option<i32>,
+        #[serde(rename = "TargetEventID")]
+        target_event_id: Option<i32>,
+        radius: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigRemoveCard")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigRemoveCard {
+        #[serde(rename = "ItemID")]
+        item_id: Option<ConfigValue>,
+        count: i32,
+        #[serde(default)]
+        rare: Vec<i32>,
+        r#type: Option<i32>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigDropCard")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigDropCard {
+        r#type: i32,
+        #[serde(default)]
+        genre: Vec<ConfigValue>,
+        #[serde(default)]
+        rare: Vec<i32>,
+        num: i32,
+        #[serde(default)]
+        specials: Vec<String>,
+    },
+    #[serde(rename = "Share.CConfigRemoveCurse")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigRemoveCurse {
+        remove_type: Option<String>,
+        count: ConfigValue,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(rename = "ItemID")]
+        item_id: Option<ConfigValue>,
+        buff_type: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigRandomBattleID")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigRandomBattleID {
+        #[serde(default)]
+        r#type: Vec<String>,
+        #[serde(default)]
+        tags: Vec<String>,
+        special_name: String,
+        #[serde(rename = "DefaultBattleID")]
+        default_battle_id: Option<i32>,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigDropCurse")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigDropCurse {
+        #[serde(rename = "CurseID")]
+        curse_id: ConfigValue,
+        stack: Option<String>,
+        time_count: Option<ConfigValue>,
+    },
+    #[serde(rename = "Share.CConfigHollowSetSwitchEffect")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigHollowSetSwitchEffect {
+        key: Option<String>,
+        index: Option<ConfigValue>,
+        #[serde(default)]
+        #[serde(rename = "play")]
+        play: bool,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigShowPopWindow")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigShowPopWindow {
+        #[serde(rename = "PopID")]
+        pop_id: i32,
+        #[serde(default)]
+        show_directly: bool,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigPerform")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigPerform {
+        #[serde(rename = "PerformID")]
+        perform_id: i32,
+    },
+    #[serde(rename = "Share.CConfigSetBattleType")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSetBattleType {
+        #[serde(rename = "BattleID")]
+        battle_id: ConfigValue,
+        r#type: String,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigSetHollowSystemState")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSetHollowSystemState {
+        #[serde(default)]
+        key: Vec<String>,
+        state: String,
+        #[serde(default)]
+        need_click: bool,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigNewHollow")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigNewHollow {
+        #[serde(default)]
+        hollow_maps: Vec<String>,
+        #[serde(rename = "SectionID")]
+        section_id: ConfigValue,
+    },
+    #[serde(rename = "Share.CConfigAddCharacter")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigAddCharacter {
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(default)]
+        add_to: Vec<i32>,
+    },
+    #[serde(rename = "Share.CConfigPushBack")]
+    ConfigPushBack,
+    #[serde(rename = "Share.CConfigRemoveAbility")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigRemoveAbility { ability_name: String },
+    #[serde(rename = "Share.CConfigChangePlayerState")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigChangePlayerState { target: String, state: String },
+    #[serde(rename = "Share.CConfigFinishQuest")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigFinishQuest {
+        #[serde(rename = "QuestID")]
+        quest_id: i32,
+    },
+    #[serde(rename = "Share.CConfigGetIndexByFilter")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigGetIndexByFilter {
+        #[serde(default)]
+        exclude_player_pos: bool,
+        node_type: String,
+        x: Option<ConfigValue>,
+        y: Option<ConfigValue>,
+        #[serde(default)]
+        position: HollowPositionOffsetType,
+        radius: Option<i32>,
+        count: ConfigValue,
+        #[serde(default)]
+        r#type: HashSet<HollowEventType>,
+        #[serde(default)]
+        from_visible_state: HashSet<NodeVisible>,
+        #[serde(default)]
+        from_state: HashSet<NodeState>,
+        list_special_name: String,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+        #[serde(default)]
+        index_list: Vec<ConfigValue>,
+    },
+    #[serde(rename = "Share.CConfigListSpecialOpt")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigListSpecialOpt {
+        opt: String,
+        list_special_name: String,
+        return_special: String,
+        #[serde(rename = "ID")]
+        id: Option<i32>,
+    },
+    #[serde(rename = "Share.CConfigPlayPostEffect")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigPlayPostEffect {
+        r#type: String,
+        key: String,
+        #[serde(default)]
+        enable: bool,
+    },
+    #[serde(rename = "Share.CConfigShop")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigShop {
+        #[serde(default)]
+        empty_messages: Vec<String>,
+        description: String,
+        #[serde(default)]
+        shop_info: Vec<ConfigShopInfo>,
+    },
+    #[serde(rename = "Share.CConfigHollowDistance")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigHollowDistance {
+        index1: ConfigValue,
+        index2: ConfigValue,
+        special: String,
+    },
+    #[serde(rename = "Share.CConfigSlotMachine")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigSlotMachine {
+        #[serde(default)]
+        indexes: Vec<i32>,
+        end_index: String,
+        #[serde(rename = "EndEventID")]
+        end_event_id: String,
+        #[serde(rename = "SetEventID")]
+        set_event_id: Option<String>,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigRandomArcana")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigRandomArcana {
+        arcana_count: i32,
+        slot1: i32,
+        slot2: i32,
+        slot3: i32,
+        on_success: String,
+    },
+    #[serde(rename = "Share.CConfigApplyAbility")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigApplyAbility { ability_name: String },
+    #[serde(rename = "Share.CConfigSetHollowBlackout")]
+    ConfigSetHollowBlackout,
+    #[serde(rename = "Share.CConfigFinishBlackout")]
+    ConfigFinishBlackout,
+    #[serde(rename = "Share.CConfigRandSpecial")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigRandSpecial {
+        min_count: ConfigValue,
+        max_count: ConfigValue,
+        special: String,
+    },
+    #[serde(rename = "Share.CConfigCloseTips")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigCloseTips {
+        #[serde(rename = "TipID")]
+        tip_id: i32,
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigGetCharacterInfo")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigGetCharacterInfo {
+        property: String,
+        #[serde(default)]
+        get_property: Vec<String>,
+    },
+    #[serde(rename = "Share.CConfigFreezeChessBoardCamera")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigFreezeChessBoardCamera {
+        stretch_key: String,
+        index_x: i32,
+        index_y: i32,
+    },
+    #[serde(rename = "Share.CConfigRandomCardIDFromItem")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigRandomCardIDFromItem {
+        r#type: i32,
+        num: i32,
+        #[serde(default)]
+        special_name: Vec<String>,
+    },
+    #[serde(rename = "Share.CConfigCameraStretch")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigCameraStretch {
+        shake_key: String,
+        #[serde(default)]
+        parallel: bool,
+    },
+    #[serde(rename = "Share.CConfigGetIndex")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigGetIndex {
+        position: HollowPositionOffsetType,
+        index: String,
+        x: ConfigValue,
+        y: ConfigValue,
+    },
+    #[serde(rename = "Share.CConfigPlaySound")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigPlaySound {
+        #[serde(rename = "SoundID")]
+        sound_id: i32,
+    },
+    #[serde(rename = "Share.CConfigEvenHP")]
+    ConfigEvenHP,
+    #[serde(rename = "Share.CConfigDropChallengeIDs")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigDropChallengeIDs {
+        #[serde(default)]
+        group: Vec<i32>,
+        count: i32,
+        #[serde(default)]
+        specials: Vec<String>,
+    },
+    #[serde(rename = "Share.CConfigDropHollowItem")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigDropHollowItem {
+        #[serde(default)]
+        #[serde(rename = "ItemIDs")]
+        item_ids: Vec<ConfigValue>,
+    },
+    #[serde(rename = "Share.CConfigLockCurse")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigLockCurse {
+        #[serde(default)]
+        lock: bool,
+        count: i32,
+    },
+    #[serde(rename = "Share.CConfigBanHollowEvent")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigBanHollowEvent {
+        #[serde(default)]
+        hollow_events: Vec<i32>,
+        #[serde(default)]
+        ban: bool,
+    },
+    #[default]
+    #[serde(other)]
+    ConfigUnknown,
+}
+
+impl ConfigAction {
+    /// Label that `ConfigGoto` refers to.
+    pub fn id(&self) -> Option<i32> {
+        match self {
+            Self::ConfigJump { id, .. }
+            | Self::ConfigSetSpecial { id, .. }
+            | Self::ConfigSetHollowVariable { id, .. }
+            | Self::ConfigEventModification { id, .. }
+            | Self::ConfigSetMapState { id, .. }
+            | Self::ConfigChoiceServer { id, .. }
+            | Self::ConfigDropPool { id, .. }
+            | Self::ConfigEmpty { id, .. }
+            | Self::ConfigGoto { id, .. }
+            | Self::ConfigRandomBattleID { id, .. }
+            | Self::ConfigListSpecialOpt { id, .. } => *id,
+            _ => None,
+        }
+    }
+
+    /// Conditions for running the action, it's skipped if any of them fails.
+    pub fn predicates(&self) -> &[ConfigPredicate] {
+        match self {
+            Self::ConfigJump { predicates, .. }
+            | Self::ConfigSetSpecial { predicates, .. }
+            | Self::ConfigSetHollowVariable { predicates, .. }
+            | Self::ConfigEventModification { predicates, .. }
+            | Self::ConfigWaitSeconds { predicates, .. }
+            | Self::ConfigSetMapState { predicates, .. }
+            | Self::ConfigLogText { predicates, .. }
+            | Self::ConfigChoiceServer { predicates, .. }
+            | Self::ConfigShowTip { predicates, .. }
+            | Self::ConfigCameraMove { predicates, .. }
+            | Self::ConfigSetConditionProgress { predicates, .. }
+            | Self::ConfigAddItem { predicates, .. }
+            | Self::ConfigModifySceneProperty { predicates, .. }
+            | Self::ConfigSwitchServer { predicates, .. }
+            | Self::ConfigTriggerBattle { predicates, .. }
+            | Self::ConfigModifyProperty { predicates, .. }
+            | Self::ConfigPlayAnim { predicates, .. }
+            | Self::ConfigGoto { predicates, .. }
+            | Self::ConfigDropBattleID { predicates, .. }
+            | Self::ActionChangeHollowEventWeightCfg { predicates, .. }
+            | Self::ConfigSetHollowItem { predicates, .. }
+            | Self::ConfigTransfer { predicates, .. }
+            | Self::ConfigRemoveCard { predicates, .. }
+            | Self::ConfigRemoveCurse { predicates, .. }
+            | Self::ConfigRandomBattleID { predicates, .. }
+            | Self::ConfigHollowSetSwitchEffect { predicates, .. }
+            | Self::ConfigShowPopWindow { predicates, .. }
+            | Self::ConfigSetBattleType { predicates, .. }
+            | Self::ConfigSetHollowSystemState { predicates, .. }
+            | Self::ConfigAddCharacter { predicates, .. }
+            | Self::ConfigGetIndexByFilter { predicates, .. }
+            | Self::ConfigSlotMachine { predicates, .. }
+            | Self::ConfigCloseTips { predicates, .. } => predicates,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigModifyType {
+    Add,
+    Replace,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConfigChoice {
+    pub option_text: String,
+    pub option_text2: Option<String>,
+    pub option_desc1: Option<String>,
+    pub title_text: Option<String>,
+    #[serde(rename = "OptionItemID1")]
+    pub option_item_id1: Option<ConfigValue>,
+    pub option_icon1: Option<ConfigValue>,
+    pub select_group: Option<i32>,
+    #[serde(default)]
+    pub option_contents: Vec<ConfigChoiceContent>,
+    #[serde(default)]
+    pub predicates: Vec<ConfigPredicate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConfigChoiceContent {
+    #[serde(default)]
+    pub weight: ConfigValue,
+    pub weight_group: Option<i32>,
+    #[serde(default)]
+    pub actions: Vec<ConfigAction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConfigSwitchCase {
+    #[serde(default)]
+    pub weight: ConfigValue,
+    #[serde(default)]
+    pub conditions: Vec<ConfigPredicate>,
+    #[serde(default)]
+    pub actions: Vec<ConfigAction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConfigShopInfo {
+    pub shop_type: String,
+    pub show_type: Option<String>,
+    pub token_type: String,
+    #[serde(default)]
+    pub slot: Vec<ConfigShopSlot>,
+    #[serde(default)]
+    pub predicates: Vec<ConfigPredicate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConfigShopSlot {
+    pub item_type: Option<String>,
+    #[serde(rename = "ItemID")]
+    pub item_id: Option<ConfigValue>,
+    pub buy_count: i32,
+    pub price: Option<i32>,
+    pub discount: Option<ConfigValue>,
+    pub weight: Option<ConfigValue>,
+    pub group: Option<i32>,
+    #[serde(default)]
+    pub rare: Vec<i32>,
+}
@@ -0,0 +1,184 @@
+#![allow(unused)]
+
+use protocol::{NodeState, NodeVisible};
+use serde::Deserialize;
+
+use super::{ConfigValue, HollowPositionOffsetType};
+
+#[derive(Deserialize, Default)]
+#[serde(tag = "$type")]
+pub enum ConfigPredicate {
+    #[serde(rename = "Share.CConfigEventByHollowVariable")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByHollowVariable {
+        key: String,
+        compare_type: ConfigCompareType,
+        count: ConfigValue,
+    },
+    #[serde(rename = "Share.CConfigEventBySpecials")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventBySpecials {
+        param_name: String,
+        param: ConfigValue,
+        compare_type: ConfigCompareType,
+    },
+    #[serde(rename = "Share.CConfigEventByEventState")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByEventState {
+        x: ConfigValue,
+        y: ConfigValue,
+        node_type: Option<String>,
+        position: HollowPositionOffsetType,
+        #[serde(default)]
+        #[serde(rename = "EventID")]
+        event_id: Vec<ConfigValue>,
+        count: i32,
+        compare_type: ConfigCompareType,
+        radius: Option<i32>,
+        #[serde(default)]
+        visible_state: Vec<NodeVisible>,
+        #[serde(default)]
+        state: Vec<NodeState>,
+        event_type: Option<String>,
+        #[serde(default)]
+        event_tags: Vec<String>,
+    },
+    #[serde(rename = "Share.CConfigEventByItemID")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByItemID {
+        #[serde(rename = "ItemID")]
+        item_id: ConfigValue,
+        compare_type: ConfigCompareType,
+        count: ConfigValue,
+        package: Option<String>,
+    },
+    #[serde(rename = "Share.CConfigEventByCompareParam")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByCompareParam {
+        param1: ConfigValue,
+        param2: ConfigValue,
+        compare_type: ConfigCompareType,
+    },
+    #[serde(rename = "Share.CConfigEventByCurse")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByCurse {
+        compare_type: ConfigCompareType,
+        count: i32,
+        #[serde(rename = "ItemID")]
+        item_id: Option<ConfigValue>,
+        r#type: Option<i32>,
+        #[serde(default)]
+        rare: Vec<i32>,
+    },
+    #[serde(rename = "Share.CConfigEventByOr")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByOr {
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigEventByDirection")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByDirection {
+        #[serde(default)]
+        direction: Vec<ConfigDirection>,
+    },
+    #[serde(rename = "Share.CConfigEventByNot")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByNot {
+        #[serde(default)]
+        predicates: Vec<ConfigPredicate>,
+    },
+    #[serde(rename = "Share.CConfigEventByRandom")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByRandom { odds: ConfigValue },
+    #[serde(rename = "Share.CConfigEventByTarotCard")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByTarotCard {
+        #[serde(rename = "ItemID")]
+        item_id: Option<ConfigValue>,
+        count: i32,
+        compare_type: ConfigCompareType,
+        card_type: Option<i32>,
+        #[serde(default)]
+        card_rare: Vec<i32>,
+    },
+    #[serde(rename = "Share.CConfigEventByCharacterState")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByCharacterState {
+        team_slot: String,
+        compare_type: ConfigCompareType,
+        state: String,
+        count: i32,
+    },
+    #[serde(rename = "Share.CConfigEventByAvatarID")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByAvatarID {
+        #[serde(rename = "AvatarID")]
+        avatar_id: i32,
+    },
+    #[serde(rename = "Share.CConfigEventByMainCharacter")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByMainCharacter {
+        compare_type: ConfigCompareType,
+        #[serde(rename = "AvatarID")]
+        avatar_id: i32,
+    },
+    #[serde(rename = "Share.CConfigEventByTarotCardGenre")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByTarotCardGenre {
+        count: i32,
+        r#type: i32,
+        #[serde(default)]
+        genre: Vec<ConfigValue>,
+        #[serde(default)]
+        rare: Vec<i32>,
+        compare_type: ConfigCompareType,
+    },
+    #[serde(rename = "Share.CConfigEventByHollowLevel")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByHollowLevel {
+        level: ConfigValue,
+        compare_type: ConfigCompareType,
+    },
+    #[serde(rename = "Share.CConfigEventBySceneProperty")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventBySceneProperty {
+        property: String,
+        compare_type: ConfigCompareType,
+        count: ConfigValue,
+    },
+    #[serde(rename = "Share.CConfigEventByMultipass")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByMultipass {
+        count: i32,
+        compare_type: ConfigCompareType,
+    },
+    #[serde(rename = "Share.CConfigEventByChallenge")]
+    #[serde(rename_all = "PascalCase")]
+    ConfigEventByChallenge {
+        #[serde(rename = "ChallengeID")]
+        challenge_id: ConfigValue,
+    },
+
+    #[default]
+    #[serde(other)]
+    ConfigUnknown,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigCompareType {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
@@ -1,45 +1,151 @@
 #![allow(unused)]
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
-use protocol::{HollowEventType, NodeState, NodeVisible};
+use serde::de::{MapAccess, Visitor};
 use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use super::{ConfigAction, ConfigPredicate};
 
 #[derive(Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct ConfigEventGraph {
     #[serde(rename = "ID")]
     pub id: i32,
+    #[serde(deserialize_with = "deserialize_events")]
     pub events: BTreeMap<ConfigEventType, ConfigEvent>,
+    #[serde(default)]
+    pub specials: HashMap<String, i32>,
+    #[serde(default)]
+    pub event_specials: HashMap<String, i32>,
+    #[serde(default)]
+    pub event_type_tag: Vec<String>,
 }
 
 #[derive(Deserialize, Default)]
 #[serde(untagged)]
 pub enum ConfigValue {
     Constant(i32),
+    Float(f64),
     Expression(String),
     #[default]
     Empty,
 }
 
-#[derive(Deserialize, Ord, PartialOrd, Eq, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone, Debug)]
+#[serde(from = "String")]
 pub enum ConfigEventType {
     OnStart,
     OnEnd,
     OnBro,
     OnSis,
-    #[serde(other)]
-    Unknown,
+    OnHollowCreated,
+    OnCreate,
+    OnPlayerMove,
+    OnTurn,
+    ItemTrigger,
+    Skip,
+    Selector,
+    /// Events without a fixed trigger that other actions refer to by name, like `GetIndexes_Lv1`.
+    Named(String),
+}
+
+impl From<String> for ConfigEventType {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "OnStart" => Self::OnStart,
+            "OnEnd" => Self::OnEnd,
+            "OnBro" => Self::OnBro,
+            "OnSis" => Self::OnSis,
+            "OnHollowCreated" => Self::OnHollowCreated,
+            "OnCreate" => Self::OnCreate,
+            "OnPlayerMove" => Self::OnPlayerMove,
+            "OnTurn" => Self::OnTurn,
+            "ItemTrigger" => Self::ItemTrigger,
+            "Skip" => Self::Skip,
+            "Selector" => Self::Selector,
+            _ => Self::Named(name),
+        }
+    }
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct ConfigEvent {
-    #[serde(default)]
+    #[serde(skip)]
+    pub id: i32,
+    #[serde(default, alias = "Acitons")]
     pub actions: Vec<ConfigAction>,
 }
 
+impl ConfigEvent {
+    pub fn action_id(&self, index: usize) -> i32 {
+        self.id + index as i32 + 1
+    }
+}
+
+impl ConfigEventGraph {
+    pub fn event(&self, event_id: i32) -> Option<&ConfigEvent> {
+        self.events.values().find(|event| event.id == event_id)
+    }
+
+    pub fn event_by_name(&self, name: &str) -> Option<&ConfigEvent> {
+        self.events.get(&ConfigEventType::from(name.to_string()))
+    }
+
+    /// Resolves an id from `EventInfo::action_move_path`.
+    pub fn action(&self, action_id: i32) -> Option<(&ConfigEvent, usize)> {
+        let event = self.event(action_id - action_id % EVENT_ID_STEP)?;
+        let index = usize::try_from(action_id % EVENT_ID_STEP - 1).ok()?;
+        (index < event.actions.len()).then_some((event, index))
+    }
+
+    /// Finds the action `ConfigGoto` jumps to, labels are unique across the graph's events.
+    pub fn labelled_action(&self, label: i32) -> Option<(&ConfigEvent, usize)> {
+        self.events.values().find_map(|event| {
+            let index = event
+                .actions
+                .iter()
+                .position(|action| action.id() == Some(label))?;
+            Some((event, index))
+        })
+    }
+}
+
+// The client numbers events by their position in the graph: 1000, 2000 and so on, and their
+// actions from there.
+const EVENT_ID_STEP: i32 = 1000;
+
+fn deserialize_events<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<ConfigEventType, ConfigEvent>, D::Error> {
+    struct EventsVisitor;
+
+    impl<'de> Visitor<'de> for EventsVisitor {
+        type Value = BTreeMap<ConfigEventType, ConfigEvent>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map of events")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut events = BTreeMap::new();
+            while let Some((event_type, mut event)) =
+                map.next_entry::<ConfigEventType, ConfigEvent>()?
+            {
+                event.id = (events.len() as i32 + 1) * EVENT_ID_STEP;
+                events.insert(event_type, event);
+            }
+
+            Ok(events)
+        }
+    }
+
+    deserializer.deserialize_map(EventsVisitor)
+}
+
 #[derive(Deserialize, Default)]
 pub enum HollowPositionOffsetType {
     #[default]
@@ -48,55 +154,50 @@ pub enum HollowPositionOffsetType {
     EventPos = 2,
 }
 
-#[derive(Deserialize, Default)]
-#[serde(tag = "$type")]
-pub enum ConfigAction {
-    #[serde(rename = "Share.CConfigEmpty")]
-    ConfigEmpty,
-    #[serde(rename = "Share.CConfigSetMapState")]
-    #[serde(rename_all = "PascalCase")]
-    ConfigSetMapState {
-        #[serde(default)]
-        x: ConfigValue,
-        #[serde(default)]
-        y: ConfigValue,
-        #[serde(default)]
-        position: HollowPositionOffsetType,
-        radius: Option<ConfigValue>,
-        count: Option<ConfigValue>,
-        #[serde(default)]
-        r#type: HashSet<HollowEventType>,
-        event_type_tag: Option<String>,
-        #[serde(default)]
-        from_visible_state: HashSet<NodeVisible>,
-        #[serde(default)]
-        to_visible_state: Vec<NodeVisible>,
-        #[serde(default)]
-        from_state: HashSet<NodeState>,
-        #[serde(default)]
-        to_state: Vec<NodeState>,
-        #[serde(default)]
-        exclude_player_pos: bool,
-        #[serde(default)]
-        index_list: Vec<ConfigValue>,
-        #[serde(default)]
-        use_perform: bool,
-    },
-    #[serde(rename = "Share.CConfigTriggerBattle")]
-    #[serde(rename_all = "PascalCase")]
-    ConfigTriggerBattle {
-        #[serde(rename = "BattleID")]
-        battle_id: ConfigValue,
-        on_success: Option<String>,
-        on_failure: Option<String>,
-        #[serde(default)]
-        end_hollow: bool,
-        #[serde(default)]
-        goto_next_hollow: bool,
-    },
-    #[serde(rename = "Share.CConfigFinishHollow")]
-    ConfigFinishHollow,
-    #[default]
-    #[serde(other)]
-    ConfigUnknown,
+/// Counts the `$type`s in a raw event graph collection that neither `ConfigAction` nor
+/// `ConfigPredicate` has a variant for.
+pub fn unknown_config_types(collection: &Value) -> BTreeMap<String, usize> {
+    let mut known = HashMap::new();
+    let mut unknown = BTreeMap::new();
+    count_unknown_types(collection, &mut known, &mut unknown);
+    unknown
+}
+
+fn count_unknown_types<'a>(
+    value: &'a Value,
+    known: &mut HashMap<&'a str, bool>,
+    unknown: &mut BTreeMap<String, usize>,
+) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(name)) = object.get("$type") {
+                if !*known
+                    .entry(name.as_str())
+                    .or_insert_with(|| is_known_config_type(name))
+                {
+                    *unknown.entry(name.clone()).or_default() += 1;
+                }
+            }
+            object
+                .values()
+                .for_each(|value| count_unknown_types(value, known, unknown));
+        }
+        Value::Array(array) => array
+            .iter()
+            .for_each(|value| count_unknown_types(value, known, unknown)),
+        _ => (),
+    }
+}
+
+// Only unknown tags fall through to `ConfigUnknown`; known ones either parse or fail on
+// their missing fields.
+fn is_known_config_type(name: &str) -> bool {
+    let tagged = serde_json::json!({ "$type": name });
+    !matches!(
+        serde_json::from_value(tagged.clone()),
+        Ok(ConfigAction::ConfigUnknown)
+    ) || !matches!(
+        serde_json::from_value(tagged),
+        Ok(ConfigPredicate::ConfigUnknown)
+    )
 }
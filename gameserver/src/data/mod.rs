@@ -1,3 +1,5 @@
+mod config_action;
+mod config_predicate;
 mod event_graph;
 mod templates;
 mod tsv_util;
@@ -5,48 +7,67 @@ mod tsv_util;
 use std::{collections::HashMap, path::Path};
 
 use anyhow::{bail, Result};
+pub use config_action::*;
+pub use config_predicate::*;
 pub use event_graph::*;
 use paste::paste;
 pub use templates::*;
 use tokio::sync::OnceCell;
 
 macro_rules! template_collections {
-    ($($template_type:ident;)*) => {
-        $(paste! {
+    ($($dir:literal => { $($template_type:ident;)* })*) => {
+        $($(paste! {
             static [<$template_type:snake:upper _COLLECTION>]: OnceCell<Vec<[<$template_type Template>]>> = OnceCell::const_new();
-        })*
+        })*)*
 
         fn init_template_collections() -> Result<()> {
-            $(paste! {
-                let path = concat!("assets/TemplateCollections/", stringify!($template_type), "TemplateCollection.tsv");
+            $($(paste! {
+                let path = concat!($dir, "/", stringify!($template_type), "TemplateCollection.tsv");
                 let data = std::fs::read_to_string(path)?;
                 [<$template_type:snake:upper _COLLECTION>].set(tsv_util::from_str(&data)?).unwrap();
-            })*
+            })*)*
 
             Ok(())
         }
 
-        $(paste! {
+        $($(paste! {
             pub fn [<iter_ $template_type:snake _collection>]() -> ::std::slice::Iter<'static, [<$template_type Template>]> {
                 [<$template_type:snake:upper _COLLECTION>].get().unwrap().iter()
             }
-        })*
+        })*)*
     };
 }
 
 template_collections! {
-    AvatarConfig;
-    UnlockConfig;
-    MainCityObject;
-    NPCTransform;
+    "assets/TemplateCollections" => {
+        AvatarConfig;
+        UnlockConfig;
+        MainCityObject;
+        NPCTransform;
+    }
+    // Hand-written stand-ins for game tables that aren't available yet, not game data.
+    "assets/PlaceholderCollections" => {
+        HollowDropPool;
+        HollowBattlePool;
+        HollowShopPrice;
+        HollowItem;
+    }
 }
 
 static EVENT_GRAPH_COLLECTION: OnceCell<HashMap<i32, ConfigEventGraph>> = OnceCell::const_new();
 
 fn init_binoutput() -> Result<()> {
-    let _ = EVENT_GRAPH_COLLECTION.set(serde_json::from_str(
-        std::fs::read_to_string("assets/BinOutput/EventGraphCollection.json")?.as_str(),
-    )?);
+    let data = std::fs::read_to_string("assets/BinOutput/EventGraphCollection.json")?;
+    let _ = EVENT_GRAPH_COLLECTION.set(serde_json::from_str(&data)?);
+
+    let unknown = unknown_config_types(&serde_json::from_str(&data)?);
+    for (name, count) in &unknown {
+        tracing::warn!("EventGraphCollection: {name} is not supported yet ({count} uses)");
+    }
+    if unknown.is_empty() {
+        tracing::info!("EventGraphCollection: all config types are supported");
+    }
+
     Ok(())
 }
 
@@ -160,3 +160,44 @@ pub struct AvatarConfigTemplate {
     #[serde(rename = "RBPProbability")]
     pub rbp_probability: i32,
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct HollowDropPoolTemplate {
+    pub pool_type: String,
+    #[serde(rename = "ItemID")]
+    pub item_id: i32,
+    pub rare: i32,
+    pub genre: i32,
+    pub weight: u32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct HollowBattlePoolTemplate {
+    pub group: i32,
+    #[serde(rename = "BattleID")]
+    pub battle_id: i32,
+    pub weight: u32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct HollowShopPriceTemplate {
+    pub shop_type: String,
+    pub rare: i32,
+    pub price: i32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct HollowItemTemplate {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    pub group: i32,
+    pub level: i32,
+    #[serde(rename = "EventGraphID")]
+    pub event_graph_id: i32,
+    #[serde(rename = "EPCost")]
+    pub ep_cost: i32,
+}
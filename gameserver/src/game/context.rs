@@ -1,10 +1,7 @@
 use std::sync::Arc;
 
-use anyhow::Result;
 use parking_lot::RwLock;
-use protocol::{PlayerInfo, PtcPlayerInfoChangedArg};
-
-use crate::net::NetworkSession;
+use protocol::PlayerInfo;
 
 use super::manager::{
     DungeonManager, HollowGridManager, ItemManager, QuestManager, SceneUnitManager,
@@ -39,47 +36,3 @@ impl GameContext {
         }
     }
 }
-
-pub struct PlayerOperationResult<T>
-where
-    T: Send + Sync,
-{
-    result: T,
-    player_info_changes: Option<PlayerInfo>,
-}
-
-impl<T> PlayerOperationResult<T>
-where
-    T: Send + Sync,
-{
-    pub fn take(self) -> T {
-        self.result
-    }
-
-    pub async fn send_changes(mut self, session: &NetworkSession) -> Result<T> {
-        if self.player_info_changes.is_some() {
-            let ptc_player_info_changed = PtcPlayerInfoChangedArg {
-                player_uid: session.player_uid().raw(),
-                player_info: self.player_info_changes.take().unwrap(),
-            };
-
-            session.push_rpc_arg(101, ptc_player_info_changed).await?;
-        }
-
-        Ok(self.take())
-    }
-
-    pub const fn ret(result: T) -> Self {
-        Self {
-            result,
-            player_info_changes: None,
-        }
-    }
-
-    pub const fn with_changes(result: T, player_info_changes: PlayerInfo) -> Self {
-        Self {
-            result,
-            player_info_changes: Some(player_info_changes),
-        }
-    }
-}
@@ -0,0 +1,68 @@
+use protocol::DungeonContentDropPoolType;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::data::{self, HollowDropPoolTemplate};
+
+pub fn pool_type(name: &str) -> Option<DungeonContentDropPoolType> {
+    Some(match name {
+        "Card" => DungeonContentDropPoolType::Card,
+        "BaneCard" => DungeonContentDropPoolType::BaneCard,
+        "Arcana" => DungeonContentDropPoolType::Arcana,
+        "Blessing" => DungeonContentDropPoolType::Blessing,
+        "Curse" => DungeonContentDropPoolType::Curse,
+        "Reward" => DungeonContentDropPoolType::Reward,
+        "HollowItem" => DungeonContentDropPoolType::HollowItem,
+        _ => return None,
+    })
+}
+
+pub fn entry(item_id: i32) -> Option<&'static HollowDropPoolTemplate> {
+    data::iter_hollow_drop_pool_collection().find(|entry| entry.item_id == item_id)
+}
+
+/// Whether `item_id` has one of the `rare` rarities and `genre`s, empty lists match anything.
+pub fn matches(item_id: i32, rare: &[i32], genre: &[i32]) -> bool {
+    if rare.is_empty() && genre.is_empty() {
+        return true;
+    }
+
+    entry(item_id).is_some_and(|entry| {
+        (rare.is_empty() || rare.contains(&entry.rare))
+            && (genre.is_empty() || genre.contains(&entry.genre))
+    })
+}
+
+/// Draws an item from `pool` by weight. `rare` and `genre` limit the candidates when they
+/// aren't empty, items in `exclude` are never drawn.
+pub fn draw(
+    pool: &DungeonContentDropPoolType,
+    rare: &[i32],
+    genre: &[i32],
+    exclude: &[i32],
+    rng: &mut impl Rng,
+) -> Option<i32> {
+    let candidates = data::iter_hollow_drop_pool_collection()
+        .filter(|entry| pool_type(&entry.pool_type).as_ref() == Some(pool))
+        .filter(|entry| rare.is_empty() || rare.contains(&entry.rare))
+        .filter(|entry| genre.is_empty() || genre.contains(&entry.genre))
+        .filter(|entry| !exclude.contains(&entry.item_id))
+        .collect::<Vec<_>>();
+
+    candidates
+        .choose_weighted(rng, |entry| entry.weight)
+        .ok()
+        .map(|entry| entry.item_id)
+}
+
+/// Draws a battle from any of the battle `groups` by weight.
+pub fn draw_battle(groups: &[i32], rng: &mut impl Rng) -> Option<i32> {
+    let candidates = data::iter_hollow_battle_pool_collection()
+        .filter(|entry| groups.contains(&entry.group))
+        .collect::<Vec<_>>();
+
+    candidates
+        .choose_weighted(rng, |entry| entry.weight)
+        .ok()
+        .map(|entry| entry.battle_id)
+}
@@ -0,0 +1,918 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use protocol::{DungeonContentDropPoolType, NodeState, NodeVisible};
+
+use super::expression::{self, ExpressionContext};
+use super::hollow_party;
+use crate::data::{
+    ConfigAction, ConfigChoice, ConfigCompareType, ConfigEvent, ConfigEventGraph, ConfigPredicate,
+    ConfigValue, HollowPositionOffsetType,
+};
+
+// Stops graphs that loop through control flow without ever reaching a client action.
+const MAX_STEPS: usize = 1024;
+
+/// State of the running hollow that event graph predicates and actions work on.
+pub trait EventContext: ExpressionContext {
+    fn item_count(&self, item_id: i32) -> i32;
+
+    /// Whether the avatar in `team_slot` is alive, `None` when the slot is empty.
+    fn is_alive(&self, team_slot: i8) -> Option<bool>;
+
+    /// Counts the cards or curses of `pools` held in the run, limited to `item_id`, `rare` and
+    /// `genre` when they're set.
+    fn held_count(
+        &self,
+        pools: &[DungeonContentDropPoolType],
+        item_id: Option<i32>,
+        rare: &[i32],
+        genre: &[i32],
+    ) -> i32;
+
+    /// Counts the grids of the map that `filter` matches.
+    fn count_grids(&self, filter: &GridFilter) -> i32;
+
+    /// Succeeds with a chance of `odds` percent.
+    fn roll(&mut self, odds: f64) -> bool;
+
+    /// Applies the server side effects of an action the client has run.
+    fn execute(&mut self, action: &ConfigAction);
+}
+
+/// Grids within `radius` steps of (`x`, `y`) whose event is one of `event_ids`, in one of
+/// `states` and `visible_states`. Empty lists match any grid.
+pub struct GridFilter<'a> {
+    pub x: i32,
+    pub y: i32,
+    pub position: &'a HollowPositionOffsetType,
+    pub radius: i32,
+    pub event_ids: Vec<i32>,
+    pub states: &'a [NodeState],
+    pub visible_states: &'a [NodeVisible],
+}
+
+pub struct EventRun {
+    pub action_move_path: Vec<i32>,
+    /// Actions skipped because their predicates failed, and the ones the client took instead
+    /// of the server's branch.
+    pub failed_actions: HashSet<i32>,
+    pub finished: bool,
+}
+
+pub fn evaluate(value: &ConfigValue, context: &mut impl EventContext) -> Result<f64> {
+    match value {
+        ConfigValue::Constant(value) => Ok(f64::from(*value)),
+        ConfigValue::Float(value) => Ok(*value),
+        ConfigValue::Expression(value) => expression::evaluate(value, context),
+        ConfigValue::Empty => Ok(0.0),
+    }
+}
+
+fn compare(lhs: f64, rhs: f64, compare_type: ConfigCompareType) -> bool {
+    match compare_type {
+        ConfigCompareType::Equal => lhs == rhs,
+        ConfigCompareType::NotEqual => lhs != rhs,
+        ConfigCompareType::Greater => lhs > rhs,
+        ConfigCompareType::GreaterEqual => lhs >= rhs,
+        ConfigCompareType::Less => lhs < rhs,
+        ConfigCompareType::LessEqual => lhs <= rhs,
+    }
+}
+
+fn call(context: &mut impl EventContext, function: &str, name: &str) -> Result<f64> {
+    let name = expression::interpolate(name, context)?;
+    context.call(function, &[name])
+}
+
+pub fn check(predicate: &ConfigPredicate, context: &mut impl EventContext) -> Result<bool> {
+    Ok(match predicate {
+        ConfigPredicate::ConfigEventByHollowVariable {
+            key,
+            compare_type,
+            count,
+        } => compare(
+            call(context, "scenevar", key)?,
+            evaluate(count, context)?,
+            *compare_type,
+        ),
+        ConfigPredicate::ConfigEventBySpecials {
+            param_name,
+            param,
+            compare_type,
+        } => compare(
+            call(context, "specials", param_name)?,
+            evaluate(param, context)?,
+            *compare_type,
+        ),
+        ConfigPredicate::ConfigEventBySceneProperty {
+            property,
+            compare_type,
+            count,
+        } => compare(
+            call(context, "sceneproperty", property)?,
+            evaluate(count, context)?,
+            *compare_type,
+        ),
+        ConfigPredicate::ConfigEventByCompareParam {
+            param1,
+            param2,
+            compare_type,
+        } => compare(
+            evaluate(param1, context)?,
+            evaluate(param2, context)?,
+            *compare_type,
+        ),
+        ConfigPredicate::ConfigEventByItemID {
+            item_id,
+            compare_type,
+            count,
+            ..
+        } => {
+            let item_id = evaluate(item_id, context)? as i32;
+            compare(
+                f64::from(context.item_count(item_id)),
+                evaluate(count, context)?,
+                *compare_type,
+            )
+        }
+        ConfigPredicate::ConfigEventByCharacterState {
+            team_slot,
+            compare_type,
+            state,
+            count,
+        } => {
+            let Some(slot) = hollow_party::team_slot(team_slot) else {
+                bail!("unknown team slot {team_slot}");
+            };
+            let in_state = match state.as_str() {
+                "Alive" => context.is_alive(slot) == Some(true),
+                "Death" => context.is_alive(slot) == Some(false),
+                _ => bail!("character state {state} is not supported"),
+            };
+            compare(
+                f64::from(u8::from(in_state)),
+                f64::from(*count),
+                *compare_type,
+            )
+        }
+        ConfigPredicate::ConfigEventByTarotCard {
+            item_id,
+            count,
+            compare_type,
+            card_type,
+            card_rare,
+        } => {
+            let item_id = evaluate_id(item_id.as_ref(), context)?;
+            let held = context.held_count(&card_pools(*card_type), item_id, card_rare, &[]);
+            compare(f64::from(held), f64::from(*count), *compare_type)
+        }
+        ConfigPredicate::ConfigEventByTarotCardGenre {
+            count,
+            r#type,
+            genre,
+            rare,
+            compare_type,
+        } => {
+            let genre = genre
+                .iter()
+                .map(|genre| Ok(evaluate(genre, context)? as i32))
+                .collect::<Result<Vec<_>>>()?;
+            let held = context.held_count(&card_pools(Some(*r#type)), None, rare, &genre);
+            compare(f64::from(held), f64::from(*count), *compare_type)
+        }
+        ConfigPredicate::ConfigEventByCurse {
+            compare_type,
+            count,
+            item_id,
+            r#type,
+            rare,
+        } => {
+            let item_id = evaluate_id(item_id.as_ref(), context)?;
+            let held = context.held_count(&curse_pools(*r#type), item_id, rare, &[]);
+            compare(f64::from(held), f64::from(*count), *compare_type)
+        }
+        ConfigPredicate::ConfigEventByHollowLevel {
+            level,
+            compare_type,
+        } => compare(
+            context.call("hollowlevel", &[])?,
+            evaluate(level, context)?,
+            *compare_type,
+        ),
+        ConfigPredicate::ConfigEventByRandom { odds } => {
+            let odds = evaluate(odds, context)?;
+            context.roll(odds)
+        }
+        ConfigPredicate::ConfigEventByOr { predicates } => {
+            for predicate in predicates {
+                if check(predicate, context)? {
+                    return Ok(true);
+                }
+            }
+
+            false
+        }
+        ConfigPredicate::ConfigEventByNot { predicates } => !check_all(predicates, context)?,
+        ConfigPredicate::ConfigEventByEventState {
+            x,
+            y,
+            node_type,
+            position,
+            event_id,
+            count,
+            compare_type,
+            radius,
+            visible_state,
+            state,
+            event_type,
+            event_tags,
+        } => {
+            let filters_all = |value: &Option<String>| value.as_deref().is_none_or(|v| v == "All");
+            if !filters_all(node_type) || !filters_all(event_type) || !event_tags.is_empty() {
+                bail!("event state by node type, event type or tags is not supported");
+            }
+
+            let filter = GridFilter {
+                x: evaluate(x, context)? as i32,
+                y: evaluate(y, context)? as i32,
+                position,
+                radius: radius.unwrap_or_default(),
+                event_ids: event_id
+                    .iter()
+                    .map(|id| Ok(evaluate(id, context)? as i32))
+                    .collect::<Result<_>>()?,
+                states: state,
+                visible_states: visible_state,
+            };
+            compare(
+                f64::from(context.count_grids(&filter)),
+                f64::from(*count),
+                *compare_type,
+            )
+        }
+        _ => bail!("unsupported predicate"),
+    })
+}
+
+fn evaluate_id(
+    value: Option<&ConfigValue>,
+    context: &mut impl EventContext,
+) -> Result<Option<i32>> {
+    value
+        .map(|value| Ok(evaluate(value, context)? as i32))
+        .transpose()
+}
+
+/// Card type 0 is a tarot card and 1 a bane card, either one when it isn't set.
+pub fn card_pools(card_type: Option<i32>) -> Vec<DungeonContentDropPoolType> {
+    match card_type {
+        Some(0) => vec![DungeonContentDropPoolType::Card],
+        Some(_) => vec![DungeonContentDropPoolType::BaneCard],
+        None => vec![
+            DungeonContentDropPoolType::Card,
+            DungeonContentDropPoolType::BaneCard,
+        ],
+    }
+}
+
+/// Curse type 0 is a blessing and 1 a curse, either one when it isn't set.
+pub fn curse_pools(curse_type: Option<i32>) -> Vec<DungeonContentDropPoolType> {
+    match curse_type {
+        Some(0) => vec![DungeonContentDropPoolType::Blessing],
+        Some(_) => vec![DungeonContentDropPoolType::Curse],
+        None => vec![
+            DungeonContentDropPoolType::Curse,
+            DungeonContentDropPoolType::Blessing,
+        ],
+    }
+}
+
+fn check_all(predicates: &[ConfigPredicate], context: &mut impl EventContext) -> Result<bool> {
+    for predicate in predicates {
+        if !check(predicate, context)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+// Predicates the server can't evaluate don't hold, so nothing they guard is handed out.
+pub fn holds(predicates: &[ConfigPredicate], context: &mut impl EventContext) -> bool {
+    check_all(predicates, context).unwrap_or_else(|err| {
+        tracing::warn!("failed to evaluate predicates, assuming they don't hold: {err}");
+        false
+    })
+}
+
+/// Continues `action_move_path` from `start_action_id`, executing the actions the client
+/// reported in `client_path` and resolving control flow on the way, until reaching an action
+/// the client hasn't run yet or the end of the event.
+pub fn run_event(
+    graph: &ConfigEventGraph,
+    start_action_id: i32,
+    action_move_path: Vec<i32>,
+    client_path: &[i32],
+    context: &mut impl EventContext,
+) -> EventRun {
+    let mut run = EventRun {
+        action_move_path,
+        failed_actions: HashSet::new(),
+        finished: false,
+    };
+
+    advance(
+        graph,
+        graph.action(start_action_id),
+        &mut run,
+        Some(client_path),
+        context,
+    );
+    run
+}
+
+/// Runs an event the server fires by itself, like `OnCreate` or `OnTurn`, executing every
+/// action on the way instead of waiting for the client to report it.
+pub fn run_server_event(
+    graph: &ConfigEventGraph,
+    event: &ConfigEvent,
+    context: &mut impl EventContext,
+) -> EventRun {
+    let mut run = EventRun {
+        action_move_path: Vec::new(),
+        failed_actions: HashSet::new(),
+        finished: false,
+    };
+
+    advance(graph, Some((event, 0)), &mut run, None, context);
+    run
+}
+
+/// Continues after the player picked `choice` of the ChoiceServer action that ends
+/// `action_move_path`. One of the choice's contents is run by weight, then the event goes on
+/// at its jump target or after the choice.
+pub fn run_choice(
+    graph: &ConfigEventGraph,
+    choice: &ConfigChoice,
+    action_move_path: Vec<i32>,
+    context: &mut impl EventContext,
+) -> EventRun {
+    let mut cursor = next_action(graph, &action_move_path);
+
+    let weights = choice
+        .option_contents
+        .iter()
+        .map(|content| {
+            evaluate(&content.weight, context).unwrap_or_else(|err| {
+                tracing::warn!("event graph {}: bad choice weight: {err}", graph.id);
+                0.0
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(index) = pick_weighted(&weights, context) {
+        for action in &choice.option_contents[index].actions {
+            if !holds(action.predicates(), context) {
+                continue;
+            }
+
+            if let ConfigAction::ConfigJump { jump, .. } = action {
+                cursor = jump_to(graph, jump);
+                break;
+            }
+
+            context.execute(action);
+        }
+    }
+
+    let mut run = EventRun {
+        action_move_path,
+        failed_actions: HashSet::new(),
+        finished: false,
+    };
+
+    advance(graph, cursor, &mut run, Some(&[]), context);
+    run
+}
+
+/// Goes on after the server action that ends `action_move_path`, once the player's input for
+/// it has been applied.
+pub fn resume(
+    graph: &ConfigEventGraph,
+    action_move_path: Vec<i32>,
+    context: &mut impl EventContext,
+) -> EventRun {
+    let cursor = next_action(graph, &action_move_path);
+    resume_from(graph, cursor, action_move_path, context)
+}
+
+/// Goes on with an event after the battle its last action triggered was won, from the
+/// battle's `OnSuccess` event when it has one.
+pub fn resume_battle(
+    graph: &ConfigEventGraph,
+    action_move_path: Vec<i32>,
+    context: &mut impl EventContext,
+) -> EventRun {
+    let cursor = match action_move_path.last().and_then(|id| graph.action(*id)) {
+        Some((event, index)) => match &event.actions[index] {
+            ConfigAction::ConfigTriggerBattle {
+                on_success: Some(on_success),
+                ..
+            } => jump_to(graph, on_success),
+            _ => Some((event, index + 1)),
+        },
+        None => None,
+    };
+    resume_from(graph, cursor, action_move_path, context)
+}
+
+// Stops at the first action the client has to run, it reports it in the next request.
+fn resume_from<'a>(
+    graph: &'a ConfigEventGraph,
+    cursor: Option<(&'a ConfigEvent, usize)>,
+    action_move_path: Vec<i32>,
+    context: &mut impl EventContext,
+) -> EventRun {
+    let mut run = EventRun {
+        action_move_path,
+        failed_actions: HashSet::new(),
+        finished: false,
+    };
+
+    advance(graph, cursor, &mut run, Some(&[]), context);
+    run
+}
+
+fn next_action<'a>(
+    graph: &'a ConfigEventGraph,
+    action_move_path: &[i32],
+) -> Option<(&'a ConfigEvent, usize)> {
+    action_move_path
+        .last()
+        .and_then(|id| graph.action(*id))
+        .map(|(event, index)| (event, index + 1))
+}
+
+/// Actions that wait for the player's input through another request before going on.
+pub fn awaits_player(action: &ConfigAction) -> bool {
+    match action {
+        ConfigAction::ConfigChoiceServer { .. } => true,
+        ConfigAction::ConfigRemoveCurse { remove_type, .. } => {
+            remove_type.as_deref() == Some("Initiative")
+        }
+        _ => false,
+    }
+}
+
+/// Picks an index with a chance proportional to its weight, or the first one when no weight is
+/// set at all.
+pub fn pick_weighted(weights: &[f64], context: &mut impl EventContext) -> Option<usize> {
+    let mut remaining = weights.iter().map(|weight| weight.max(0.0)).sum::<f64>();
+    if remaining <= 0.0 {
+        return (!weights.is_empty()).then_some(0);
+    }
+
+    for (index, weight) in weights.iter().enumerate() {
+        let weight = weight.max(0.0);
+        if weight > 0.0 && context.roll(weight / remaining * 100.0) {
+            return Some(index);
+        }
+
+        remaining -= weight;
+    }
+
+    weights.iter().rposition(|weight| *weight > 0.0)
+}
+
+fn advance<'a>(
+    graph: &'a ConfigEventGraph,
+    mut cursor: Option<(&'a ConfigEvent, usize)>,
+    run: &mut EventRun,
+    // `None` when the server runs the event by itself.
+    client_path: Option<&[i32]>,
+    context: &mut impl EventContext,
+) {
+    let reported = client_path.map(|path| path.iter().copied().collect::<HashSet<_>>());
+    let client_path = client_path.unwrap_or_default();
+
+    for step in 0.. {
+        if step == MAX_STEPS {
+            tracing::warn!(
+                "event graph {}: gave up after {MAX_STEPS} actions",
+                graph.id
+            );
+            break;
+        }
+
+        let Some((event, index)) = cursor.filter(|(event, index)| *index < event.actions.len())
+        else {
+            run.action_move_path.push(-1);
+            run.finished = true;
+            break;
+        };
+
+        let action = &event.actions[index];
+        let id = event.action_id(index);
+        if !holds(action.predicates(), context) {
+            run.failed_actions.insert(id);
+            cursor = Some((event, index + 1));
+            continue;
+        }
+
+        run.action_move_path.push(id);
+        cursor = match action {
+            ConfigAction::ConfigJump { jump, .. } => jump_to(graph, jump),
+            ConfigAction::ConfigIf {
+                on_success,
+                on_failure,
+                conditions,
+            } => jump_to(
+                graph,
+                if holds(conditions, context) {
+                    on_success
+                } else {
+                    on_failure
+                },
+            ),
+            ConfigAction::ConfigGoto { goto_id, .. } => {
+                let label = evaluate(goto_id, context).unwrap_or_else(|err| {
+                    tracing::warn!("event graph {}: bad goto: {err}", graph.id);
+                    0.0
+                });
+                graph.labelled_action(label as i32)
+            }
+            // Waits for `HollowGridManager::make_choice` or `remove_curses`.
+            _ if awaits_player(action) => break,
+            _ if reported
+                .as_ref()
+                .is_some_and(|reported| !reported.contains(&id)) =>
+            {
+                break
+            }
+            _ => {
+                context.execute(action);
+
+                // The client goes on from an empty action by itself, battles resume through
+                // `HollowGridManager::battle_finished`.
+                if client_path.last() == Some(&id)
+                    && matches!(
+                        action,
+                        ConfigAction::ConfigEmpty { .. } | ConfigAction::ConfigTriggerBattle { .. }
+                    )
+                {
+                    break;
+                }
+
+                match action {
+                    ConfigAction::ConfigRandomArcana { on_success, .. } => {
+                        jump_to(graph, on_success)
+                    }
+                    _ => Some((event, index + 1)),
+                }
+            }
+        };
+    }
+
+    run.failed_actions.extend(
+        client_path
+            .iter()
+            .filter(|id| **id != -1 && !run.action_move_path.contains(id)),
+    );
+}
+
+fn jump_to<'a>(graph: &'a ConfigEventGraph, name: &str) -> Option<(&'a ConfigEvent, usize)> {
+    let event = graph.event_by_name(name);
+    if event.is_none() {
+        tracing::warn!("event graph {}: no event named {name}", graph.id);
+    }
+
+    event.map(|event| (event, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::data::ConfigEventType;
+
+    #[derive(Default)]
+    struct TestContext {
+        variables: HashMap<String, i32>,
+        // Event ids of the map's grids along with their state.
+        grids: Vec<(i32, NodeState)>,
+    }
+
+    impl ExpressionContext for TestContext {
+        fn call(&mut self, function: &str, args: &[String]) -> Result<f64> {
+            match (function, args) {
+                ("scenevar" | "specials", [name]) => Ok(f64::from(
+                    self.variables.get(name).copied().unwrap_or_default(),
+                )),
+                _ => bail!("unknown function {function}"),
+            }
+        }
+    }
+
+    impl EventContext for TestContext {
+        fn item_count(&self, _item_id: i32) -> i32 {
+            0
+        }
+
+        fn is_alive(&self, _team_slot: i8) -> Option<bool> {
+            None
+        }
+
+        fn held_count(
+            &self,
+            _pools: &[DungeonContentDropPoolType],
+            _item_id: Option<i32>,
+            _rare: &[i32],
+            _genre: &[i32],
+        ) -> i32 {
+            0
+        }
+
+        fn count_grids(&self, filter: &GridFilter) -> i32 {
+            self.grids
+                .iter()
+                .filter(|(id, _)| filter.event_ids.contains(id))
+                .filter(|(_, state)| filter.states.is_empty() || filter.states.contains(state))
+                .count() as i32
+        }
+
+        fn roll(&mut self, odds: f64) -> bool {
+            odds >= 100.0
+        }
+
+        fn execute(&mut self, action: &ConfigAction) {
+            if let ConfigAction::ConfigSetHollowVariable {
+                key,
+                value: ConfigValue::Constant(value),
+                ..
+            } = action
+            {
+                self.variables.insert(key.clone(), *value);
+            }
+        }
+    }
+
+    fn graph(events: serde_json::Value) -> ConfigEventGraph {
+        serde_json::from_value(json!({ "ID": 1, "Events": events })).unwrap()
+    }
+
+    fn set_variable(key: &str, value: i32) -> serde_json::Value {
+        json!({ "$type": "Share.CConfigSetHollowVariable", "Key": key, "Value": value })
+    }
+
+    fn variable_equals(key: &str, count: i32) -> serde_json::Value {
+        json!({
+            "$type": "Share.CConfigEventByHollowVariable",
+            "Key": key,
+            "CompareType": "Equal",
+            "Count": count,
+        })
+    }
+
+    fn start(graph: &ConfigEventGraph) -> &ConfigEvent {
+        &graph.events[&ConfigEventType::OnStart]
+    }
+
+    fn named<'a>(graph: &'a ConfigEventGraph, name: &str) -> &'a ConfigEvent {
+        graph.event_by_name(name).unwrap()
+    }
+
+    #[test]
+    fn test_jump() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [
+                { "$type": "Share.CConfigJump", "Jump": "Target" },
+                set_variable("skipped", 1),
+            ] },
+            "Target": { "Actions": [set_variable("reached", 1)] },
+        }));
+        let (start, target) = (start(&graph), named(&graph, "Target"));
+
+        let mut context = TestContext::default();
+        let run = run_event(
+            &graph,
+            start.action_id(0),
+            Vec::new(),
+            &[start.action_id(0), target.action_id(0)],
+            &mut context,
+        );
+
+        assert_eq!(
+            run.action_move_path,
+            [start.action_id(0), target.action_id(0), -1]
+        );
+        assert!(run.finished);
+        assert_eq!(context.variables.get("reached"), Some(&1));
+        assert_eq!(context.variables.get("skipped"), None);
+    }
+
+    #[test]
+    fn test_if() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [{
+                "$type": "Share.CConfigIf",
+                "OnSuccess": "Yes",
+                "OnFailure": "No",
+                "Conditions": [variable_equals("x", 1)],
+            }] },
+            "Yes": { "Actions": [set_variable("branch", 1)] },
+            "No": { "Actions": [set_variable("branch", 2)] },
+        }));
+
+        for (x, branch, event) in [(1, 1, "Yes"), (0, 2, "No")] {
+            let mut context = TestContext::default();
+            context.variables.insert(String::from("x"), x);
+            let run = run_server_event(&graph, start(&graph), &mut context);
+
+            assert_eq!(
+                run.action_move_path,
+                [
+                    start(&graph).action_id(0),
+                    named(&graph, event).action_id(0),
+                    -1
+                ]
+            );
+            assert_eq!(context.variables.get("branch"), Some(&branch));
+        }
+    }
+
+    #[test]
+    fn test_goto() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [{ "$type": "Share.CConfigGoto", "GotoID": "specials(label)" }] },
+            "Labels": { "Actions": [
+                { "$type": "Share.CConfigEmpty", "ID": 7 },
+                set_variable("after_7", 1),
+                { "$type": "Share.CConfigEmpty", "ID": 8 },
+                set_variable("after_8", 1),
+            ] },
+        }));
+        let labels = named(&graph, "Labels");
+
+        let mut context = TestContext::default();
+        context.variables.insert(String::from("label"), 8);
+        let run = run_server_event(&graph, start(&graph), &mut context);
+
+        assert_eq!(
+            run.action_move_path,
+            [
+                start(&graph).action_id(0),
+                labels.action_id(2),
+                labels.action_id(3),
+                -1
+            ]
+        );
+        assert_eq!(context.variables.get("after_7"), None);
+        assert_eq!(context.variables.get("after_8"), Some(&1));
+    }
+
+    #[test]
+    fn test_failed_actions() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [
+                {
+                    "$type": "Share.CConfigSetHollowVariable",
+                    "Key": "guarded",
+                    "Value": 1,
+                    "Predicates": [variable_equals("x", 1)],
+                },
+                set_variable("after", 1),
+            ] },
+        }));
+        let start = start(&graph);
+
+        let mut context = TestContext::default();
+        let run = run_event(
+            &graph,
+            start.action_id(0),
+            Vec::new(),
+            &[start.action_id(0), start.action_id(1)],
+            &mut context,
+        );
+
+        assert_eq!(run.action_move_path, [start.action_id(1), -1]);
+        assert_eq!(run.failed_actions, HashSet::from([start.action_id(0)]));
+        assert_eq!(context.variables.get("guarded"), None);
+        assert_eq!(context.variables.get("after"), Some(&1));
+    }
+
+    #[test]
+    fn test_waits_for_unreported_actions() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [set_variable("a", 1), set_variable("b", 1)] },
+        }));
+        let start = start(&graph);
+
+        let mut context = TestContext::default();
+        let run = run_event(
+            &graph,
+            start.action_id(0),
+            Vec::new(),
+            &[start.action_id(0)],
+            &mut context,
+        );
+
+        assert_eq!(
+            run.action_move_path,
+            [start.action_id(0), start.action_id(1)]
+        );
+        assert!(!run.finished);
+        assert_eq!(context.variables.get("a"), Some(&1));
+        assert_eq!(context.variables.get("b"), None);
+    }
+
+    #[test]
+    fn test_max_steps() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [{ "$type": "Share.CConfigJump", "Jump": "OnStart" }] },
+        }));
+
+        let run = run_server_event(&graph, start(&graph), &mut TestContext::default());
+
+        assert!(!run.finished);
+        assert_eq!(run.action_move_path.len(), MAX_STEPS);
+    }
+
+    #[test]
+    fn test_event_state() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [{
+                "$type": "Share.CConfigIf",
+                "OnSuccess": "Done",
+                "OnFailure": "Pending",
+                "Conditions": [{
+                    "$type": "Share.CConfigEventByEventState",
+                    "X": 0,
+                    "Y": 0,
+                    "Position": "Absolute",
+                    "EventID": [100, 200],
+                    "State": ["Finished"],
+                    "Count": 2,
+                    "CompareType": "Equal",
+                }],
+            }] },
+            "Done": { "Actions": [set_variable("done", 1)] },
+            "Pending": { "Actions": [set_variable("done", 0)] },
+        }));
+
+        for (state, done) in [(NodeState::Finished, 1), (NodeState::Unlocked, 0)] {
+            let mut context = TestContext {
+                grids: vec![(100, NodeState::Finished), (200, state)],
+                ..Default::default()
+            };
+            run_server_event(&graph, start(&graph), &mut context);
+
+            assert_eq!(context.variables.get("done"), Some(&done));
+        }
+    }
+
+    #[test]
+    fn test_unsupported_predicates_fail() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [
+                {
+                    "$type": "Share.CConfigSetHollowVariable",
+                    "Key": "reward",
+                    "Value": 1,
+                    "Predicates": [{ "$type": "Share.CConfigEventByAvatarID", "AvatarID": 1011 }],
+                },
+            ] },
+        }));
+
+        let mut context = TestContext::default();
+        let run = run_server_event(&graph, start(&graph), &mut context);
+
+        assert_eq!(
+            run.failed_actions,
+            HashSet::from([start(&graph).action_id(0)])
+        );
+        assert_eq!(context.variables.get("reward"), None);
+    }
+
+    #[test]
+    fn test_resume_battle() {
+        let graph = graph(json!({
+            "OnStart": { "Actions": [
+                { "$type": "Share.CConfigTriggerBattle", "BattleID": 1, "OnSuccess": "Won" },
+                set_variable("skipped", 1),
+            ] },
+            "Won": { "Actions": [set_variable("won", 1)] },
+        }));
+        let (start, won) = (start(&graph), named(&graph, "Won"));
+
+        let mut context = TestContext::default();
+        let run = resume_battle(&graph, vec![start.action_id(0)], &mut context);
+
+        assert_eq!(run.action_move_path, [start.action_id(0), won.action_id(0)]);
+        assert!(!run.finished);
+        assert_eq!(context.variables.get("skipped"), None);
+    }
+}
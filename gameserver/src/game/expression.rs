@@ -0,0 +1,257 @@
+use anyhow::{bail, Result};
+
+/// Resolves the functions used in event graph expressions, like `specials(name)`.
+pub trait ExpressionContext {
+    fn call(&mut self, function: &str, args: &[String]) -> Result<f64>;
+}
+
+/// Evaluates expressions such as `6-specials(len1)` or `property(HpMax_Battle)*0.01`.
+pub fn evaluate(expression: &str, context: &mut impl ExpressionContext) -> Result<f64> {
+    let mut parser = Parser {
+        input: expression,
+        pos: 0,
+        context,
+    };
+
+    let value = parser.expression()?;
+    if parser.peek().is_some() {
+        bail!("unexpected input at {} in `{expression}`", parser.pos);
+    }
+
+    Ok(value)
+}
+
+/// Substitutes the `{expression}` parts of a name, as in `Coord.100{specials(x)}`.
+pub fn interpolate(name: &str, context: &mut impl ExpressionContext) -> Result<String> {
+    let mut result = String::new();
+    let mut rest = name;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = closing_brace(&rest[start + 1..]) else {
+            bail!("unclosed `{{` in `{name}`");
+        };
+
+        let value = evaluate(&rest[start + 1..start + 1 + len], context)?;
+        result.push_str(&rest[..start]);
+        result.push_str(&(value as i64).to_string());
+        rest = &rest[start + len + 2..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+fn closing_brace(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+struct Parser<'a, C> {
+    input: &'a str,
+    pos: usize,
+    context: &'a mut C,
+}
+
+impl<C: ExpressionContext> Parser<'_, C> {
+    fn peek(&mut self) -> Option<u8> {
+        while self.input.as_bytes().get(self.pos) == Some(&b' ') {
+            self.pos += 1;
+        }
+
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &str {
+        let start = self.pos;
+        while self.input.as_bytes().get(self.pos).is_some_and(|c| f(*c)) {
+            self.pos += 1;
+        }
+
+        &self.input[start..self.pos]
+    }
+
+    fn expression(&mut self) -> Result<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat(b'+') {
+                value += self.term()?;
+            } else if self.eat(b'-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat(b'*') {
+                value *= self.unary()?;
+            } else if self.eat(b'/') {
+                value /= self.unary()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64> {
+        if self.eat(b'-') {
+            return Ok(-self.unary()?);
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<f64> {
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.expression()?;
+                if !self.eat(b')') {
+                    bail!("missing `)` in `{}`", self.input);
+                }
+
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == b'.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == b'.');
+                Ok(number.parse()?)
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == b'_' => {
+                let function = self
+                    .take_while(|c| c.is_ascii_alphanumeric() || c == b'_')
+                    .to_string();
+                if !self.eat(b'(') {
+                    bail!("expected `(` after `{function}` in `{}`", self.input);
+                }
+
+                let args = self.args()?;
+                self.context.call(&function, &args)
+            }
+            _ => bail!("unexpected input at {} in `{}`", self.pos, self.input),
+        }
+    }
+
+    // Arguments are names or nested expressions, the function decides how to read them.
+    fn args(&mut self) -> Result<Vec<String>> {
+        let mut args = Vec::new();
+        let mut depth = 0;
+        let mut start = self.pos;
+
+        while let Some(&c) = self.input.as_bytes().get(self.pos) {
+            self.pos += 1;
+            match c {
+                b'(' | b'{' => depth += 1,
+                b')' if depth == 0 => {
+                    let arg = self.input[start..self.pos - 1].trim();
+                    if !arg.is_empty() || !args.is_empty() {
+                        args.push(interpolate(arg, self.context)?);
+                    }
+
+                    return Ok(args);
+                }
+                b')' | b'}' => depth -= 1,
+                b',' if depth == 0 => {
+                    args.push(interpolate(
+                        self.input[start..self.pos - 1].trim(),
+                        self.context,
+                    )?);
+                    start = self.pos;
+                }
+                _ => (),
+            }
+        }
+
+        bail!("missing `)` in `{}`", self.input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    struct Specials(HashMap<&'static str, f64>);
+
+    impl ExpressionContext for Specials {
+        fn call(&mut self, function: &str, args: &[String]) -> Result<f64> {
+            match (function, args) {
+                ("specials", [name]) => Ok(self.0.get(name.as_str()).copied().unwrap_or_default()),
+                _ => bail!("unknown function {function}"),
+            }
+        }
+    }
+
+    fn specials(values: &[(&'static str, f64)]) -> Specials {
+        Specials(values.iter().copied().collect())
+    }
+
+    #[test]
+    fn test_precedence() {
+        let mut context = specials(&[("len1", 2.0)]);
+        assert_eq!(evaluate("1+2*3", &mut context).unwrap(), 7.0);
+        assert_eq!(evaluate("(1+2)*3", &mut context).unwrap(), 9.0);
+        assert_eq!(evaluate("10-4-3", &mut context).unwrap(), 3.0);
+        assert_eq!(evaluate("8/2*2", &mut context).unwrap(), 8.0);
+        assert_eq!(evaluate("6-specials(len1)*2", &mut context).unwrap(), 2.0);
+        assert_eq!(evaluate(" 1 + 0.5 ", &mut context).unwrap(), 1.5);
+    }
+
+    #[test]
+    fn test_unary_minus() {
+        let mut context = specials(&[("x", 4.0)]);
+        assert_eq!(evaluate("-3+5", &mut context).unwrap(), 2.0);
+        assert_eq!(evaluate("2*-3", &mut context).unwrap(), -6.0);
+        assert_eq!(evaluate("--2", &mut context).unwrap(), 2.0);
+        assert_eq!(evaluate("-(1+2)", &mut context).unwrap(), -3.0);
+        assert_eq!(evaluate("1-specials(x)", &mut context).unwrap(), -3.0);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let mut context = specials(&[("x", 7.0), ("index", 1.0), ("pos1", 5.0)]);
+        assert_eq!(
+            interpolate("Coord.100{specials(x)}", &mut context).unwrap(),
+            "Coord.1007"
+        );
+        assert_eq!(
+            interpolate("Grid{specials(pos{specials(index)})}_{1+1}", &mut context).unwrap(),
+            "Grid5_2"
+        );
+        assert_eq!(
+            evaluate("specials(pos{specials(index)})+1", &mut context).unwrap(),
+            6.0
+        );
+        assert!(interpolate("Coord{specials(x)", &mut context).is_err());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut context = specials(&[]);
+        assert!(evaluate("1 2", &mut context).is_err());
+        assert!(evaluate("(1+2", &mut context).is_err());
+        assert!(evaluate("specials", &mut context).is_err());
+        assert!(evaluate("unknown(x)", &mut context).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use protocol::{ErrorCode, RamenData};
+
+use crate::data::{self, HollowItemTemplate};
+
+pub fn template(item_id: i32) -> Option<&'static HollowItemTemplate> {
+    data::iter_hollow_item_collection().find(|item| item.id == item_id)
+}
+
+pub fn is_unlocked(ramen_data: &RamenData, item_id: i32) -> bool {
+    ramen_data
+        .unlock_initiative_item
+        .as_ref()
+        .is_some_and(|items| items.iter().any(|id| *id == item_id))
+}
+
+/// Whether an initiative item can be made: its group has to be unlocked by finding one in a
+/// hollow, and the item a level below made first.
+pub fn check_make(ramen_data: &RamenData, item_id: i32) -> Result<(), ErrorCode> {
+    let Some(item) = template(item_id) else {
+        return Err(ErrorCode::InvalidTemplateID);
+    };
+    if is_unlocked(ramen_data, item_id) {
+        return Err(ErrorCode::AlreadyGet);
+    }
+
+    if !ramen_data
+        .unlock_initiative_item_group
+        .as_ref()
+        .is_some_and(|groups| groups.iter().any(|group| *group == item.group))
+    {
+        return Err(ErrorCode::InitiativeItemUnlock);
+    }
+
+    let prev_level = data::iter_hollow_item_collection()
+        .find(|prev| prev.group == item.group && prev.level == item.level - 1);
+    if prev_level.is_some_and(|prev| !is_unlocked(ramen_data, prev.id)) {
+        return Err(ErrorCode::InitiativeItemLevel);
+    }
+
+    Ok(())
+}
+
+/// Finding a hollow item unlocks making the initiative items of its group.
+pub fn unlock_group(ramen_data: &mut RamenData, item_id: i32) {
+    let (Some(item), Some(groups)) = (
+        template(item_id),
+        ramen_data.unlock_initiative_item_group.as_mut(),
+    ) else {
+        return;
+    };
+
+    if !groups.iter().any(|group| *group == item.group) {
+        groups.insert(item.group);
+    }
+}
+
+/// Counts the times each hollow item was used.
+pub fn record_use(ramen_data: &mut RamenData, item_id: i32) {
+    let Some(history) = ramen_data.hollow_item_history.as_mut() else {
+        return;
+    };
+
+    let used = history.get(&item_id).copied().unwrap_or_default();
+    history.insert(item_id, used + 1);
+}
@@ -0,0 +1,256 @@
+use protocol::{
+    AvatarPropertyChgInHollow, AvatarUnitInfo, DungeonInfo, ItemInfo, PlayerInfo, PropertyType,
+    PtcHpOrStressChangedArg, ScenePropertyType,
+};
+use qwer::{pdkhashmap, phashmap, PropertyDoubleKeyHashMap, PropertyHashMap};
+
+use crate::data;
+
+// Property changes of the hollow are kept per layer, there's only the first one for now.
+const HOLLOW_LAYER: i32 = 1;
+
+pub fn scene_property(name: &str) -> Option<ScenePropertyType> {
+    Some(match name {
+        "Stamina" => ScenePropertyType::Stamina,
+        "Stamina_Max" | "StaminaMax" => ScenePropertyType::StaminaMax,
+        "Stamina_Increase" | "StaminaIncrease" => ScenePropertyType::StaminaIncrease,
+        "Ban_Character1" => ScenePropertyType::BanCharacter1,
+        "Ban_Character2" => ScenePropertyType::BanCharacter2,
+        "Ban_Character3" => ScenePropertyType::BanCharacter3,
+        _ => return None,
+    })
+}
+
+/// `TeamCharacter1` is the first slot of the team.
+pub fn team_slot(name: &str) -> Option<i8> {
+    let slot = name.strip_prefix("TeamCharacter")?.parse::<i8>().ok()?;
+    slot.checked_sub(1).filter(|slot| *slot >= 0)
+}
+
+/// The max HP an avatar starts a hollow with.
+pub fn base_hp(avatar_id: i32) -> Option<i32> {
+    data::iter_avatar_config_collection()
+        .find(|config| config.id == avatar_id)
+        .map(|config| config.hp)
+}
+
+/// HP of the avatars in the hollow's team and the team's stress, which is the hollow scene's
+/// `Stamina`.
+pub struct Party<'a> {
+    dungeon: &'a mut DungeonInfo,
+    items: &'a PropertyHashMap<u64, ItemInfo>,
+    properties: &'a mut PropertyDoubleKeyHashMap<u64, u16, i32>,
+    scene_properties: &'a mut PropertyDoubleKeyHashMap<u64, u16, i32>,
+}
+
+impl<'a> Party<'a> {
+    /// The team of the dungeon the player is in, from the hollow or one of its battles.
+    pub fn of(player: &'a mut PlayerInfo) -> Option<Self> {
+        let scene_uid = player.scene_uid?;
+        let dungeon_collection = player.dungeon_collection.as_mut()?;
+        let dungeon_uid = dungeon_collection
+            .scenes
+            .as_ref()?
+            .get(&scene_uid)?
+            .get_dungeon_uid();
+
+        Some(Self {
+            dungeon: dungeon_collection
+                .dungeons
+                .as_mut()?
+                .get_mut(&dungeon_uid)?,
+            items: player.items.as_ref()?,
+            properties: player.properties.get_or_insert_with(|| pdkhashmap![]),
+            scene_properties: player.scene_properties.get_or_insert_with(|| pdkhashmap![]),
+        })
+    }
+
+    /// Team slots from the first one, with their avatar's uid.
+    pub fn members(&self) -> Vec<(i8, u64)> {
+        let mut members = self
+            .dungeon
+            .avatar_map
+            .iter()
+            .map(|(slot, unit)| (*slot, unit.uid))
+            .collect::<Vec<_>>();
+        members.sort_unstable();
+
+        members
+    }
+
+    pub fn slot_of(&self, avatar_uid: u64) -> Option<i8> {
+        self.members()
+            .into_iter()
+            .find(|(_, uid)| *uid == avatar_uid)
+            .map(|(slot, _)| slot)
+    }
+
+    /// Current and max HP of the avatar in `slot`.
+    pub fn hp(&self, slot: i8) -> Option<(i32, i32)> {
+        let unit = self.dungeon.avatar_map.get(&slot)?;
+        let hp = self
+            .properties
+            .get(&unit.properties_uid, &(PropertyType::Hp as u16))?;
+        let hp_max = self
+            .properties
+            .get(&unit.properties_uid, &(PropertyType::HpMax as u16))?;
+
+        Some((*hp, *hp_max))
+    }
+
+    pub fn is_alive(&self, slot: i8) -> Option<bool> {
+        self.hp(slot).map(|(hp, _)| hp > 0)
+    }
+
+    pub fn is_wiped(&self) -> bool {
+        let members = self.members();
+        !members.is_empty()
+            && members
+                .iter()
+                .all(|(slot, _)| self.is_alive(*slot) == Some(false))
+    }
+
+    /// Changes the HP of the avatar in `slot` within its max, dead avatars aren't healed.
+    pub fn change_hp(&mut self, slot: i8, delta: i32) -> Option<PtcHpOrStressChangedArg> {
+        let (hp, hp_max) = self.hp(slot)?;
+        if hp <= 0 {
+            return None;
+        }
+
+        let delta = (hp + delta).clamp(0, hp_max) - hp;
+        if delta == 0 {
+            return None;
+        }
+
+        let unit = self.dungeon.avatar_map.get_mut(&slot)?;
+        self.properties
+            .insert(unit.properties_uid, PropertyType::Hp as u16, hp + delta);
+
+        let mut layer = unit
+            .layer_property_change
+            .get(&HOLLOW_LAYER)
+            .cloned()
+            .unwrap_or(AvatarPropertyChgInHollow {
+                hp_lost: 0,
+                hp_add: 0,
+            });
+        if delta < 0 {
+            unit.hp_lost_hollow -= delta;
+            layer.hp_lost -= delta;
+        } else {
+            unit.hp_add_hollow += delta;
+            layer.hp_add += delta;
+        }
+        unit.layer_property_change.insert(HOLLOW_LAYER, layer);
+
+        Some(PtcHpOrStressChangedArg {
+            avatar_uid: unit.uid,
+            hp: hp + delta,
+            hp_changed: delta,
+            stress: self.stress().0,
+            stress_changed: 0,
+        })
+    }
+
+    /// Current and max stress of the team.
+    pub fn stress(&self) -> (i32, i32) {
+        (
+            self.scene_property(ScenePropertyType::Stamina),
+            self.scene_property(ScenePropertyType::StaminaMax),
+        )
+    }
+
+    pub fn change_stress(&mut self, delta: i32) -> Option<PtcHpOrStressChangedArg> {
+        let (stress, stress_max) = self.stress();
+        let delta = (stress + delta).clamp(0, stress_max) - stress;
+        if delta == 0 {
+            return None;
+        }
+
+        self.set_scene_property(ScenePropertyType::Stamina, stress + delta);
+        Some(PtcHpOrStressChangedArg {
+            avatar_uid: 0,
+            hp: 0,
+            hp_changed: 0,
+            stress: stress + delta,
+            stress_changed: delta,
+        })
+    }
+
+    pub fn scene_property(&self, property: ScenePropertyType) -> i32 {
+        self.scene_properties
+            .get(&self.dungeon.scene_properties_uid, &(property as u16))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_scene_property(&mut self, property: ScenePropertyType, value: i32) {
+        self.scene_properties
+            .insert(self.dungeon.scene_properties_uid, property as u16, value);
+    }
+
+    /// Puts a trial avatar in `slot`, it takes over the HP ratio of the avatar it replaces.
+    pub fn replace(&mut self, slot: i8, avatar_id: i32) -> Option<()> {
+        let (hp, hp_max) = self.hp(slot)?;
+        let new_max = base_hp(avatar_id).unwrap_or(hp_max);
+        let new_hp = if hp_max > 0 {
+            hp * new_max / hp_max
+        } else {
+            new_max
+        };
+
+        self.insert_trial(slot, avatar_id, new_hp, new_max);
+        Some(())
+    }
+
+    /// Adds a trial avatar after the last slot of the team.
+    pub fn add(&mut self, avatar_id: i32) {
+        let members = self.members();
+        let slot = members.last().map_or(0, |(slot, _)| slot + 1);
+        let hp_max = base_hp(avatar_id)
+            .or_else(|| {
+                members
+                    .first()
+                    .and_then(|(slot, _)| self.hp(*slot))
+                    .map(|(_, max)| max)
+            })
+            .unwrap_or_default();
+
+        self.insert_trial(slot, avatar_id, hp_max, hp_max);
+    }
+
+    /// The slot of the avatar with `avatar_id`, owned or trial.
+    pub fn slot_of_avatar(&self, avatar_id: i32) -> Option<i8> {
+        self.members()
+            .into_iter()
+            .find(|(_, uid)| match self.items.get(uid) {
+                Some(ItemInfo::Avatar { id, .. }) => *id == avatar_id,
+                _ => *uid == avatar_id as u64,
+            })
+            .map(|(slot, _)| slot)
+    }
+
+    // Trial avatars aren't items of the player, they go by their id.
+    fn insert_trial(&mut self, slot: i8, avatar_id: i32, hp: i32, hp_max: i32) {
+        let uid = avatar_id as u64;
+        self.properties.insert(uid, PropertyType::Hp as u16, hp);
+        self.properties
+            .insert(uid, PropertyType::HpMax as u16, hp_max);
+        self.dungeon.avatar_map.insert(
+            slot,
+            AvatarUnitInfo {
+                uid,
+                properties_uid: uid,
+                is_banned: false,
+                modified_property: pdkhashmap![],
+                hp_lost_hollow: 0,
+                hp_add_hollow: 0,
+                layer_property_change: phashmap![],
+            },
+        );
+    }
+
+    pub fn avatar_map(&self) -> PropertyHashMap<i8, AvatarUnitInfo> {
+        self.dungeon.avatar_map.clone()
+    }
+}
@@ -0,0 +1,34 @@
+use protocol::{HollowShopCurrency, HollowShopType};
+
+use crate::data;
+
+/// Gear coins, the currency of a hollow run.
+pub const GEAR_COIN_ID: i32 = 1;
+
+pub fn shop_type(name: &str) -> Option<HollowShopType> {
+    Some(match name {
+        "Item" => HollowShopType::Item,
+        "Card" => HollowShopType::Card,
+        "Curse" => HollowShopType::Curse,
+        "HollowItem" => HollowShopType::HollowItem,
+        "Discount" => HollowShopType::Discount,
+        "Gachashop" => HollowShopType::Gachashop,
+        _ => return None,
+    })
+}
+
+pub fn currency(name: &str) -> Option<HollowShopCurrency> {
+    Some(match name {
+        "Coin" => HollowShopCurrency::Coin,
+        "Curse" => HollowShopCurrency::Curse,
+        "Random" => HollowShopCurrency::Random,
+        _ => return None,
+    })
+}
+
+/// The price of goods of the given rarity in `shop` shops, items without a rarity use 0.
+pub fn base_price(shop: &HollowShopType, rare: i32) -> Option<i32> {
+    data::iter_hollow_shop_price_collection()
+        .find(|entry| shop_type(&entry.shop_type).as_ref() == Some(shop) && entry.rare == rare)
+        .map(|entry| entry.price)
+}
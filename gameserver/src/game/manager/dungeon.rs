@@ -1,9 +1,15 @@
 use anyhow::{anyhow, bail, Result};
 use parking_lot::RwLock;
 use protocol::*;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::game::{manager::UniqueIDManager, util, PlayerOperationResult};
+use crate::game::{
+    hollow_item,
+    hollow_party::{self, Party},
+    manager::UniqueIDManager,
+    util,
+};
 use qwer::{
     pdkhashmap, phashmap, phashset, PropertyDoubleKeyHashMap, PropertyHashMap, PropertyHashSet,
 };
@@ -23,7 +29,7 @@ impl DungeonManager {
         }
     }
 
-    pub fn enter_main_city(&self) -> Result<PlayerOperationResult<PtcEnterSceneArg>> {
+    pub fn enter_main_city(&self) -> Result<PtcEnterSceneArg> {
         let (player_uid, scene_position, scene_rotation) = {
             let player = self.player.read();
             let pos_in_main_city = player.pos_in_main_city.as_ref().unwrap();
@@ -89,37 +95,13 @@ impl DungeonManager {
             },
         };
 
-        let add_dungeon = dungeon_info.clone();
-        let add_scene = scene_info.clone();
-        let cur_scene_uid = scene_info.get_uid();
-
-        Ok(PlayerOperationResult::with_changes(
-            ptc_enter_scene,
-            PlayerInfo {
-                dungeon_collection: Some(DungeonCollection {
-                    dungeons: Some(PropertyHashMap::Modify {
-                        to_add: vec![(add_dungeon.uid, add_dungeon)],
-                        to_remove: Vec::new(),
-                    }),
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![(add_scene.get_uid(), add_scene)],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                scene_uid: Some(cur_scene_uid),
-                ..Default::default()
-            },
-        ))
+        drop(player);
+        Ok(ptc_enter_scene)
     }
 
-    pub fn enter_scene_section(
-        &self,
-        scene_uid: u64,
-        section_id: i32,
-    ) -> PlayerOperationResult<PtcEnterSectionArg> {
-        let mut player = self.player.write();
-        let scene_info = player
+    pub fn enter_scene_section(&self, scene_uid: u64, section_id: i32) -> PtcEnterSectionArg {
+        self.player
+            .write()
             .dungeon_collection
             .as_mut()
             .unwrap()
@@ -127,25 +109,13 @@ impl DungeonManager {
             .as_mut()
             .unwrap()
             .get_mut(&scene_uid)
-            .unwrap();
-        scene_info.set_section_id(section_id);
-
-        PlayerOperationResult::with_changes(
-            PtcEnterSectionArg { section_id },
-            PlayerInfo {
-                dungeon_collection: Some(DungeonCollection {
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![(scene_uid, scene_info.clone())],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-        )
+            .unwrap()
+            .set_section_id(section_id);
+
+        PtcEnterSectionArg { section_id }
     }
 
-    pub fn enter_scene(&self, scene_uid: u64) -> Result<PlayerOperationResult<PtcEnterSceneArg>> {
+    pub fn enter_scene(&self, scene_uid: u64) -> Result<PtcEnterSceneArg> {
         let (player_uid, prev_scene_uid) = {
             let player = self.player.read();
 
@@ -224,28 +194,11 @@ impl DungeonManager {
             },
         };
 
-        Ok(PlayerOperationResult::with_changes(
-            ptc_enter_scene,
-            PlayerInfo {
-                dungeon_collection: Some(DungeonCollection {
-                    dungeons: Some(PropertyHashMap::Modify {
-                        to_add: vec![(dungeon_info.uid, dungeon_info.clone())],
-                        to_remove: Vec::new(),
-                    }),
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![(scene_info.get_uid(), scene_info.clone())],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                scene_uid: Some(scene_uid),
-                prev_scene_uid: Some(prev_scene_uid),
-                ..Default::default()
-            },
-        ))
+        drop(player);
+        Ok(ptc_enter_scene)
     }
 
-    pub fn hollow_finished(&self) -> PlayerOperationResult<u64> {
+    pub fn hollow_finished(&self, success: bool) -> u64 {
         let cur_scene_uid = self.get_cur_scene_uid();
 
         let mut player = self.player.write();
@@ -274,40 +227,53 @@ impl DungeonManager {
             hollow_system_ui_state.insert(HollowSystemType::Menu, HollowSystemUIState::Close);
         }
 
-        let add_scene = hollow_scene.clone();
-
-        let quest_data = player.quest_data.as_ref().unwrap();
-        let mut dungeon_quest = quest_data
-            .quests
-            .as_ref()
+        let dungeon_quest = player
+            .quest_data
+            .as_mut()
             .unwrap()
-            .get(&dungeon_uid, &1001000101)
+            .quests
+            .as_mut()
             .unwrap()
-            .clone();
-        dungeon_quest.set_progress(1);
-        dungeon_quest.set_finished_count(1);
-        dungeon_quest.set_state(QuestState::Finished);
-
-        PlayerOperationResult::with_changes(
-            cur_scene_uid,
-            PlayerInfo {
-                dungeon_collection: Some(DungeonCollection {
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![(cur_scene_uid, add_scene)],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                quest_data: Some(QuestData {
-                    quests: Some(PropertyDoubleKeyHashMap::Modify {
-                        to_add: vec![(dungeon_uid, 1001000101, dungeon_quest)],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-        )
+            .get_mut(&dungeon_uid, &1001000101)
+            .unwrap();
+        if success {
+            dungeon_quest.set_progress(1);
+            dungeon_quest.set_finished_count(1);
+            dungeon_quest.set_state(QuestState::Finished);
+        }
+
+        drop(player);
+
+        cur_scene_uid
+    }
+
+    /// Takes the team's HP at the end of a battle, along with whether nobody is left standing.
+    pub fn battle_hp(
+        &self,
+        avatar_properties: &PropertyHashMap<u64, HashMap<u16, i32>>,
+    ) -> (Vec<PtcHpOrStressChangedArg>, bool) {
+        let mut player = self.player.write();
+
+        let mut changes = Vec::new();
+        let mut party_wiped = false;
+        if let Some(mut party) = Party::of(&mut player) {
+            for (avatar_uid, properties) in avatar_properties.iter() {
+                let Some(slot) = party.slot_of(*avatar_uid) else {
+                    continue;
+                };
+                let (Some(hp), Some((cur_hp, _))) =
+                    (properties.get(&(PropertyType::Hp as u16)), party.hp(slot))
+                else {
+                    continue;
+                };
+
+                changes.extend(party.change_hp(slot, hp - cur_hp));
+            }
+            party_wiped = party.is_wiped();
+        }
+
+        drop(player);
+        (changes, party_wiped)
     }
 
     pub fn get_default_scene_uid(&self) -> u64 {
@@ -375,7 +341,7 @@ impl DungeonManager {
         }
     }
 
-    pub fn leave_battle(&self) -> Result<PlayerOperationResult<PtcEnterSceneArg>> {
+    pub fn leave_battle(&self) -> Result<PtcEnterSceneArg> {
         let back_scene_uid = self.get_back_scene_uid();
 
         {
@@ -418,41 +384,13 @@ impl DungeonManager {
         fight_scene.get_back_scene_uid()
     }
 
-    pub fn enter_battle(&self, scene_uid: u64) -> PlayerOperationResult<PtcEnterSceneArg> {
+    pub fn enter_battle(&self, scene_uid: u64) -> PtcEnterSceneArg {
         let hollow_scene_uid = *self.player.read().scene_uid.as_ref().unwrap();
-        let hollow_scene = self.set_cur_hollow_battle(scene_uid, hollow_scene_uid);
-        let ptc_enter_scene = self.enter_scene(scene_uid).unwrap().take().clone();
-
-        let player = self.player.read();
-        let dungeon_collection = player.dungeon_collection.as_ref().unwrap();
-        let fight_scene = dungeon_collection
-            .scenes
-            .as_ref()
-            .unwrap()
-            .get(&scene_uid)
-            .unwrap();
-
-        PlayerOperationResult::with_changes(
-            ptc_enter_scene,
-            PlayerInfo {
-                dungeon_collection: Some(DungeonCollection {
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![
-                            (hollow_scene_uid, hollow_scene),
-                            (scene_uid, fight_scene.clone()),
-                        ],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                scene_uid: Some(scene_uid),
-                prev_scene_uid: Some(hollow_scene_uid),
-                ..Default::default()
-            },
-        )
+        self.set_cur_hollow_battle(scene_uid, hollow_scene_uid);
+        self.enter_scene(scene_uid).unwrap()
     }
 
-    fn set_cur_hollow_battle(&self, scene_uid: u64, hollow_scene_uid: u64) -> SceneInfo {
+    fn set_cur_hollow_battle(&self, scene_uid: u64, hollow_scene_uid: u64) {
         let mut player = self.player.write();
         let hollow_scene = player
             .dungeon_collection
@@ -474,11 +412,9 @@ impl DungeonManager {
 
         *battle_scene_uid = scene_uid;
         *on_battle_success = String::from("OnEnd");
-
-        hollow_scene.clone()
     }
 
-    pub fn create_fight(&self, id: i32, hollow_scene_uid: u64) -> PlayerOperationResult<u64> {
+    pub fn create_fight(&self, id: i32, hollow_scene_uid: u64) -> u64 {
         let mut player = self.player.write();
         let dungeon_collection = player.dungeon_collection.as_mut().unwrap();
         let scenes = dungeon_collection.scenes.as_mut().unwrap();
@@ -502,20 +438,10 @@ impl DungeonManager {
             random_seed: 2281337,
         };
 
-        scenes.insert(fight_scene_uid, fight_scene.clone());
-        PlayerOperationResult::with_changes(
-            fight_scene_uid,
-            PlayerInfo {
-                dungeon_collection: Some(DungeonCollection {
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![(fight_scene_uid, fight_scene)],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-        )
+        scenes.insert(fight_scene_uid, fight_scene);
+
+        drop(player);
+        fight_scene_uid
     }
 
     #[allow(dead_code)]
@@ -541,24 +467,37 @@ impl DungeonManager {
         id: i32,
         world_quest_id: i32,
         avatar_uids: &[u64],
-    ) -> PlayerOperationResult<(u64, u64)> {
+        initiative_item: i32,
+    ) -> (u64, u64) {
         let back_scene_uid = self.get_default_scene_uid();
 
         let mut dungeon = self.create_base_dungeon(id, back_scene_uid, world_quest_id);
         dungeon.hollow_event_version = 526;
 
+        // Only initiative items the player made can be taken into the hollow.
+        if hollow_item::is_unlocked(
+            self.player.read().ramen_data.as_ref().unwrap(),
+            initiative_item,
+        ) {
+            dungeon.initiative_item = initiative_item;
+        }
+
         let scene_uid = self.uid_mgr.next();
         dungeon.default_scene_uid = scene_uid;
         dungeon.scene_properties_uid = scene_uid;
 
         self.add_default_hollow_properties(scene_uid);
 
+        let mut avatar_properties = Vec::new();
         for (index, avatar_uid) in avatar_uids.iter().enumerate() {
+            let properties_uid = self.uid_mgr.next();
+            avatar_properties.push((*avatar_uid, properties_uid));
+
             dungeon.avatar_map.insert(
                 index.try_into().unwrap(),
                 AvatarUnitInfo {
                     uid: *avatar_uid,
-                    properties_uid: self.uid_mgr.next(),
+                    properties_uid,
                     is_banned: false,
                     modified_property: pdkhashmap![],
                     hp_add_hollow: 0,
@@ -632,6 +571,8 @@ impl DungeonManager {
             hollow_system_ui_state: phashmap![],
         };
 
+        let dungeon_uid = dungeon.uid;
+
         {
             let mut player = self.player.write();
             player
@@ -644,59 +585,39 @@ impl DungeonManager {
                 .dungeons
                 .as_mut()
                 .unwrap()
-                .insert(dungeon.uid, dungeon.clone());
+                .insert(dungeon_uid, dungeon);
             dungeon_collection
                 .scenes
                 .as_mut()
                 .unwrap()
-                .insert(scene_uid, scene.clone());
-        }
-        let mut player = self.player.write();
-        let items = player.items.as_mut().unwrap();
-
-        let mut updated_items = Vec::new();
-        for avatar_uid in avatar_uids {
-            let item = items.get_mut(avatar_uid).unwrap();
-            let ItemInfo::Avatar { robot_id, .. } = item else {
-                continue;
-            };
-            *robot_id = 101000101;
-            updated_items.push((*avatar_uid, item.clone()));
-        }
+                .insert(scene_uid, scene);
+
+            let items = player.items.as_mut().unwrap();
+            for avatar_uid in avatar_uids {
+                if let Some(ItemInfo::Avatar { robot_id, .. }) = items.get_mut(avatar_uid) {
+                    *robot_id = 101000101;
+                }
+            }
 
-        let mut prop_changes = Vec::new();
-        for (key, sub_key, value) in &*self.scene_properties.write() {
-            prop_changes.push((*key, *sub_key, *value));
+            // The team enters the hollow with full HP.
+            for (avatar_uid, properties_uid) in avatar_properties {
+                let Some(ItemInfo::Avatar { id, .. }) =
+                    player.items.as_ref().unwrap().get(&avatar_uid)
+                else {
+                    continue;
+                };
+                let hp = hollow_party::base_hp(*id).unwrap_or_default();
+
+                let properties = player.properties.get_or_insert_with(|| pdkhashmap![]);
+                properties.insert(properties_uid, PropertyType::Hp as u16, hp);
+                properties.insert(properties_uid, PropertyType::HpMax as u16, hp);
+            }
         }
 
-        PlayerOperationResult::with_changes(
-            (dungeon.uid, scene_uid),
-            PlayerInfo {
-                items: Some(PropertyHashMap::Modify {
-                    to_add: updated_items,
-                    to_remove: vec![],
-                }),
-                dungeon_collection: Some(DungeonCollection {
-                    dungeons: Some(PropertyHashMap::Modify {
-                        to_add: vec![(dungeon.uid, dungeon)],
-                        to_remove: Vec::new(),
-                    }),
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![(scene_uid, scene)],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                scene_properties: Some(PropertyDoubleKeyHashMap::Modify {
-                    to_add: prop_changes,
-                    to_remove: Vec::new(),
-                }),
-                ..Default::default()
-            },
-        )
+        (dungeon_uid, scene_uid)
     }
 
-    pub fn create_hall(&self, id: i32) -> PlayerOperationResult<u64> {
+    pub fn create_hall(&self, id: i32) -> u64 {
         let mut dungeon = self.create_base_dungeon(id, 0, 0);
         let dungeon_uid = dungeon.uid;
 
@@ -724,36 +645,20 @@ impl DungeonManager {
             .dungeons
             .as_mut()
             .unwrap()
-            .insert(dungeon_uid, dungeon.clone());
+            .insert(dungeon_uid, dungeon);
 
         dungeon_collection
             .scenes
             .as_mut()
             .unwrap()
-            .insert(scene_uid, hall_scene_info.clone());
+            .insert(scene_uid, hall_scene_info);
 
         dungeon_collection.default_scene_uid.replace(scene_uid);
-        PlayerOperationResult::with_changes(
-            scene_uid,
-            PlayerInfo {
-                dungeon_collection: Some(DungeonCollection {
-                    dungeons: Some(PropertyHashMap::Modify {
-                        to_add: vec![(dungeon_uid, dungeon)],
-                        to_remove: vec![],
-                    }),
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![(scene_uid, hall_scene_info)],
-                        to_remove: vec![],
-                    }),
-                    default_scene_uid: Some(scene_uid),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-        )
+        drop(player);
+        scene_uid
     }
 
-    pub fn create_fresh(&self) -> PlayerOperationResult<u64> {
+    pub fn create_fresh(&self) -> u64 {
         let mut dungeon = self.create_base_dungeon(2, 0, 0);
         let dungeon_uid = dungeon.uid;
 
@@ -781,31 +686,16 @@ impl DungeonManager {
             .dungeons
             .as_mut()
             .unwrap()
-            .insert(dungeon_uid, dungeon.clone());
+            .insert(dungeon_uid, dungeon);
 
         dungeon_collection
             .scenes
             .as_mut()
             .unwrap()
-            .insert(scene_uid, fresh_scene_info.clone());
+            .insert(scene_uid, fresh_scene_info);
 
-        PlayerOperationResult::with_changes(
-            scene_uid,
-            PlayerInfo {
-                dungeon_collection: Some(DungeonCollection {
-                    dungeons: Some(PropertyHashMap::Modify {
-                        to_add: vec![(dungeon_uid, dungeon)],
-                        to_remove: vec![],
-                    }),
-                    scenes: Some(PropertyHashMap::Modify {
-                        to_add: vec![(scene_uid, fresh_scene_info)],
-                        to_remove: vec![],
-                    }),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-        )
+        drop(player);
+        scene_uid
     }
 
     fn create_base_dungeon(
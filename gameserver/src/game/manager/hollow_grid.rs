@@ -1,18 +1,159 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     sync::Arc,
 };
 
+use anyhow::{anyhow, bail, Result};
 use parking_lot::RwLock;
 use protocol::*;
 use qwer::{phashmap, phashset, PropertyHashMap, PropertyHashSet};
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::{Rng, SeedableRng};
 
-use crate::data::{self, ConfigAction, ConfigEventType, ConfigValue};
+use crate::data::{
+    self, ConfigAction, ConfigChoice, ConfigEventGraph, ConfigEventType, ConfigModifyType,
+    ConfigShopSlot, ConfigValue, HollowItemTemplate, HollowPositionOffsetType,
+};
+use crate::game::event_runner::{self, EventContext, EventRun, GridFilter};
+use crate::game::expression::{self, ExpressionContext};
+use crate::game::hollow_party::{self, Party};
+use crate::game::{drop_pool, hollow_item, hollow_shop};
+
+// Hollow items run their graph outside of the map, on a uid no grid has.
+const HOLLOW_ITEM_EVENT_GRAPH_UID: u64 = u16::MAX as u64;
+
+pub struct EventGraphRun {
+    // The event that ran, and the graphs whose specials the events the server fires changed.
+    pub sync_events: Vec<PtcSyncHollowEventInfoArg>,
+    pub grids: PtcHollowGridArg,
+    pub trigger_battle_id: Option<i32>,
+    pub hollow_finished: bool,
+    pub disabled_cards: Vec<u64>,
+    pub card_genre_tips: Vec<i32>,
+    pub hp_or_stress_changes: Vec<PtcHpOrStressChangedArg>,
+    pub avatar_map: Option<PropertyHashMap<i8, AvatarUnitInfo>>,
+    pub party_wiped: bool,
+}
+
+impl EventGraphRun {
+    fn new(player_uid: u64, scene_uid: u64) -> Self {
+        Self {
+            sync_events: Vec::new(),
+            grids: PtcHollowGridArg {
+                player_uid,
+                is_partial: true,
+                scene_uid,
+                hollow_level: 1,
+                grids: HashMap::new(),
+            },
+            trigger_battle_id: None,
+            hollow_finished: false,
+            disabled_cards: Vec::new(),
+            card_genre_tips: Vec::new(),
+            hp_or_stress_changes: Vec::new(),
+            avatar_map: None,
+            party_wiped: false,
+        }
+    }
+
+    // Takes over what an event did to the run besides moving through its graph.
+    fn merge(&mut self, context: HollowEventContext) {
+        self.grids.grids.extend(context.updated_grids);
+        self.trigger_battle_id = self.trigger_battle_id.or(context.trigger_battle_id);
+        self.hollow_finished |= context.hollow_finished;
+        self.disabled_cards.extend(context.disabled_cards);
+        self.card_genre_tips.extend(context.card_genre_tips);
+        self.hp_or_stress_changes
+            .extend(context.hp_or_stress_changes);
+        if context.avatar_map.is_some() {
+            self.avatar_map = context.avatar_map;
+        }
+        self.party_wiped |= context.party_wiped;
+    }
+}
 
 pub struct HollowGridManager {
     player: Arc<RwLock<PlayerInfo>>,
     map: RwLock<Option<HollowGridMapProtocolInfo>>,
     events: RwLock<HashMap<u64, EventInfo>>,
+    specials: RwLock<HashMap<u64, EventSpecials>>,
+    run: RwLock<HollowRun>,
+}
+
+// State of the current hollow run, reset along with the map.
+struct HollowRun {
+    variables: HashMap<String, i32>,
+    inventory: HashMap<DungeonContentDropPoolType, HashMap<i32, i32>>,
+    locked_curses: HashSet<i32>,
+    // Event graphs whose finished event ended with `ConfigFinishEvent { can_trigger_again }`.
+    retriggerable: HashSet<u64>,
+    coins: i32,
+    // EP the hollow item in the slot is charged with, using it takes its whole cost.
+    item_charge: i32,
+    // The graph of the hollow item used last.
+    item_graph: Option<i32>,
+    // The event graph waiting on the battle it triggered.
+    battle_graph: Option<u64>,
+    rng: StdRng,
+}
+
+impl HollowRun {
+    // Items already held aren't drawn again, except for rewards.
+    fn draw(
+        &mut self,
+        pool: &DungeonContentDropPoolType,
+        rare: &[i32],
+        genre: &[i32],
+        drawn: &[i32],
+    ) -> Result<i32> {
+        let mut exclude = drawn.to_vec();
+        if *pool != DungeonContentDropPoolType::Reward {
+            exclude.extend(self.inventory.get(pool).into_iter().flat_map(HashMap::keys));
+        }
+
+        drop_pool::draw(pool, rare, genre, &exclude, &mut self.rng)
+            .ok_or_else(|| anyhow!("nothing left to draw from the {pool:?} pool"))
+    }
+
+    fn new(rng: StdRng) -> Self {
+        Self {
+            variables: HashMap::new(),
+            inventory: HashMap::new(),
+            locked_curses: HashSet::new(),
+            retriggerable: HashSet::new(),
+            coins: 0,
+            item_charge: 0,
+            item_graph: None,
+            battle_graph: None,
+            rng,
+        }
+    }
+}
+
+// Specials of one event graph on the map, seeded from the graph's `Specials` block.
+#[derive(Default)]
+struct EventSpecials {
+    values: HashMap<String, i32>,
+    lists: HashMap<String, Vec<i32>>,
+}
+
+impl EventSpecials {
+    fn seeded(graph: &ConfigEventGraph) -> Self {
+        Self {
+            values: graph
+                .specials
+                .iter()
+                .chain(&graph.event_specials)
+                .map(|(name, value)| (name.clone(), *value))
+                .collect(),
+            lists: HashMap::new(),
+        }
+    }
+
+    fn to_sync(&self) -> PropertyHashMap<String, i32> {
+        PropertyHashMap::Base(self.values.clone())
+    }
 }
 
 impl HollowGridManager {
@@ -21,18 +162,48 @@ impl HollowGridManager {
             player,
             map: RwLock::new(None),
             events: RwLock::new(HashMap::new()),
+            specials: RwLock::new(HashMap::new()),
+            run: RwLock::new(HollowRun::new(StdRng::from_entropy())),
         }
     }
 
+    /// Takes the rewards dropped during the hollow, to be granted to the player at its end.
+    pub fn take_rewards(&self) -> Vec<(i32, i32)> {
+        let mut rewards = self
+            .run
+            .write()
+            .inventory
+            .remove(&DungeonContentDropPoolType::Reward)
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        rewards.sort_unstable();
+
+        rewards
+    }
+
+    fn sync_specials(
+        &self,
+        event_graph_uid: u64,
+        template_id: i32,
+    ) -> PropertyHashMap<String, i32> {
+        let Some(graph) = data::get_event_graph(template_id) else {
+            return phashmap![];
+        };
+
+        self.specials
+            .write()
+            .entry(event_graph_uid)
+            .or_insert_with(|| EventSpecials::seeded(graph))
+            .to_sync()
+    }
+
     pub fn get_cur_position_in_hollow(&self) -> u16 {
         self.map.read().as_ref().unwrap().start_grid
     }
 
-    pub fn move_to(
-        &self,
-        destination_grid: u16,
-        scene_uid: u64,
-    ) -> (PtcHollowGridArg, Option<PtcSyncHollowEventInfoArg>) {
+    /// Moves the player onto `destination_grid`, which fires the events every turn runs.
+    pub fn move_to(&self, destination_grid: u16, scene_uid: u64) -> EventGraphRun {
         let mut map = self.map.write();
         let map = map.as_mut().unwrap();
 
@@ -41,33 +212,33 @@ impl HollowGridManager {
 
         self.update_position_to_scene(scene_uid, destination_grid);
 
+        let mut run = EventGraphRun::new(self.player.read().uid.unwrap(), scene_uid);
         let mut events = self.events.write();
-        let sync_event_info =
-            if let Entry::Vacant(entry) = events.entry(u64::from(destination_grid)) {
-                let event_info = EventInfo {
-                    id: 1000,
-                    cur_action_id: 1001,
-                    action_move_path: vec![1001],
-                    state: EventState::WaitingClient,
-                    prev_state: EventState::Running,
-                    cur_action_info: ActionInfo::None {},
-                    cur_action_state: ActionState::Init,
-                    predicated_failed_actions: phashset![],
-                    stack_frames: Vec::new(),
-                };
+        if let Entry::Vacant(entry) = events.entry(u64::from(destination_grid)) {
+            let event_info = EventInfo {
+                id: 1000,
+                cur_action_id: 1001,
+                action_move_path: vec![1001],
+                state: EventState::WaitingClient,
+                prev_state: EventState::Running,
+                cur_action_info: ActionInfo::None {},
+                cur_action_state: ActionState::Init,
+                predicated_failed_actions: phashset![],
+                stack_frames: Vec::new(),
+            };
 
-                entry.insert(event_info.clone());
+            entry.insert(event_info.clone());
 
-                Some(PtcSyncHollowEventInfoArg {
-                    event_graph_uid: u64::from(destination_grid),
-                    hollow_event_template_id: grid.grid.event_graph_info.hollow_event_template_id,
-                    event_graph_id: grid.grid.event_graph_info.hollow_event_template_id,
-                    updated_event: event_info,
-                    specials: phashmap![],
-                })
-            } else {
-                None
-            };
+            let template_id = grid.grid.event_graph_info.hollow_event_template_id;
+            run.sync_events.push(PtcSyncHollowEventInfoArg {
+                event_graph_uid: u64::from(destination_grid),
+                hollow_event_template_id: template_id,
+                event_graph_id: template_id,
+                updated_event: event_info,
+                specials: self.sync_specials(u64::from(destination_grid), template_id),
+            });
+        }
+        drop(events);
 
         if !grid.grid.event_graph_info.finished {
             grid.grid.flag |= HollowGridFlag::Travelled as i32;
@@ -80,53 +251,42 @@ impl HollowGridManager {
             grid.grid.event_graph_info.fired_count = 2;
         }
 
-        (
-            PtcHollowGridArg {
-                player_uid: self.player.read().uid.unwrap(),
-                is_partial: true,
-                scene_uid,
-                hollow_level: 1,
-                grids: HashMap::from([(destination_grid, grid.clone())]),
-            },
-            sync_event_info,
-        )
+        run.grids.grids.insert(destination_grid, grid.clone());
+
+        // Every move advances the hollow by one turn.
+        self.fire_event(map, &ConfigEventType::OnPlayerMove, &mut run);
+        self.fire_event(map, &ConfigEventType::OnTurn, &mut run);
+
+        run
     }
 
-    pub fn battle_finished(&self) -> (PtcSyncHollowEventInfoArg, bool) {
-        let map = self.map.read();
-        let map = map.as_ref().unwrap();
-        let cur_grid = map.grids.get(&map.start_grid).unwrap();
+    /// Goes on with the event that triggered the battle the player just won.
+    pub fn battle_finished(&self) -> Result<EventGraphRun> {
+        let event_graph_uid = self
+            .run
+            .write()
+            .battle_graph
+            .take()
+            .ok_or_else(|| anyhow!("no event is waiting on a battle"))?;
 
-        let graph =
-            data::get_event_graph(cur_grid.grid.event_graph_info.hollow_event_template_id).unwrap();
-
-        let mut hollow_finished = false;
-        if let Some(event) = graph.events.get(&ConfigEventType::OnEnd) {
-            if let Some(ConfigAction::ConfigFinishHollow) = event.actions.first() {
-                hollow_finished = true;
-            }
-        }
-
-        (
-            PtcSyncHollowEventInfoArg {
-                event_graph_uid: u64::from(map.start_grid),
-                hollow_event_template_id: cur_grid.grid.event_graph_info.hollow_event_template_id,
-                event_graph_id: cur_grid.grid.event_graph_info.hollow_event_template_id,
-                updated_event: EventInfo {
-                    id: 1000,
-                    cur_action_id: 2001,
-                    action_move_path: vec![1001, 1002, 2001],
-                    state: EventState::WaitingClient,
-                    prev_state: EventState::Running,
-                    cur_action_info: ActionInfo::None {},
-                    cur_action_state: ActionState::Init,
-                    predicated_failed_actions: phashset![],
-                    stack_frames: Vec::new(),
-                },
-                specials: phashmap![],
-            },
-            hollow_finished,
-        )
+        self.run_with(event_graph_uid, |graph, info, context| {
+            let info = info?;
+            let end_hollow = matches!(
+                info.action_move_path
+                    .last()
+                    .and_then(|id| graph.action(*id))
+                    .map(|(event, index)| &event.actions[index]),
+                Some(ConfigAction::ConfigTriggerBattle {
+                    end_hollow: true,
+                    ..
+                })
+            );
+
+            let run = event_runner::resume_battle(graph, info.action_move_path.clone(), context);
+            context.hollow_finished |= end_hollow;
+            Some((info.id, run))
+        })
+        .ok_or_else(|| anyhow!("event graph {event_graph_uid} can't go on after its battle"))
     }
 
     pub fn get_cur_event_template_id(&self) -> i32 {
@@ -165,154 +325,454 @@ impl HollowGridManager {
         }
     }
 
+    /// Runs the event the client asks for, or returns `None` if the event graph's event has
+    /// finished and can't be triggered again.
     pub fn run_event_graph(
         &self,
         event_graph_uid: u64,
-        _event_id: i32,
+        event_id: i32,
         move_path: Vec<i32>,
-    ) -> (
-        PtcSyncHollowEventInfoArg,
-        PtcHollowGridArg,
-        Option<i32>,
-        bool,
-    ) {
-        let (player_uid, scene_uid) = {
-            let player = self.player.read();
+    ) -> Option<EventGraphRun> {
+        let finished = self
+            .events
+            .read()
+            .get(&event_graph_uid)
+            .is_some_and(|info| matches!(info.state, EventState::Finished));
+        if finished && !self.run.read().retriggerable.contains(&event_graph_uid) {
+            return None;
+        }
+
+        self.run_with(event_graph_uid, |graph, info, context| {
+            // Picks up at the action the client was sent to last, or runs the event from the start.
+            let (start_action_id, action_move_path) = match info {
+                Some(info)
+                    if matches!(info.state, EventState::WaitingClient)
+                        && info.cur_action_id > 0 =>
+                {
+                    let mut path = info.action_move_path.clone();
+                    if path.last() == Some(&info.cur_action_id) {
+                        path.pop();
+                    }
+
+                    (info.cur_action_id, path)
+                }
+                _ => (
+                    graph.event(event_id).map_or_else(
+                        || move_path.first().copied().unwrap_or(-1),
+                        |event| event.action_id(0),
+                    ),
+                    Vec::new(),
+                ),
+            };
+            let event_id = graph
+                .action(start_action_id)
+                .map_or(event_id, |(event, _)| event.id);
+
+            let run = event_runner::run_event(
+                graph,
+                start_action_id,
+                action_move_path,
+                &move_path,
+                context,
+            );
+
+            let mispredicted = move_path
+                .iter()
+                .filter(|id| run.failed_actions.contains(id))
+                .collect::<Vec<_>>();
+            if !mispredicted.is_empty() {
+                tracing::debug!(
+                    "event graph {}: client took failed actions {mispredicted:?}",
+                    graph.id
+                );
+            }
 
-            (player.uid.unwrap(), player.scene_uid.unwrap())
+            Some((event_id, run))
+        })
+    }
+
+    /// Continues the event waiting on a ChoiceServer action down the branch of `choice_id`,
+    /// or returns `None` if that choice wasn't offered to the player.
+    pub fn make_choice(&self, event_graph_uid: u64, choice_id: i32) -> Option<EventGraphRun> {
+        let template_id = self.template_id(event_graph_uid)?;
+        let graph = data::get_event_graph(template_id)?;
+
+        let (event_id, choice, action_move_path) = {
+            let events = self.events.read();
+            let info = events.get(&event_graph_uid)?;
+            let ActionInfo::ServerChoices { choices, .. } = &info.cur_action_info else {
+                return None;
+            };
+            if !choices
+                .iter()
+                .any(|choice| choice.id == choice_id && !choice.forbidden)
+            {
+                return None;
+            }
+
+            let (event, index) = graph.action(info.cur_action_id)?;
+            let ConfigAction::ConfigChoiceServer { choices, .. } = &event.actions[index] else {
+                return None;
+            };
+
+            (
+                event.id,
+                choices.get(usize::try_from(choice_id).ok()?)?,
+                info.action_move_path.clone(),
+            )
         };
 
-        let mut map = self.map.write();
-        let map = map.as_mut().unwrap();
+        self.run_with(event_graph_uid, |graph, _, context| {
+            Some((
+                event_id,
+                event_runner::run_choice(graph, choice, action_move_path, context),
+            ))
+        })
+    }
 
-        let mut trigger_battle_id = None;
-        let mut grid_update = PtcHollowGridArg {
-            player_uid,
-            is_partial: true,
-            scene_uid,
-            hollow_level: 1,
-            grids: HashMap::new(),
+    /// Buys goods offered by the shop the event on `event_graph_uid` is stopped at.
+    pub fn buy(
+        &self,
+        event_graph_uid: u64,
+        shop_type: &HollowShopType,
+        goods_uid: i32,
+    ) -> Option<EventGraphRun> {
+        self.run_with(event_graph_uid, |_, info, context| {
+            let info = info?;
+            let ActionInfo::Shop {
+                shop_info,
+                finished: false,
+            } = &mut info.cur_action_info
+            else {
+                return None;
+            };
+            let shop = shop_info.get_mut(shop_type)?;
+            if let Err(err) = context.buy(shop_type, shop, goods_uid) {
+                tracing::warn!("hollow shop: {err}");
+                return None;
+            }
+
+            // The shop stays open, so the event is where it was.
+            Some((
+                info.id,
+                EventRun {
+                    action_move_path: info.action_move_path.clone(),
+                    failed_actions: info.predicated_failed_actions.iter().copied().collect(),
+                    finished: false,
+                },
+            ))
+        })
+    }
+
+    /// Removes the curses picked for the event waiting on a curse removal and goes on with it.
+    pub fn remove_curses(&self, curse_uids: &[u64]) -> Option<EventGraphRun> {
+        let (event_graph_uid, event_id, action_move_path) = {
+            let events = self.events.read();
+            let (uid, info) = events.iter().find(|(_, info)| {
+                matches!(
+                    info.cur_action_info,
+                    ActionInfo::RemoveCurse { choosed: false, .. }
+                )
+            })?;
+            let ActionInfo::RemoveCurse {
+                curse_can_remove,
+                to_remove_num,
+                ..
+            } = &info.cur_action_info
+            else {
+                return None;
+            };
+
+            let picked = curse_uids.iter().collect::<HashSet<_>>();
+            if picked.len() != curse_uids.len()
+                || picked.len() > usize::from(*to_remove_num)
+                || !picked.iter().all(|uid| curse_can_remove.contains(uid))
+            {
+                return None;
+            }
+
+            (*uid, info.id, info.action_move_path.clone())
         };
 
-        let mut hollow_finished = false;
+        self.run_with(event_graph_uid, |graph, _, context| {
+            for uid in curse_uids {
+                context.remove_item(&DungeonContentDropPoolType::Curse, *uid as i32, 1);
+            }
 
-        let sync_hollow_event = {
-            let info = map.grids.get(&(event_graph_uid as u16)).unwrap().clone();
-            let graph =
-                data::get_event_graph(info.grid.event_graph_info.hollow_event_template_id).unwrap();
+            Some((
+                event_id,
+                event_runner::resume(graph, action_move_path, context),
+            ))
+        })
+    }
 
-            let mut last_action = &ConfigAction::ConfigEmpty;
+    fn template_id(&self, event_graph_uid: u64) -> Option<i32> {
+        graph_template_id(self.map.read().as_ref()?, &self.run.read(), event_graph_uid)
+    }
 
-            for id in &move_path {
-                let index = (id % 1000) - 1;
-                let event = if id / 1000 == 1 {
-                    graph.events.get(&ConfigEventType::OnStart)
-                } else {
-                    graph.events.get(&ConfigEventType::OnEnd)
-                };
-                if let Some(action) = event.unwrap().actions.get(index as usize) {
-                    last_action = action;
-
-                    match action {
-                        ConfigAction::ConfigSetMapState { x, y, .. } => {
-                            let (ConfigValue::Constant(x), ConfigValue::Constant(y)) = (x, y)
-                            else {
-                                panic!("ConfigSetMapState: only constant values are supported");
-                            };
-
-                            let uid = ((y * 11) + x) as u16;
-                            if let Some(info) = map.grids.get_mut(&uid) {
-                                info.grid.flag |= HollowGridFlag::Visible as i32
-                                    | HollowGridFlag::CanMove as i32
-                                    | HollowGridFlag::ShowEventType as i32;
-
-                                grid_update.grids.insert(uid, info.clone());
-                            }
-                        }
-                        ConfigAction::ConfigTriggerBattle { .. } => {
-                            trigger_battle_id =
-                                Some(match info.grid.event_graph_info.hollow_event_template_id {
-                                    1000107 => 10101002,
-                                    _ => 10101001,
-                                });
-                        }
-                        ConfigAction::ConfigFinishHollow => {
-                            hollow_finished = true;
-                        }
-                        _ => {}
-                    };
-                }
+    /// Uses the hollow item in the slot if it's charged enough, which fires the `ItemTrigger`
+    /// event of its graph.
+    pub fn use_item(&self, item_id: i32) -> Option<EventGraphRun> {
+        let item = hollow_item::template(item_id)?;
+        let graph = data::get_event_graph(item.event_graph_id)?;
+        let event = graph.events.get(&ConfigEventType::ItemTrigger)?;
+
+        {
+            let mut player = self.player.write();
+            let (dungeon, _) = cur_hollow(&mut player)?;
+            if dungeon.initiative_item != item_id || self.run.read().item_charge < item.ep_cost {
+                return None;
             }
+        }
 
-            let mut action_move_path = move_path;
+        // Every use starts over from the graph's specials, at the level of the item.
+        let mut specials = EventSpecials::seeded(graph);
+        if let Some(level) = specials.values.get_mut("level") {
+            *level = item.level;
+        }
+        self.specials
+            .write()
+            .insert(HOLLOW_ITEM_EVENT_GRAPH_UID, specials);
+        self.events.write().remove(&HOLLOW_ITEM_EVENT_GRAPH_UID);
+        self.run.write().item_graph = Some(graph.id);
+
+        self.run_with(HOLLOW_ITEM_EVENT_GRAPH_UID, |graph, _, context| {
+            context.spend_item(item);
 
-            let last_client_action = *action_move_path.last().unwrap();
-            let actions = if last_client_action / 1000 == 1 {
-                &graph.events.get(&ConfigEventType::OnStart).unwrap().actions
+            Some((
+                event.id,
+                event_runner::run_event(graph, event.action_id(0), Vec::new(), &[], context),
+            ))
+        })
+    }
+
+    // Runs an event of the graph on `event_graph_uid` and stores where it stopped.
+    fn run_with(
+        &self,
+        event_graph_uid: u64,
+        run: impl FnOnce(
+            &'static ConfigEventGraph,
+            Option<&mut EventInfo>,
+            &mut HollowEventContext,
+        ) -> Option<(i32, EventRun)>,
+    ) -> Option<EventGraphRun> {
+        let (player_uid, scene_uid) = {
+            let player = self.player.read();
+
+            (player.uid?, player.scene_uid?)
+        };
+
+        let mut map = self.map.write();
+        let map = map.as_mut()?;
+        let mut hollow_run = self.run.write();
+
+        let template_id = graph_template_id(map, &hollow_run, event_graph_uid)?;
+        let graph = data::get_event_graph(template_id)?;
+
+        let mut events = self.events.write();
+        let finished_events = finished_events(&events);
+        let mut specials = self.specials.write();
+        let specials = specials
+            .entry(event_graph_uid)
+            .or_insert_with(|| EventSpecials::seeded(graph));
+
+        let mut context = HollowEventContext {
+            player: &self.player,
+            grid: grid_of(map, event_graph_uid),
+            map,
+            finished_events: &finished_events,
+            graph,
+            run: &mut hollow_run,
+            specials,
+            updated_grids: HashMap::new(),
+            trigger_battle_id: None,
+            hollow_finished: false,
+            disabled_cards: Vec::new(),
+            card_genre_tips: Vec::new(),
+            hp_or_stress_changes: Vec::new(),
+            avatar_map: None,
+            party_wiped: false,
+            can_trigger_again: false,
+            cur_avatar: None,
+        };
+        let (event_id, run) = run(graph, events.get_mut(&event_graph_uid), &mut context)?;
+
+        if run.finished {
+            if context.can_trigger_again {
+                context.run.retriggerable.insert(event_graph_uid);
             } else {
-                &graph.events.get(&ConfigEventType::OnEnd).unwrap().actions
-            };
-            let state = if last_client_action == -1 {
-                EventState::Finished
-            } else if last_client_action % 1000 >= actions.len() as i32 {
-                action_move_path.push(-1);
+                context.run.retriggerable.remove(&event_graph_uid);
+            }
+        }
+
+        let cur_action = run
+            .action_move_path
+            .last()
+            .and_then(|id| graph.action(*id))
+            .map(|(event, index)| &event.actions[index]);
+
+        let cur_action_id = *run.action_move_path.last().unwrap_or(&-1);
+        let cur_action_info = match cur_action {
+            // The stock is kept for as long as the player is in the shop.
+            Some(ConfigAction::ConfigShop { .. })
+                if events.get(&event_graph_uid).is_some_and(|info| {
+                    info.cur_action_id == cur_action_id
+                        && matches!(info.cur_action_info, ActionInfo::Shop { .. })
+                }) =>
+            {
+                events[&event_graph_uid].cur_action_info.clone()
+            }
+            Some(ConfigAction::ConfigShop { shop_info, .. }) => ActionInfo::Shop {
+                shop_info: context.stock_shops(shop_info),
+                finished: false,
+            },
+            Some(ConfigAction::ConfigChoiceServer {
+                options_mode,
+                choices,
+                random_num,
+                ..
+            }) => ActionInfo::ServerChoices {
+                choices: context.offer_choices(options_mode, choices, *random_num),
+                finished: false,
+            },
+            Some(ConfigAction::ConfigRemoveCurse { count, .. }) => ActionInfo::RemoveCurse {
+                curse_can_remove: context.removable_curses(),
+                to_remove_num: event_runner::evaluate(count, &mut context).unwrap_or(1.0) as u8,
+                choosed: false,
+            },
+            _ => ActionInfo::None {},
+        };
+
+        let updated_event = EventInfo {
+            id: event_id,
+            cur_action_id,
+            action_move_path: run.action_move_path,
+            state: if run.finished {
                 EventState::Finished
             } else {
-                if !matches!(last_action, ConfigAction::ConfigEmpty) {
-                    action_move_path.push(last_client_action + 1);
-                }
-
                 EventState::WaitingClient
-            };
+            },
+            prev_state: EventState::Running,
+            cur_action_info,
+            cur_action_state: ActionState::Init,
+            predicated_failed_actions: PropertyHashSet::Base(run.failed_actions),
+            stack_frames: Vec::new(),
+        };
 
-            let finish_event = if let ConfigAction::ConfigTriggerBattle { .. } = last_action {
-                PtcSyncHollowEventInfoArg {
-                    event_graph_uid,
-                    hollow_event_template_id: info.grid.event_graph_info.hollow_event_template_id,
-                    event_graph_id: info.grid.event_graph_info.hollow_event_template_id,
-                    updated_event: EventInfo {
-                        id: 0,
-                        cur_action_id: 0,
-                        action_move_path: vec![],
-                        state: EventState::Initing,
-                        prev_state: EventState::Initing,
-                        cur_action_info: ActionInfo::None {},
-                        cur_action_state: ActionState::Init,
-                        predicated_failed_actions: phashset![],
-                        stack_frames: Vec::new(),
-                    },
-                    specials: phashmap![],
-                }
-            } else {
-                PtcSyncHollowEventInfoArg {
-                    event_graph_uid,
-                    hollow_event_template_id: info.grid.event_graph_info.hollow_event_template_id,
-                    event_graph_id: info.grid.event_graph_info.hollow_event_template_id,
-                    updated_event: EventInfo {
-                        id: 1000,
-                        cur_action_id: *action_move_path.last().unwrap(),
-                        action_move_path,
-                        state,
-                        prev_state: EventState::Running,
-                        cur_action_info: ActionInfo::None {},
-                        cur_action_state: ActionState::Init,
-                        predicated_failed_actions: phashset![],
-                        stack_frames: Vec::new(),
-                    },
-                    specials: phashmap![],
-                }
-            };
+        // The client is shown no event during a battle, the server goes on with it from where it
+        // stopped once the battle is won.
+        let synced_event = if let Some(ConfigAction::ConfigTriggerBattle { .. }) = cur_action {
+            if context.trigger_battle_id.is_some() {
+                context.run.battle_graph = Some(event_graph_uid);
+            }
+
+            idle_event()
+        } else {
+            updated_event.clone()
+        };
+        events.insert(event_graph_uid, updated_event);
+
+        let mut graph_run = EventGraphRun::new(player_uid, scene_uid);
+        graph_run.sync_events.push(PtcSyncHollowEventInfoArg {
+            event_graph_uid,
+            hollow_event_template_id: template_id,
+            event_graph_id: template_id,
+            updated_event: synced_event,
+            specials: context.specials.to_sync(),
+        });
+        graph_run.merge(context);
+
+        Some(graph_run)
+    }
 
-            //tracing::info!("sending evt info: {:#?}", &finish_event);
-            finish_event
+    fn set_map_state(
+        map: &mut HollowGridMapProtocolInfo,
+        x: &ConfigValue,
+        y: &ConfigValue,
+        updated_grids: &mut HashMap<u16, HollowGridProtocolInfo>,
+    ) {
+        let (ConfigValue::Constant(x), ConfigValue::Constant(y)) = (x, y) else {
+            tracing::warn!("ConfigSetMapState: only constant values are supported");
+            return;
         };
 
-        (
-            sync_hollow_event,
-            grid_update,
-            trigger_battle_id,
-            hollow_finished,
-        )
+        let uid = ((y * 11) + x) as u16;
+        if let Some(info) = map.grids.get_mut(&uid) {
+            info.grid.flag |= HollowGridFlag::Visible as i32
+                | HollowGridFlag::CanMove as i32
+                | HollowGridFlag::ShowEventType as i32;
+
+            updated_grids.insert(uid, info.clone());
+        }
+    }
+
+    // Runs `event_type` of every grid's event graph that has it, the server goes through these
+    // by itself. What they change is added to `fired`.
+    fn fire_event(
+        &self,
+        map: &mut HollowGridMapProtocolInfo,
+        event_type: &ConfigEventType,
+        fired: &mut EventGraphRun,
+    ) {
+        let events = map
+            .grids
+            .iter()
+            .filter_map(|(uid, info)| {
+                let graph =
+                    data::get_event_graph(info.grid.event_graph_info.hollow_event_template_id)?;
+                Some((u64::from(*uid), graph, graph.events.get(event_type)?))
+            })
+            .collect::<Vec<_>>();
+
+        let events_info = self.events.read();
+        let finished_events = finished_events(&events_info);
+        let mut hollow_run = self.run.write();
+        let mut specials = self.specials.write();
+        for (uid, graph, event) in events {
+            tracing::debug!("grid {uid}: firing {event_type:?}");
+
+            let specials = specials
+                .entry(uid)
+                .or_insert_with(|| EventSpecials::seeded(graph));
+            let specials_before = specials.values.clone();
+            let mut context = HollowEventContext {
+                player: &self.player,
+                grid: grid_of(map, uid),
+                map,
+                finished_events: &finished_events,
+                graph,
+                run: &mut hollow_run,
+                specials,
+                updated_grids: HashMap::new(),
+                trigger_battle_id: None,
+                hollow_finished: false,
+                disabled_cards: Vec::new(),
+                card_genre_tips: Vec::new(),
+                hp_or_stress_changes: Vec::new(),
+                avatar_map: None,
+                party_wiped: false,
+                can_trigger_again: false,
+                cur_avatar: None,
+            };
+            let run = event_runner::run_server_event(graph, event, &mut context);
+            if !run.failed_actions.is_empty() {
+                tracing::debug!(
+                    "grid {uid}: {event_type:?} skipped actions {:?}",
+                    run.failed_actions
+                );
+            }
+
+            if context.specials.values != specials_before {
+                fired.sync_events.push(PtcSyncHollowEventInfoArg {
+                    event_graph_uid: uid,
+                    hollow_event_template_id: graph.id,
+                    event_graph_id: graph.id,
+                    updated_event: events_info.get(&uid).cloned().unwrap_or_else(idle_event),
+                    specials: context.specials.to_sync(),
+                });
+            }
+            fired.merge(context);
+        }
     }
 
     pub fn sync_hollow_maps(&self, player_uid: u64, scene_uid: u64) -> PtcSyncHollowGridMapsArg {
@@ -326,8 +786,9 @@ impl HollowGridManager {
         }
     }
 
-    pub fn init_default_map(&self) {
-        *self.map.write() = Some(HollowGridMapProtocolInfo {
+    /// Creates the map of a new hollow and fires the events run on its creation.
+    pub fn init_default_map(&self) -> EventGraphRun {
+        let mut map = HollowGridMapProtocolInfo {
             row: 5,
             col: 11,
             start_grid: 22,
@@ -828,6 +1289,1154 @@ impl HollowGridManager {
                 )
             ],
             chessboard_id: 1000101,
-        });
+        };
+
+        // The default map isn't generated, so nothing drops the battles of its battle grids.
+        let mut specials = self.specials.write();
+        specials.clear();
+        for (uid, info) in map.grids.iter() {
+            let template_id = info.grid.event_graph_info.hollow_event_template_id;
+            let battle_id = match template_id {
+                1000103 => 10101001,
+                1000107 => 10101002,
+                _ => continue,
+            };
+            let Some(graph) = data::get_event_graph(template_id) else {
+                continue;
+            };
+
+            let mut grid_specials = EventSpecials::seeded(graph);
+            grid_specials
+                .values
+                .insert(String::from("BattleID"), battle_id);
+            specials.insert(u64::from(*uid), grid_specials);
+        }
+        drop(specials);
+
+        // Logged so a run's drops and rolls can be reproduced.
+        let seed = rand::random();
+        tracing::debug!("hollow rng seed: {seed}");
+        let mut run = HollowRun::new(StdRng::seed_from_u64(seed));
+
+        // The initiative item taken into the hollow comes charged.
+        if let Some((dungeon, _)) = cur_hollow(&mut self.player.write()) {
+            run.item_charge =
+                hollow_item::template(dungeon.initiative_item).map_or(0, |item| item.ep_cost);
+        }
+        *self.run.write() = run;
+
+        self.events.write().clear();
+
+        let mut fired = {
+            let player = self.player.read();
+            EventGraphRun::new(
+                player.uid.unwrap_or_default(),
+                player.scene_uid.unwrap_or_default(),
+            )
+        };
+        self.fire_event(&mut map, &ConfigEventType::OnCreate, &mut fired);
+        self.fire_event(&mut map, &ConfigEventType::OnHollowCreated, &mut fired);
+
+        *self.map.write() = Some(map);
+        fired
+    }
+}
+
+struct HollowEventContext<'a> {
+    player: &'a RwLock<PlayerInfo>,
+    // Grid of the running graph, `None` for hollow items, which run off the map.
+    grid: Option<u16>,
+    map: &'a mut HollowGridMapProtocolInfo,
+    // Event graphs on the map whose event has finished.
+    finished_events: &'a HashSet<u64>,
+    graph: &'static ConfigEventGraph,
+    run: &'a mut HollowRun,
+    specials: &'a mut EventSpecials,
+    updated_grids: HashMap<u16, HollowGridProtocolInfo>,
+    trigger_battle_id: Option<i32>,
+    hollow_finished: bool,
+    disabled_cards: Vec<u64>,
+    card_genre_tips: Vec<i32>,
+    hp_or_stress_changes: Vec<PtcHpOrStressChangedArg>,
+    avatar_map: Option<PropertyHashMap<i8, AvatarUnitInfo>>,
+    party_wiped: bool,
+    can_trigger_again: bool,
+    // The team slot `property()` reads while an action applies to each avatar in turn.
+    cur_avatar: Option<i8>,
+}
+
+impl ExpressionContext for HollowEventContext<'_> {
+    fn call(&mut self, function: &str, args: &[String]) -> Result<f64> {
+        match (function, args) {
+            ("specials", [name]) => Ok(f64::from(
+                self.specials.values.get(name).copied().unwrap_or_default(),
+            )),
+            ("scenevar", [key]) => Ok(f64::from(
+                self.run.variables.get(key).copied().unwrap_or_default(),
+            )),
+            ("rand", [min, max]) => {
+                let min = expression::evaluate(min, self)? as i32;
+                let max = expression::evaluate(max, self)? as i32;
+                Ok(f64::from(self.random(min, max)?))
+            }
+            ("hollowlevel", []) => Ok(1.0),
+            ("hollowitem", []) => Ok(f64::from(self.hollow_item())),
+            ("property", [name]) => Ok(f64::from(self.property(name)?)),
+            ("sceneproperty", [name]) => {
+                let Some(property) = hollow_party::scene_property(name) else {
+                    bail!("unknown scene property {name}");
+                };
+                let mut player = self.player.write();
+                let Some(party) = Party::of(&mut player) else {
+                    bail!("sceneproperty({name}) outside of a hollow");
+                };
+                Ok(f64::from(party.scene_property(property)))
+            }
+            _ => bail!("{function}({}) is not supported", args.join(",")),
+        }
+    }
+}
+
+impl EventContext for HollowEventContext<'_> {
+    fn item_count(&self, item_id: i32) -> i32 {
+        if item_id == hollow_shop::GEAR_COIN_ID {
+            return self.run.coins;
+        }
+
+        self.player
+            .read()
+            .items
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|(_, item)| item.get_id() == item_id)
+            .map(|(_, item)| item.get_count())
+            .sum()
+    }
+
+    fn is_alive(&self, team_slot: i8) -> Option<bool> {
+        Party::of(&mut self.player.write())?.is_alive(team_slot)
+    }
+
+    fn held_count(
+        &self,
+        pools: &[DungeonContentDropPoolType],
+        item_id: Option<i32>,
+        rare: &[i32],
+        genre: &[i32],
+    ) -> i32 {
+        pools
+            .iter()
+            .filter_map(|pool| self.run.inventory.get(pool))
+            .flatten()
+            .filter(|(id, _)| item_id.is_none_or(|item_id| **id == item_id))
+            .filter(|(id, _)| drop_pool::matches(**id, rare, genre))
+            .map(|(_, count)| *count)
+            .sum()
+    }
+
+    // Positions not given in absolute grid coordinates are relative to the running graph's grid,
+    // or to the player's for hollow items.
+    fn count_grids(&self, filter: &GridFilter) -> i32 {
+        let col = i32::from(self.map.col);
+        let (x, y) = match filter.position {
+            HollowPositionOffsetType::Absolute => (filter.x, filter.y),
+            HollowPositionOffsetType::Relative | HollowPositionOffsetType::EventPos => {
+                let origin = i32::from(self.grid.unwrap_or(self.map.start_grid));
+                (origin % col + filter.x, origin / col + filter.y)
+            }
+        };
+
+        self.map
+            .grids
+            .iter()
+            .filter(|(uid, info)| {
+                let pos = i32::from(**uid);
+                let state_matches = |state: &NodeState| match state {
+                    NodeState::All => true,
+                    NodeState::Finished => {
+                        info.grid.node_state == NodeState::Finished
+                            || self.finished_events.contains(&u64::from(**uid))
+                    }
+                    state => info.grid.node_state == *state,
+                };
+
+                (pos % col - x).abs() + (pos / col - y).abs() <= filter.radius
+                    && (filter.event_ids.is_empty()
+                        || filter
+                            .event_ids
+                            .contains(&info.grid.event_graph_info.hollow_event_template_id))
+                    && (filter.states.is_empty() || filter.states.iter().any(state_matches))
+                    && (filter.visible_states.is_empty()
+                        || filter.visible_states.iter().any(|visible| {
+                            *visible == NodeVisible::All || *visible == info.grid.node_visible
+                        }))
+            })
+            .count() as i32
+    }
+
+    fn roll(&mut self, odds: f64) -> bool {
+        self.run.rng.gen_bool((odds / 100.0).clamp(0.0, 1.0))
+    }
+
+    fn execute(&mut self, action: &ConfigAction) {
+        if let Err(err) = self.try_execute(action) {
+            tracing::warn!("event graph {}: {err}", self.graph.id);
+        }
+    }
+}
+
+impl HollowEventContext<'_> {
+    // Choices are identified by their index, the ones with failing predicates are shown but
+    // can't be picked.
+    fn offer_choices(
+        &mut self,
+        options_mode: &str,
+        choices: &[ConfigChoice],
+        random_num: Option<i32>,
+    ) -> Vec<ChoiceInfo> {
+        let mut offered = (0..choices.len()).collect::<Vec<_>>();
+        if let ("RandomByNum", Some(num)) = (options_mode, random_num) {
+            offered = offered
+                .into_iter()
+                .choose_multiple(&mut self.run.rng, num.max(0) as usize);
+            offered.sort_unstable();
+        }
+
+        offered
+            .into_iter()
+            .map(|index| ChoiceInfo {
+                id: index as i32,
+                hide_info: false,
+                forbidden: !event_runner::holds(&choices[index].predicates, self),
+            })
+            .collect()
+    }
+
+    // Stocks the shops of a `ConfigShop` whose predicates hold, goods are numbered across all of
+    // them.
+    fn stock_shops(
+        &mut self,
+        shops: &[data::ConfigShopInfo],
+    ) -> PropertyHashMap<HollowShopType, ConfigShopInfo> {
+        let mut stock = phashmap![];
+        let mut offered = Vec::new();
+        for shop in shops {
+            if !event_runner::holds(&shop.predicates, self) {
+                continue;
+            }
+
+            match self.stock_shop(shop, &mut offered) {
+                Ok((shop_type, info)) => stock.insert(shop_type, info),
+                Err(err) => tracing::warn!("event graph {}: {err}", self.graph.id),
+            }
+        }
+
+        stock
+    }
+
+    fn stock_shop(
+        &mut self,
+        shop: &data::ConfigShopInfo,
+        offered: &mut Vec<i32>,
+    ) -> Result<(HollowShopType, ConfigShopInfo)> {
+        let Some(shop_type) = hollow_shop::shop_type(&shop.shop_type) else {
+            bail!("ConfigShop: unknown shop {}", shop.shop_type);
+        };
+        let Some(currency) = hollow_shop::currency(&shop.token_type) else {
+            bail!("ConfigShop: unknown currency {}", shop.token_type);
+        };
+
+        let mut goods = Vec::new();
+        for slot in self.pick_slots(shop)? {
+            let item_id = match (&slot.item_id, slot.item_type.as_deref()) {
+                (Some(item_id), _) => event_runner::evaluate(item_id, self)? as i32,
+                (None, Some(item_type)) => {
+                    let Some(pool) = drop_pool::pool_type(item_type) else {
+                        bail!("ConfigShop: unknown item type {item_type}");
+                    };
+                    self.draw(&pool, &slot.rare, &[], offered)?
+                }
+                (None, None) => bail!("ConfigShop: slot without an item"),
+            };
+            offered.push(item_id);
+
+            let rare = drop_pool::entry(item_id).map_or(0, |entry| entry.rare);
+            let base_value = match slot.price {
+                Some(price) => price,
+                None => self.price(&shop_type, rare)?,
+            };
+            let discount = match &slot.discount {
+                Some(discount) => event_runner::evaluate(discount, self)? as i32,
+                None => 0,
+            };
+
+            goods.push(ConfigItem {
+                uid: offered.len() as i32,
+                item_id,
+                count: slot.buy_count,
+                // Discounts are in ten-thousandths of the price.
+                value: if discount > 0 {
+                    base_value * discount / 10000
+                } else {
+                    base_value
+                },
+                base_value,
+                discount,
+            });
+        }
+
+        Ok((shop_type, ConfigShopInfo { goods, currency }))
+    }
+
+    // `ByGroup` shops offer one slot of each group picked by weight, others offer every slot.
+    fn pick_slots<'s>(
+        &mut self,
+        shop: &'s data::ConfigShopInfo,
+    ) -> Result<Vec<&'s ConfigShopSlot>> {
+        if shop.show_type.as_deref() != Some("ByGroup") {
+            return Ok(shop.slot.iter().collect());
+        }
+
+        let mut groups = shop.slot.iter().map(|slot| slot.group).collect::<Vec<_>>();
+        groups.sort_unstable();
+        groups.dedup();
+
+        let mut picked = Vec::new();
+        for group in groups {
+            let slots = shop
+                .slot
+                .iter()
+                .filter(|slot| slot.group == group)
+                .collect::<Vec<_>>();
+            let weights = slots
+                .iter()
+                .map(|slot| match &slot.weight {
+                    Some(weight) => event_runner::evaluate(weight, self),
+                    None => Ok(0.0),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if let Some(index) = event_runner::pick_weighted(&weights, self) {
+                picked.push(slots[index]);
+            }
+        }
+
+        Ok(picked)
+    }
+
+    // The base price of goods, unless the hollow overwrites the prices of the shop.
+    fn price(&self, shop_type: &HollowShopType, rare: i32) -> Result<i32> {
+        let mut player = self.player.write();
+        if let Some((
+            _,
+            SceneInfo::Hollow {
+                shop_modification, ..
+            },
+        )) = cur_hollow(&mut player)
+        {
+            if let Some(price) = shop_modification.overwrite_price.get(shop_type) {
+                return Ok(*price);
+            }
+        }
+
+        hollow_shop::base_price(shop_type, rare)
+            .ok_or_else(|| anyhow!("no price for {shop_type:?} goods of rarity {rare}"))
+    }
+
+    // Gear coins are spent, a price in curses is paid by drawing random ones. The goods only
+    // leave the shelf once they're handed over.
+    fn buy(
+        &mut self,
+        shop_type: &HollowShopType,
+        shop: &mut ConfigShopInfo,
+        goods_uid: i32,
+    ) -> Result<()> {
+        let Some(goods) = shop
+            .goods
+            .iter_mut()
+            .find(|goods| goods.uid == goods_uid && goods.count > 0)
+        else {
+            bail!("goods {goods_uid} aren't on offer");
+        };
+
+        let mut curses = Vec::new();
+        match shop.currency {
+            HollowShopCurrency::Coin if self.run.coins < goods.value => {
+                bail!("{} coins can't pay for goods {goods_uid}", self.run.coins)
+            }
+            HollowShopCurrency::Coin => (),
+            HollowShopCurrency::Curse => {
+                for _ in 0..goods.value {
+                    curses.push(self.draw(
+                        &DungeonContentDropPoolType::Curse,
+                        &[],
+                        &[],
+                        &curses,
+                    )?);
+                }
+            }
+            HollowShopCurrency::Random => bail!("goods {goods_uid} have no currency"),
+        }
+
+        if shop.currency == HollowShopCurrency::Coin {
+            self.run.coins -= goods.value;
+        }
+        for curse in curses {
+            self.drop_item(DungeonContentDropPoolType::Curse, curse, 1);
+        }
+        self.drop_item(hollow_shop_pool(shop_type, goods.item_id), goods.item_id, 1);
+        goods.count -= 1;
+
+        Ok(())
+    }
+
+    // Properties of the avatar the action applies to, or of the team's first one.
+    fn property(&self, name: &str) -> Result<i32> {
+        let mut player = self.player.write();
+        let Some(party) = Party::of(&mut player) else {
+            bail!("property({name}) outside of a hollow");
+        };
+        let Some(slot) = self
+            .cur_avatar
+            .or_else(|| party.members().first().map(|(slot, _)| *slot))
+        else {
+            bail!("property({name}) without a team");
+        };
+        let (hp, hp_max) = party.hp(slot).unwrap_or_default();
+
+        Ok(match name {
+            "Hp" => hp,
+            "HpMax" | "HpMax_Battle" => hp_max,
+            "Stamina" => party.stress().0,
+            "Stamina_Max" => party.stress().1,
+            _ => bail!("property({name}) is not supported"),
+        })
+    }
+
+    // Applies HP or stress changes to the team, they're sent to the client and fail the hollow
+    // once nobody in the team is standing.
+    fn change_party<I>(&mut self, change: impl FnOnce(&mut Party) -> I)
+    where
+        I: IntoIterator<Item = PtcHpOrStressChangedArg>,
+    {
+        let mut player = self.player.write();
+        let Some(mut party) = Party::of(&mut player) else {
+            return;
+        };
+
+        self.hp_or_stress_changes.extend(change(&mut party));
+        if party.is_wiped() {
+            self.party_wiped = true;
+        }
+    }
+
+    // The hollow item in the slot, 0 when there's none.
+    fn hollow_item(&self) -> i32 {
+        cur_hollow(&mut self.player.write()).map_or(0, |(dungeon, _)| dungeon.initiative_item)
+    }
+
+    // Puts a hollow item in the slot, it replaces the one there and starts out uncharged.
+    fn set_hollow_item(&mut self, item_id: i32) -> Result<()> {
+        if hollow_item::template(item_id).is_none() {
+            bail!("unknown hollow item {item_id}");
+        }
+
+        let mut player = self.player.write();
+        let Some((dungeon, _)) = cur_hollow(&mut player) else {
+            bail!("no hollow to put hollow item {item_id} in");
+        };
+        dungeon.initiative_item = item_id;
+        dungeon.initiative_item_used_times = 0;
+        self.run.item_charge = 0;
+
+        Ok(())
+    }
+
+    // Charges the hollow item in the slot up to its cost.
+    fn charge_item(&mut self, ep: i32) {
+        if let Some(item) = hollow_item::template(self.hollow_item()) {
+            self.run.item_charge = (self.run.item_charge + ep).clamp(0, item.ep_cost);
+        }
+    }
+
+    fn spend_item(&mut self, item: &HollowItemTemplate) {
+        self.run.item_charge -= item.ep_cost;
+
+        let mut player = self.player.write();
+        if let Some(ramen_data) = player.ramen_data.as_mut() {
+            hollow_item::record_use(ramen_data, item.id);
+        }
+        if let Some((dungeon, _)) = cur_hollow(&mut player) {
+            dungeon.initiative_item_used_times += 1;
+        }
+    }
+
+    fn target_slots(&self, target: Option<&str>) -> Result<Vec<i8>> {
+        match target {
+            None | Some("All") => Ok(Party::of(&mut self.player.write())
+                .map(|party| party.members().into_iter().map(|(slot, _)| slot).collect())
+                .unwrap_or_default()),
+            Some(target) => match hollow_party::team_slot(target) {
+                Some(slot) => Ok(vec![slot]),
+                None => bail!("unknown target {target}"),
+            },
+        }
+    }
+
+    fn random(&mut self, min: i32, max: i32) -> Result<i32> {
+        if min > max {
+            bail!("empty random range {min}..={max}");
+        }
+
+        Ok(self.run.rng.gen_range(min..=max))
+    }
+
+    // Adds to the dungeon inventory and marks the item as dropped in the dungeon's pool info, so
+    // cards and curses aren't drawn twice in a run. Cards and curses also take effect as abilities
+    // of the hollow scene and are added to the player's collection.
+    fn drop_item(&mut self, pool: DungeonContentDropPoolType, item_id: i32, count: i32) {
+        let genre = drop_pool::entry(item_id).map_or(0, |entry| entry.genre);
+        if is_card(&pool)
+            && genre != 0
+            && self.held_count(std::slice::from_ref(&pool), None, &[], &[genre]) == 0
+        {
+            self.card_genre_tips.push(genre);
+        }
+
+        let held = self
+            .run
+            .inventory
+            .entry(pool.clone())
+            .or_default()
+            .entry(item_id)
+            .or_default();
+        *held += count;
+        let stack_num = *held;
+
+        let mut player = self.player.write();
+        collect(&mut player, &pool, item_id);
+        let Some((dungeon, scene)) = cur_hollow(&mut player) else {
+            return;
+        };
+        if is_ability(&pool) {
+            sync_ability(scene, item_id, stack_num);
+        }
+
+        if dungeon.drop_poll_chg_infos.get(&pool).is_none() {
+            dungeon.drop_poll_chg_infos.insert(
+                pool.clone(),
+                DungeonDropPollInfo {
+                    action_card_mask: phashmap![],
+                },
+            );
+        }
+        let mask = &mut dungeon
+            .drop_poll_chg_infos
+            .get_mut(&pool)
+            .unwrap()
+            .action_card_mask;
+        let dropped = mask.get(&item_id).copied().unwrap_or_default();
+        mask.insert(item_id, dropped + count);
+    }
+
+    // Takes up to `count` of a held item away, removed cards are disabled on the client.
+    fn remove_item(&mut self, pool: &DungeonContentDropPoolType, item_id: i32, count: i32) {
+        let Some(items) = self.run.inventory.get_mut(pool) else {
+            return;
+        };
+        let Some(held) = items.get_mut(&item_id) else {
+            return;
+        };
+        *held -= count.min(*held);
+        let stack_num = *held;
+        // Cards are only disabled once their last stack is gone.
+        if stack_num == 0 {
+            items.remove(&item_id);
+            self.run.locked_curses.remove(&item_id);
+
+            if is_card(pool) {
+                self.disabled_cards.push(item_id as u64);
+            }
+        }
+        if let Some((_, scene)) = cur_hollow(&mut self.player.write()) {
+            sync_ability(scene, item_id, stack_num);
+        }
+    }
+
+    // Removes `count` random held items of `pools` with one of the `rare` rarities, locked
+    // curses are kept.
+    fn remove_random(&mut self, pools: &[DungeonContentDropPoolType], rare: &[i32], count: i32) {
+        for _ in 0..count {
+            let Some((pool, item_id)) = pools
+                .iter()
+                .filter_map(|pool| Some((pool, self.run.inventory.get(pool)?)))
+                .flat_map(|(pool, items)| items.keys().map(move |id| (pool.clone(), *id)))
+                .filter(|(_, id)| !self.run.locked_curses.contains(id))
+                .filter(|(_, id)| drop_pool::matches(*id, rare, &[]))
+                .choose(&mut self.run.rng)
+            else {
+                return;
+            };
+
+            self.remove_item(&pool, item_id, 1);
+        }
+    }
+
+    // Removes `count` of `item_id` from whichever of `pools` holds it.
+    fn remove_held(&mut self, pools: &[DungeonContentDropPoolType], item_id: i32, count: i32) {
+        let Some(pool) = pools.iter().find(|pool| {
+            self.run
+                .inventory
+                .get(pool)
+                .is_some_and(|items| items.contains_key(&item_id))
+        }) else {
+            return;
+        };
+
+        self.remove_item(pool, item_id, count);
+    }
+
+    // The curses the player can pick to remove, locked ones stay.
+    fn removable_curses(&self) -> Vec<u64> {
+        let mut curses = self
+            .run
+            .inventory
+            .get(&DungeonContentDropPoolType::Curse)
+            .into_iter()
+            .flat_map(HashMap::keys)
+            .filter(|id| !self.run.locked_curses.contains(id))
+            .map(|id| *id as u64)
+            .collect::<Vec<_>>();
+        curses.sort_unstable();
+
+        curses
+    }
+
+    fn draw(
+        &mut self,
+        pool: &DungeonContentDropPoolType,
+        rare: &[i32],
+        genre: &[i32],
+        drawn: &[i32],
+    ) -> Result<i32> {
+        self.run.draw(pool, rare, genre, drawn)
+    }
+
+    fn try_execute(&mut self, action: &ConfigAction) -> Result<()> {
+        match action {
+            ConfigAction::ConfigSetMapState { x, y, .. } => {
+                HollowGridManager::set_map_state(self.map, x, y, &mut self.updated_grids);
+            }
+            ConfigAction::ConfigTriggerBattle { battle_id, .. } => {
+                let battle_id = event_runner::evaluate(battle_id, self)? as i32;
+                if battle_id == 0 {
+                    bail!("ConfigTriggerBattle: no battle to trigger");
+                }
+                self.trigger_battle_id = Some(battle_id);
+            }
+            ConfigAction::ConfigFinishHollow => self.hollow_finished = true,
+            ConfigAction::ConfigDropPool {
+                r#type,
+                item_id,
+                num,
+                rare,
+                ..
+            } => {
+                let Some(pool) = r#type.as_deref().and_then(drop_pool::pool_type) else {
+                    bail!("ConfigDropPool: unknown pool {type:?}");
+                };
+
+                for _ in 0..num.unwrap_or(1) {
+                    let item_id = match item_id {
+                        Some(item_id) => event_runner::evaluate(item_id, self)? as i32,
+                        None => self.draw(&pool, rare, &[], &[])?,
+                    };
+                    if item_id != 0 {
+                        self.drop_item(pool.clone(), item_id, 1);
+                    }
+                }
+            }
+            ConfigAction::ConfigRandomDropID {
+                r#type,
+                special_name,
+                rare,
+            } => {
+                let Some(pool) = drop_pool::pool_type(r#type) else {
+                    bail!("ConfigRandomDropID: unknown pool {type}");
+                };
+                let rare = rare
+                    .iter()
+                    .map(|rare| Ok(event_runner::evaluate(rare, self)? as i32))
+                    .collect::<Result<Vec<_>>>()?;
+
+                let mut drawn = Vec::new();
+                for name in special_name {
+                    let item_id = self.draw(&pool, &rare, &[], &drawn)?;
+                    drawn.push(item_id);
+                    self.specials.values.insert(name.clone(), item_id);
+                }
+            }
+            ConfigAction::ConfigAddItem { item_id, count, .. } => {
+                let item_id = event_runner::evaluate(item_id, self)? as i32;
+                let count = event_runner::evaluate(count, self)? as i32;
+                if item_id == hollow_shop::GEAR_COIN_ID {
+                    self.run.coins += count;
+                } else {
+                    self.drop_item(DungeonContentDropPoolType::Reward, item_id, count);
+                }
+            }
+            ConfigAction::ConfigDropHollowItem { item_ids } => {
+                for item_id in item_ids {
+                    let item_id = event_runner::evaluate(item_id, self)? as i32;
+                    if item_id != 0 {
+                        self.drop_item(DungeonContentDropPoolType::HollowItem, item_id, 1);
+                    }
+                }
+            }
+            ConfigAction::ConfigFinishEvent { can_trigger_again } => {
+                self.can_trigger_again = *can_trigger_again;
+            }
+            ConfigAction::ConfigSetHollowItem {
+                ep_charge,
+                hollow_item_id,
+                ..
+            } => {
+                if let Some(item_id) = hollow_item_id {
+                    let item_id = event_runner::evaluate(item_id, self)? as i32;
+                    self.set_hollow_item(item_id)?;
+                }
+                if let Some(ep_charge) = ep_charge {
+                    let ep = event_runner::evaluate(ep_charge, self)? as i32;
+                    self.charge_item(ep);
+                }
+            }
+            ConfigAction::ConfigDropBattleID {
+                r#type,
+                special_name,
+                ..
+            } => {
+                let groups = r#type
+                    .iter()
+                    .map(|group| Ok(event_runner::evaluate(group, self)? as i32))
+                    .collect::<Result<Vec<_>>>()?;
+                let Some(battle_id) = drop_pool::draw_battle(&groups, &mut self.run.rng) else {
+                    bail!("ConfigDropBattleID: no battles in groups {groups:?}");
+                };
+                self.specials.values.insert(special_name.clone(), battle_id);
+            }
+            ConfigAction::ConfigDropCard {
+                r#type,
+                genre,
+                rare,
+                num,
+                specials,
+            } => {
+                let genre = genre
+                    .iter()
+                    .map(|genre| Ok(event_runner::evaluate(genre, self)? as i32))
+                    .collect::<Result<Vec<_>>>()?;
+
+                let pool = event_runner::card_pools(Some(*r#type)).remove(0);
+                let mut drawn = Vec::new();
+                for i in 0..*num {
+                    let item_id = self.draw(&pool, rare, &genre, &drawn)?;
+                    drawn.push(item_id);
+                    self.drop_item(pool.clone(), item_id, 1);
+                    if let Some(name) = specials.get(i as usize) {
+                        self.specials.values.insert(name.clone(), item_id);
+                    }
+                }
+            }
+            ConfigAction::ConfigRemoveCard {
+                item_id,
+                count,
+                rare,
+                r#type,
+                ..
+            } => {
+                let pools = event_runner::card_pools(*r#type);
+                match item_id {
+                    Some(item_id) => {
+                        let item_id = event_runner::evaluate(item_id, self)? as i32;
+                        self.remove_held(&pools, item_id, *count);
+                    }
+                    None => self.remove_random(&pools, rare, *count),
+                }
+            }
+            ConfigAction::ConfigDropCurse {
+                curse_id, stack, ..
+            } => {
+                let curse_id = event_runner::evaluate(curse_id, self)? as i32;
+                if curse_id == 0 {
+                    return Ok(());
+                }
+
+                let pool = drop_pool::entry(curse_id)
+                    .and_then(|entry| drop_pool::pool_type(&entry.pool_type))
+                    .unwrap_or(DungeonContentDropPoolType::Curse);
+                if stack.as_deref() == Some("Unique")
+                    && self.held_count(std::slice::from_ref(&pool), Some(curse_id), &[], &[]) > 0
+                {
+                    return Ok(());
+                }
+
+                self.drop_item(pool, curse_id, 1);
+            }
+            // The `Initiative` removal is picked by the player, see `HollowGridManager::remove_curses`.
+            ConfigAction::ConfigRemoveCurse {
+                count,
+                item_id,
+                buff_type,
+                ..
+            } => {
+                let count = event_runner::evaluate(count, self)? as i32;
+                let pools = event_runner::curse_pools(*buff_type);
+                match item_id {
+                    Some(item_id) => {
+                        let item_id = event_runner::evaluate(item_id, self)? as i32;
+                        self.remove_held(&pools, item_id, count);
+                    }
+                    None => self.remove_random(&pools, &[], count),
+                }
+            }
+            ConfigAction::ConfigLockCurse { lock, count } => {
+                let curses = self
+                    .run
+                    .inventory
+                    .get(&DungeonContentDropPoolType::Curse)
+                    .into_iter()
+                    .flat_map(HashMap::keys)
+                    .filter(|id| self.run.locked_curses.contains(id) != *lock)
+                    .copied()
+                    .choose_multiple(&mut self.run.rng, (*count).max(0) as usize);
+
+                for curse in curses {
+                    if *lock {
+                        self.run.locked_curses.insert(curse);
+                    } else {
+                        self.run.locked_curses.remove(&curse);
+                    }
+                }
+            }
+            ConfigAction::ConfigRandomArcana { arcana_count, .. } => {
+                let mut drawn = Vec::new();
+                for _ in 0..*arcana_count {
+                    drawn.push(self.draw(&DungeonContentDropPoolType::Arcana, &[], &[], &drawn)?);
+                }
+
+                for item_id in drawn {
+                    self.drop_item(DungeonContentDropPoolType::Arcana, item_id, 1);
+                }
+            }
+            ConfigAction::ConfigModifyProperty {
+                property,
+                count,
+                modify_type,
+                target,
+                ..
+            } => {
+                if property != "Hp" {
+                    bail!("ConfigModifyProperty: {property} is not supported");
+                }
+
+                for slot in self.target_slots(target.as_deref())? {
+                    self.cur_avatar = Some(slot);
+                    let value = event_runner::evaluate(count, self);
+                    self.cur_avatar = None;
+                    let value = value? as i32;
+
+                    self.change_party(|party| {
+                        let delta = match modify_type {
+                            Some(ConfigModifyType::Replace) => {
+                                value - party.hp(slot).map_or(0, |(hp, _)| hp)
+                            }
+                            _ => value,
+                        };
+                        party.change_hp(slot, delta)
+                    });
+                }
+            }
+            ConfigAction::ConfigModifySceneProperty {
+                property,
+                modify_type,
+                count,
+                ..
+            } => {
+                let Some(property) = hollow_party::scene_property(property) else {
+                    bail!("ConfigModifySceneProperty: unknown property {property}");
+                };
+                let value = event_runner::evaluate(count, self)? as i32;
+                let replace = matches!(modify_type, Some(ConfigModifyType::Replace));
+
+                self.change_party(|party| match property {
+                    ScenePropertyType::Stamina => {
+                        let stress = party.stress().0;
+                        party.change_stress(if replace { value - stress } else { value })
+                    }
+                    _ => {
+                        let value = if replace {
+                            value
+                        } else {
+                            party.scene_property(property.clone()) + value
+                        };
+                        party.set_scene_property(property, value);
+                        None
+                    }
+                });
+            }
+            // Every avatar standing gets the team's HP ratio.
+            ConfigAction::ConfigEvenHP => self.change_party(|party| {
+                let alive = party
+                    .members()
+                    .into_iter()
+                    .filter_map(|(slot, _)| Some((slot, party.hp(slot)?)))
+                    .filter(|(_, (hp, _))| *hp > 0)
+                    .collect::<Vec<_>>();
+                let hp_sum = alive.iter().map(|(_, (hp, _))| hp).sum::<i32>();
+                let hp_max_sum = alive.iter().map(|(_, (_, hp_max))| hp_max).sum::<i32>();
+                if hp_max_sum == 0 {
+                    return Vec::new();
+                }
+
+                alive
+                    .into_iter()
+                    .filter_map(|(slot, (hp, hp_max))| {
+                        party.change_hp(slot, hp_max * hp_sum / hp_max_sum - hp)
+                    })
+                    .collect()
+            }),
+            ConfigAction::ConfigChangeCharacter {
+                mode,
+                change_from,
+                change_to,
+            } => {
+                let Some(avatar_id) = change_to.first() else {
+                    bail!("ConfigChangeCharacter: nothing to change to");
+                };
+
+                let mut player = self.player.write();
+                let Some(mut party) = Party::of(&mut player) else {
+                    bail!("ConfigChangeCharacter: no team");
+                };
+                // Mode 2 changes the avatar in a team slot from 1, mode 4 a given avatar.
+                let slot = match mode {
+                    2 => i8::try_from(change_from - 1).ok(),
+                    4 => party.slot_of_avatar(*change_from),
+                    _ => bail!("ConfigChangeCharacter: mode {mode} is not supported"),
+                };
+                if slot
+                    .and_then(|slot| party.replace(slot, *avatar_id))
+                    .is_none()
+                {
+                    bail!("ConfigChangeCharacter: {change_from} isn't in the team");
+                }
+
+                self.avatar_map = Some(party.avatar_map());
+            }
+            ConfigAction::ConfigAddCharacter { add_to, .. } => {
+                let mut player = self.player.write();
+                let Some(mut party) = Party::of(&mut player) else {
+                    bail!("ConfigAddCharacter: no team");
+                };
+                for avatar_id in add_to {
+                    party.add(*avatar_id);
+                }
+
+                self.avatar_map = Some(party.avatar_map());
+            }
+            ConfigAction::ConfigSetHollowVariable { key, value, .. } => {
+                let key = expression::interpolate(key, self)?;
+                let value = event_runner::evaluate(value, self)? as i32;
+                self.run.variables.insert(key, value);
+            }
+            ConfigAction::ConfigSetSpecial {
+                special_name,
+                special_value,
+                ..
+            } => {
+                let name = expression::interpolate(special_name, self)?;
+                let value = event_runner::evaluate(special_value, self)? as i32;
+                self.specials.values.insert(name, value);
+            }
+            ConfigAction::ConfigRandSpecial {
+                min_count,
+                max_count,
+                special,
+            } => {
+                let min = event_runner::evaluate(min_count, self)? as i32;
+                let max = event_runner::evaluate(max_count, self)? as i32;
+                let name = expression::interpolate(special, self)?;
+                let value = self.random(min, max)?;
+                self.specials.values.insert(name, value);
+            }
+            ConfigAction::ConfigListSpecialOpt {
+                opt,
+                list_special_name,
+                return_special,
+                ..
+            } => {
+                let list = self
+                    .specials
+                    .lists
+                    .entry(list_special_name.clone())
+                    .or_default();
+                let value = match opt.as_str() {
+                    "Count" => list.len() as i32,
+                    "Pop" => match list.pop() {
+                        Some(value) => value,
+                        None => bail!("ConfigListSpecialOpt: {list_special_name} is empty"),
+                    },
+                    _ => bail!("ConfigListSpecialOpt: {opt} is not supported"),
+                };
+                self.specials.values.insert(return_special.clone(), value);
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+fn is_card(pool: &DungeonContentDropPoolType) -> bool {
+    matches!(
+        pool,
+        DungeonContentDropPoolType::Card | DungeonContentDropPoolType::BaneCard
+    )
+}
+
+// Items of these pools take effect as abilities for as long as they're held.
+fn is_ability(pool: &DungeonContentDropPoolType) -> bool {
+    matches!(
+        pool,
+        DungeonContentDropPoolType::Card
+            | DungeonContentDropPoolType::BaneCard
+            | DungeonContentDropPoolType::Arcana
+            | DungeonContentDropPoolType::Curse
+            | DungeonContentDropPoolType::Blessing
+    )
+}
+
+// The dungeon and hollow scene the player is in.
+fn cur_hollow(player: &mut PlayerInfo) -> Option<(&mut DungeonInfo, &mut SceneInfo)> {
+    let scene_uid = player.scene_uid?;
+    let dungeon_collection = player.dungeon_collection.as_mut()?;
+    let scene = dungeon_collection.scenes.as_mut()?.get_mut(&scene_uid)?;
+    let dungeon = dungeon_collection
+        .dungeons
+        .as_mut()?
+        .get_mut(&scene.get_dungeon_uid())?;
+
+    Some((dungeon, scene))
+}
+
+// Abilities are keyed by the item they come from, a stack of 0 removes it.
+fn sync_ability(scene: &mut SceneInfo, item_id: i32, stack_num: i32) {
+    let SceneInfo::Hollow { abilities_info, .. } = scene else {
+        return;
+    };
+
+    let uid = item_id as u64;
+    if stack_num <= 0 {
+        abilities_info.abilities.remove(uid);
+    } else if let Some(ability) = abilities_info.abilities.get_mut(&uid) {
+        ability.stack_num = stack_num;
+    } else {
+        abilities_info.sequence_no += 1;
+        abilities_info.abilities.insert(
+            uid,
+            AbilityInfo {
+                id: item_id.to_string(),
+                specials: phashmap![],
+                modifiers_info: phashmap![],
+                stack_num,
+                disabled: false,
+                sequence: abilities_info.sequence_no,
+            },
+        );
+    }
+}
+
+// Records the first time a card or curse is obtained, marked as new until it's seen.
+fn collect(player: &mut PlayerInfo, pool: &DungeonContentDropPoolType, item_id: i32) {
+    if *pool == DungeonContentDropPoolType::HollowItem {
+        if let Some(ramen_data) = player.ramen_data.as_mut() {
+            hollow_item::unlock_group(ramen_data, item_id);
+        }
+        return;
+    }
+
+    let Some(collect_map) = player.collect_map.as_mut() else {
+        return;
+    };
+    let (map, new_map) = match pool {
+        DungeonContentDropPoolType::Card
+        | DungeonContentDropPoolType::BaneCard
+        | DungeonContentDropPoolType::Arcana => {
+            (&mut collect_map.card_map, &mut collect_map.new_card_map)
+        }
+        DungeonContentDropPoolType::Curse | DungeonContentDropPoolType::Blessing => {
+            (&mut collect_map.curse_map, &mut collect_map.new_curse_map)
+        }
+        _ => return,
+    };
+    let (Some(map), Some(new_map)) = (map.as_mut(), new_map.as_mut()) else {
+        return;
+    };
+
+    if !map.iter().any(|id| *id == item_id) {
+        map.insert(item_id);
+        new_map.insert(item_id);
+    }
+}
+
+fn graph_template_id(
+    map: &HollowGridMapProtocolInfo,
+    run: &HollowRun,
+    event_graph_uid: u64,
+) -> Option<i32> {
+    if event_graph_uid == HOLLOW_ITEM_EVENT_GRAPH_UID {
+        return run.item_graph;
+    }
+
+    let info = map.grids.get(&(event_graph_uid as u16))?;
+    Some(info.grid.event_graph_info.hollow_event_template_id)
+}
+
+// Cards, curses and hollow items bought are held in the run, anything else is a reward.
+// What the client is shown of a graph with no event running.
+fn idle_event() -> EventInfo {
+    EventInfo {
+        id: 0,
+        cur_action_id: 0,
+        action_move_path: vec![],
+        state: EventState::Initing,
+        prev_state: EventState::Initing,
+        cur_action_info: ActionInfo::None {},
+        cur_action_state: ActionState::Init,
+        predicated_failed_actions: phashset![],
+        stack_frames: Vec::new(),
+    }
+}
+
+fn finished_events(events: &HashMap<u64, EventInfo>) -> HashSet<u64> {
+    events
+        .iter()
+        .filter(|(_, info)| matches!(info.state, EventState::Finished))
+        .map(|(uid, _)| *uid)
+        .collect()
+}
+
+fn grid_of(map: &HollowGridMapProtocolInfo, event_graph_uid: u64) -> Option<u16> {
+    u16::try_from(event_graph_uid)
+        .ok()
+        .filter(|uid| map.grids.get(uid).is_some())
+}
+
+fn hollow_shop_pool(shop_type: &HollowShopType, item_id: i32) -> DungeonContentDropPoolType {
+    match drop_pool::entry(item_id).and_then(|entry| drop_pool::pool_type(&entry.pool_type)) {
+        Some(pool) => pool,
+        None if *shop_type == HollowShopType::HollowItem => DungeonContentDropPoolType::HollowItem,
+        None => DungeonContentDropPoolType::Reward,
     }
 }
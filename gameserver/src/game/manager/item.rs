@@ -1,9 +1,9 @@
 use parking_lot::RwLock;
-use protocol::{ItemInfo, PlayerInfo};
+use protocol::{ErrorCode, ItemInfo, PlayerInfo};
 use qwer::{phashmap, PropertyHashMap};
 use std::sync::Arc;
 
-use crate::game::{util, PlayerOperationResult};
+use crate::game::{hollow_item, util};
 
 use super::UniqueIDManager;
 
@@ -20,24 +20,17 @@ impl ItemManager {
         }
     }
 
-    pub fn add_resource(&self, currency_id: i32, amount: i32) -> PlayerOperationResult<i32> {
+    pub fn add_resource(&self, currency_id: i32, amount: i32) -> i32 {
         let mut player_info = self.player_info.write();
 
-        for (uid, item) in player_info.items.as_mut().unwrap() {
+        for (_, item) in player_info.items.as_mut().unwrap() {
             if let ItemInfo::Resource { id, count, .. } = item {
                 if currency_id == *id {
                     *count += amount;
 
-                    return PlayerOperationResult::with_changes(
-                        *count,
-                        PlayerInfo {
-                            items: Some(PropertyHashMap::Modify {
-                                to_add: vec![(*uid, item.clone())],
-                                to_remove: Vec::new(),
-                            }),
-                            ..Default::default()
-                        },
-                    );
+                    let count = *count;
+                    drop(player_info);
+                    return count;
                 }
             }
         }
@@ -51,22 +44,13 @@ impl ItemManager {
             first_get_time: util::cur_timestamp_ms(),
         };
 
-        let items = player_info.items.as_mut().unwrap();
-        items.insert(uid, item.clone());
-
-        PlayerOperationResult::with_changes(
-            amount,
-            PlayerInfo {
-                items: Some(PropertyHashMap::Modify {
-                    to_add: vec![(uid, item)],
-                    to_remove: Vec::new(),
-                }),
-                ..Default::default()
-            },
-        )
+        player_info.items.as_mut().unwrap().insert(uid, item);
+
+        drop(player_info);
+        amount
     }
 
-    pub fn unlock_avatar(&self, id: i32) -> PlayerOperationResult<u64> {
+    pub fn unlock_avatar(&self, id: i32) -> u64 {
         let uid = self.uid_mgr.next();
 
         let avatar = ItemInfo::Avatar {
@@ -86,32 +70,43 @@ impl ItemManager {
         };
 
         // Unlock & equip default weapon
-        let weapon_uid = self.unlock_weapon(10012).take();
+        let weapon_uid = self.unlock_weapon(10012);
         self.equip_weapon(weapon_uid, uid);
 
-        let mut player_info = self.player_info.write();
-        let items = player_info.items.as_mut().unwrap();
-        items.insert(uid, avatar.clone());
+        self.player_info
+            .write()
+            .items
+            .as_mut()
+            .unwrap()
+            .insert(uid, avatar);
 
-        PlayerOperationResult::with_changes(
-            uid,
-            PlayerInfo {
-                items: Some(PropertyHashMap::Modify {
-                    to_add: vec![
-                        (uid, avatar),
-                        (weapon_uid, items.get(&weapon_uid).unwrap().clone()),
-                    ],
-                    to_remove: vec![],
-                }),
-                ..Default::default()
-            },
-        )
+        uid
     }
 
-    pub fn unlock_weapon(&self, id: i32) -> PlayerOperationResult<u64> {
+    pub fn make_initiative_item(&self, item_id: i32) -> Result<(), ErrorCode> {
         let mut player_info = self.player_info.write();
-        let items = player_info.items.as_mut().unwrap();
+        let ramen_data = player_info.ramen_data.as_mut().unwrap();
+
+        let result = hollow_item::check_make(ramen_data, item_id);
+        if result.is_ok() {
+            ramen_data
+                .unlock_initiative_item
+                .as_mut()
+                .unwrap()
+                .insert(item_id);
+            ramen_data
+                .new_unlock_initiative_item
+                .as_mut()
+                .unwrap()
+                .insert(item_id);
+            *ramen_data.make_hollow_item_times.get_or_insert(0) += 1;
+        }
+
+        drop(player_info);
+        result
+    }
 
+    pub fn unlock_weapon(&self, id: i32) -> u64 {
         let uid = self.uid_mgr.next();
 
         let weapon = ItemInfo::Weapon {
@@ -128,42 +123,27 @@ impl ItemManager {
             refine_level: 1,
         };
 
-        items.insert(uid, weapon.clone());
-        PlayerOperationResult::with_changes(
-            uid,
-            PlayerInfo {
-                items: Some(PropertyHashMap::Modify {
-                    to_add: vec![(uid, weapon)],
-                    to_remove: Vec::new(),
-                }),
-                ..Default::default()
-            },
-        )
+        self.player_info
+            .write()
+            .items
+            .as_mut()
+            .unwrap()
+            .insert(uid, weapon);
+
+        uid
     }
 
-    pub fn equip_weapon(
-        &self,
-        weapon_uid: u64,
-        equip_avatar_uid: u64,
-    ) -> PlayerOperationResult<bool> {
+    pub fn equip_weapon(&self, weapon_uid: u64, equip_avatar_uid: u64) -> bool {
         let mut player_info = self.player_info.write();
         let items = player_info.items.as_mut().unwrap();
 
         let Some(ItemInfo::Weapon { avatar_uid, .. }) = items.get_mut(&weapon_uid) else {
-            return PlayerOperationResult::ret(false);
+            return false;
         };
 
         *avatar_uid = equip_avatar_uid;
 
-        PlayerOperationResult::with_changes(
-            true,
-            PlayerInfo {
-                items: Some(PropertyHashMap::Modify {
-                    to_add: vec![(weapon_uid, items.get(&weapon_uid).unwrap().clone())],
-                    to_remove: Vec::new(),
-                }),
-                ..Default::default()
-            },
-        )
+        drop(player_info);
+        true
     }
 }
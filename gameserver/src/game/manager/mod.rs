@@ -9,7 +9,7 @@ mod unlock;
 mod yorozuya_quest;
 
 pub use dungeon::DungeonManager;
-pub use hollow_grid::HollowGridManager;
+pub use hollow_grid::{EventGraphRun, HollowGridManager};
 pub use item::ItemManager;
 pub use quest::QuestManager;
 pub use scene_unit::SceneUnitManager;
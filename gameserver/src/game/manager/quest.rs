@@ -1,9 +1,6 @@
 use std::sync::Arc;
 
 use parking_lot::RwLock;
-use qwer::PropertyDoubleKeyHashMap;
-
-use crate::game::PlayerOperationResult;
 
 use super::UniqueIDManager;
 use protocol::*;
@@ -18,7 +15,7 @@ impl QuestManager {
         Self { uid_mgr, player }
     }
 
-    pub fn add_world_quest(&self, quest: QuestInfo) -> PlayerOperationResult<u64> {
+    pub fn add_world_quest(&self, quest: QuestInfo) -> u64 {
         let mut world_quest_collection_uid = self
             .player
             .read()
@@ -42,33 +39,19 @@ impl QuestManager {
         self.add_quest_to_collection(world_quest_collection_uid, quest)
     }
 
-    pub fn add_quest_to_collection(
-        &self,
-        collection_uid: u64,
-        mut quest: QuestInfo,
-    ) -> PlayerOperationResult<u64> {
-        let mut player = self.player.write();
-        let quest_data = player.quest_data.as_mut().unwrap();
+    pub fn add_quest_to_collection(&self, collection_uid: u64, mut quest: QuestInfo) -> u64 {
         quest.set_collection_uid(collection_uid);
 
-        quest_data
+        self.player
+            .write()
+            .quest_data
+            .as_mut()
+            .unwrap()
             .quests
             .as_mut()
             .unwrap()
-            .insert(collection_uid, quest.get_id(), quest.clone());
+            .insert(collection_uid, quest.get_id(), quest);
 
-        PlayerOperationResult::with_changes(
-            collection_uid,
-            PlayerInfo {
-                quest_data: Some(QuestData {
-                    quests: Some(PropertyDoubleKeyHashMap::Modify {
-                        to_add: vec![(collection_uid, quest.get_id(), quest)],
-                        to_remove: Vec::new(),
-                    }),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-        )
+        collection_uid
     }
 }
@@ -1,5 +1,11 @@
 mod context;
+mod drop_pool;
+mod event_runner;
+mod expression;
+mod hollow_item;
+mod hollow_party;
+mod hollow_shop;
 pub mod manager;
 pub mod util;
 
-pub use context::{GameContext, PlayerOperationResult};
+pub use context::GameContext;
@@ -0,0 +1,64 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use qwer::{CaptureDirection, CaptureRecord, CaptureWriter, ProtocolHeader};
+
+use super::ChannelID;
+
+pub struct SessionCapture(Mutex<CaptureWriter<BufWriter<File>>>);
+
+impl SessionCapture {
+    pub fn create(dir: &str, client_addr: SocketAddr, channel_id: ChannelID) -> Option<Self> {
+        let path = Path::new(dir).join(format!(
+            "{}_{}.cap",
+            timestamp_ms(),
+            client_addr.to_string().replace([':', '.'], "_")
+        ));
+
+        let writer = fs::create_dir_all(dir)
+            .and_then(|()| File::create(&path))
+            .and_then(|file| CaptureWriter::new(BufWriter::new(file), channel_id.0));
+
+        match writer {
+            Ok(writer) => {
+                tracing::info!("Recording session to {}", path.display());
+                Some(Self(Mutex::new(writer)))
+            }
+            Err(err) => {
+                tracing::error!("Failed to create capture file {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    pub fn record(
+        &self,
+        direction: CaptureDirection,
+        header: &ProtocolHeader,
+        protocol_id: u16,
+        payload: &[u8],
+    ) {
+        let record = CaptureRecord {
+            direction,
+            timestamp_ms: timestamp_ms(),
+            header: header.clone(),
+            protocol_id,
+            payload: payload.to_vec(),
+        };
+
+        if let Err(err) = self.0.lock().write_record(&record) {
+            tracing::warn!("Failed to write capture record: {err}");
+        }
+    }
+}
+
+fn timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
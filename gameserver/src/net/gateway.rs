@@ -1,25 +1,55 @@
+use std::sync::Arc;
+
 use anyhow::Result;
-use tokio::net::TcpListener;
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, TcpStream};
 use tracing::Instrument;
 
+use crate::config::CONFIGURATION;
 use crate::log_error;
 
-use super::NetworkSession;
+use super::capture::SessionCapture;
+use super::{middleware, ChannelID, NetworkSession};
 
 pub async fn listen(bind_addr: &str) -> Result<()> {
     let listener = TcpListener::bind(bind_addr).await?;
     tracing::info!("Listening at {bind_addr}");
 
+    let middlewares = Arc::new(middleware::default_chain());
+
     loop {
-        let Ok((client_socket, client_addr)) = listener.accept().await else {
+        let Ok((mut client_socket, client_addr)) = listener.accept().await else {
             continue;
         };
 
         tracing::info!("New session from {client_addr}");
 
-        let mut session = NetworkSession::new(client_socket);
+        let middlewares = middlewares.clone();
         tokio::spawn(
             async move {
+                let channel_id = match read_handshake(&mut client_socket).await {
+                    Ok(Some(channel_id)) => channel_id,
+                    Ok(None) => return,
+                    Err(err) => {
+                        tracing::error!("Handshake with {client_addr} failed: {err}");
+                        return;
+                    }
+                };
+
+                tracing::info!("Session from {client_addr} joined channel {channel_id:?}");
+
+                let capture = CONFIGURATION
+                    .capture_dir
+                    .as_deref()
+                    .and_then(|dir| SessionCapture::create(dir, client_addr, channel_id));
+
+                let mut session = NetworkSession::new(
+                    client_socket,
+                    CONFIGURATION.packet_limits.codec(),
+                    channel_id,
+                    middlewares,
+                    capture,
+                );
                 log_error!(
                     "Session from {client_addr} disconnected",
                     format!("An error occurred while processing session ({client_addr})"),
@@ -30,3 +60,11 @@ pub async fn listen(bind_addr: &str) -> Result<()> {
         );
     }
 }
+
+async fn read_handshake(client_socket: &mut TcpStream) -> Result<Option<ChannelID>> {
+    match client_socket.read_u16_le().await {
+        Ok(channel_id) => Ok(Some(ChannelID(channel_id))),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -3,6 +3,7 @@ use qwer::{phashmap, phashset, PropertyHashMap, PropertyHashSet};
 use std::collections::HashMap;
 
 use crate::data;
+use crate::game::manager::EventGraphRun;
 
 use super::*;
 
@@ -15,20 +16,11 @@ pub async fn on_rpc_hollow_move(
     let destination_pos = *arg.positions.last().unwrap();
     let scene_uid = session.ns_prop_mgr.player_info.read().scene_uid.unwrap();
 
-    let (ptc_hollow_grid, ptc_sync_hollow_event) = session
+    let run = session
         .context
         .hollow_grid_manager
         .move_to(destination_pos, scene_uid);
-
-    session
-        .push_rpc_arg(PTC_HOLLOW_GRID_ID, ptc_hollow_grid)
-        .await?;
-
-    if let Some(ptc_sync_hollow_event) = ptc_sync_hollow_event {
-        session
-            .push_rpc_arg(PTC_SYNC_HOLLOW_EVENT_INFO_ID, ptc_sync_hollow_event)
-            .await?;
-    }
+    send_event_graph_run(session, run).await?;
 
     let pos = PtcPositionInHollowChangedArg {
         player_uid: session.player_uid().raw(),
@@ -53,41 +45,20 @@ pub async fn on_rpc_end_battle(
     tracing::info!("RpcEndBattle: {:?}", &arg);
 
     let player_uid = session.player_uid().raw();
-    let (sync_event, hollow_finished) = session.context.hollow_grid_manager.battle_finished();
+    let (hp_changes, party_wiped) = session
+        .context
+        .dungeon_manager
+        .battle_hp(&arg.avatar_properties);
+    send_hp_or_stress_changes(session, hp_changes).await?;
 
-    if !hollow_finished {
-        session
-            .push_rpc_arg(PTC_SYNC_HOLLOW_EVENT_INFO_ID, sync_event)
-            .await?;
+    if !arg.success || party_wiped {
+        finish_hollow(session, false, phashmap![]).await?;
     } else {
-        let _ = session
-            .context
-            .dungeon_manager
-            .hollow_finished()
-            .send_changes(session)
-            .await?;
-
-        let ptc_dungeon_quest_finished = PtcDungeonQuestFinishedArg {
-            player_uid,
-            quest_id: 1001000101,
-            success: true,
-            reward_items: phashmap![],
-            statistics: phashmap![(QuestStatisticsType::ArrivedLevel, 1)],
-        };
-
-        session
-            .push_rpc_arg(PTC_DUNGEON_QUEST_FINISHED_ID, ptc_dungeon_quest_finished)
-            .await?;
+        let run = session.context.hollow_grid_manager.battle_finished()?;
+        send_event_graph_run(session, run).await?;
     }
 
-    let ptc_enter_scene = session
-        .context
-        .dungeon_manager
-        .leave_battle()
-        .unwrap()
-        .send_changes(session)
-        .await?
-        .clone();
+    let ptc_enter_scene = session.context.dungeon_manager.leave_battle().unwrap();
 
     session
         .push_rpc_arg(
@@ -159,99 +130,251 @@ pub async fn on_rpc_run_hollow_event_graph(
             .push_rpc_arg(PTC_SYNC_HOLLOW_EVENT_INFO_ID, finish_perform)
             .await?;
 
-        let (ptc_hollow_grid, ptc_sync_hollow_event) =
-            session.context.hollow_grid_manager.move_to(22, scene_uid);
+        let run = session.context.hollow_grid_manager.move_to(22, scene_uid);
+        send_event_graph_run(session, run).await?;
+    } else {
+        let Some(run) = session.context.hollow_grid_manager.run_event_graph(
+            arg.event_graph_uid,
+            arg.event_id,
+            arg.move_path.clone(),
+        ) else {
+            tracing::warn!(
+                "event {} of event graph {} has finished",
+                arg.event_id,
+                arg.event_graph_uid
+            );
+            return Ok(RpcRunHollowEventGraphRet::error(
+                ErrorCode::NodeRunFailure,
+                Vec::new(),
+            ));
+        };
+        send_event_graph_run(session, run).await?;
+    }
+
+    Ok(RpcRunHollowEventGraphRet::new())
+}
+
+pub async fn on_rpc_make_choice_of_event(
+    session: &mut NetworkSession,
+    arg: &RpcMakeChoiceOfEventArg,
+) -> Result<RpcMakeChoiceOfEventRet> {
+    tracing::info!("Make choice of event {:?}", arg);
+
+    let Some(run) = session
+        .context
+        .hollow_grid_manager
+        .make_choice(arg.event_graph_uid, arg.choice_id)
+    else {
+        tracing::warn!(
+            "choice {} wasn't offered by event graph {}",
+            arg.choice_id,
+            arg.event_graph_uid
+        );
+        return Ok(RpcMakeChoiceOfEventRet::error(
+            ErrorCode::NodeRunFailure,
+            Vec::new(),
+        ));
+    };
+
+    send_event_graph_run(session, run).await?;
+    Ok(RpcMakeChoiceOfEventRet::new())
+}
+
+pub async fn on_rpc_remove_hollow_curse(
+    session: &mut NetworkSession,
+    arg: &RpcRemoveHollowCurseArg,
+) -> Result<RpcRemoveHollowCurseRet> {
+    tracing::info!("Remove hollow curse {:?}", arg);
+
+    let Some(run) = session
+        .context
+        .hollow_grid_manager
+        .remove_curses(&arg.curse_uids)
+    else {
+        tracing::warn!("curses {:?} can't be removed", arg.curse_uids);
+        return Ok(RpcRemoveHollowCurseRet::error(
+            ErrorCode::NodeRunFailure,
+            Vec::new(),
+        ));
+    };
 
+    send_event_graph_run(session, run).await?;
+    Ok(RpcRemoveHollowCurseRet::new())
+}
+
+pub async fn on_rpc_hollow_shopping(
+    session: &mut NetworkSession,
+    arg: &RpcHollowShoppingArg,
+) -> Result<RpcHollowShoppingRet> {
+    tracing::info!("Hollow shopping {:?}", arg);
+
+    let Some(run) =
         session
-            .push_rpc_arg(PTC_HOLLOW_GRID_ID, ptc_hollow_grid)
-            .await?;
-        if let Some(ptc_sync_hollow_event) = ptc_sync_hollow_event {
-            session
-                .push_rpc_arg(PTC_SYNC_HOLLOW_EVENT_INFO_ID, ptc_sync_hollow_event)
-                .await?;
-        }
-    } else {
-        let (sync_hollow_event, hollow_grid, trigger_battle_id, hollow_finished) = session
             .context
             .hollow_grid_manager
-            .run_event_graph(arg.event_graph_uid, arg.event_id, arg.move_path.clone());
+            .buy(arg.event_graph_uid, &arg.shop_type, arg.goods_uid)
+    else {
+        tracing::warn!(
+            "goods {} of {:?} can't be bought from event graph {}",
+            arg.goods_uid,
+            arg.shop_type,
+            arg.event_graph_uid
+        );
+        return Ok(RpcHollowShoppingRet::error(
+            ErrorCode::NodeRunFailure,
+            Vec::new(),
+        ));
+    };
+
+    send_event_graph_run(session, run).await?;
+    Ok(RpcHollowShoppingRet::new())
+}
+
+pub async fn on_rpc_use_initiative_item(
+    session: &mut NetworkSession,
+    arg: &RpcUseInitiativeItemArg,
+) -> Result<RpcUseInitiativeItemRet> {
+    tracing::info!("Use initiative item {:?}", arg);
+
+    let Some(run) = session
+        .context
+        .hollow_grid_manager
+        .use_item(arg.initiative_item)
+    else {
+        tracing::warn!("initiative item {} can't be used", arg.initiative_item);
+        return Ok(RpcUseInitiativeItemRet::error(
+            ErrorCode::NodeRunFailure,
+            Vec::new(),
+        ));
+    };
+
+    send_event_graph_run(session, run).await?;
+    Ok(RpcUseInitiativeItemRet::new())
+}
+
+pub async fn on_rpc_make_initiative_item(
+    session: &mut NetworkSession,
+    arg: &RpcMakeInitiativeItemArg,
+) -> Result<RpcMakeInitiativeItemRet> {
+    tracing::info!("Make initiative item {:?}", arg);
+
+    let made = session
+        .context
+        .item_manager
+        .make_initiative_item(arg.initiative_item);
+
+    Ok(match made {
+        Ok(()) => RpcMakeInitiativeItemRet::new(),
+        Err(error_code) => RpcMakeInitiativeItemRet::error(error_code, Vec::new()),
+    })
+}
+
+async fn send_event_graph_run(session: &NetworkSession, run: EventGraphRun) -> Result<()> {
+    let EventGraphRun {
+        sync_events,
+        grids,
+        trigger_battle_id,
+        hollow_finished,
+        disabled_cards,
+        card_genre_tips,
+        hp_or_stress_changes,
+        avatar_map,
+        party_wiped,
+    } = run;
+
+    send_hp_or_stress_changes(session, hp_or_stress_changes).await?;
+    if let Some(avatar_map) = avatar_map {
+        let ptc_avatar_map_changed = PtcAvatarMapChangedArg {
+            player_uid: session.player_uid().raw(),
+            avatar_map,
+        };
 
-        if !hollow_finished {
-            session
-                .push_rpc_arg(PTC_SYNC_HOLLOW_EVENT_INFO_ID, sync_hollow_event)
-                .await?;
-        }
         session
-            .push_rpc_arg(PTC_HOLLOW_GRID_ID, hollow_grid)
+            .push_rpc_arg(PTC_AVATAR_MAP_CHANGED_ID, ptc_avatar_map_changed)
             .await?;
+    }
 
-        if hollow_finished {
-            let _ = session
-                .context
-                .dungeon_manager
-                .hollow_finished()
-                .send_changes(session)
-                .await?;
+    if !disabled_cards.is_empty() {
+        let ptc_card_disable = PtcCardDisableArg {
+            player_uid: session.player_uid().raw(),
+            card_uids: disabled_cards,
+        };
 
-            let ptc_dungeon_quest_finished = PtcDungeonQuestFinishedArg {
-                player_uid: session.player_uid().raw(),
-                quest_id: 1001000101,
-                success: true,
-                reward_items: phashmap![],
-                statistics: phashmap![],
-            };
+        session
+            .push_rpc_arg(PTC_CARD_DISABLE_ID, ptc_card_disable)
+            .await?;
+    }
+    for genre in card_genre_tips {
+        session
+            .push_rpc_arg(
+                PTC_SHOW_CARD_GENRE_TIPS_ID,
+                PtcShowCardGenreTipsArg { genre },
+            )
+            .await?;
+    }
 
+    if !hollow_finished && !party_wiped {
+        for sync_event in sync_events {
             session
-                .push_rpc_arg(PTC_DUNGEON_QUEST_FINISHED_ID, ptc_dungeon_quest_finished)
+                .push_rpc_arg(PTC_SYNC_HOLLOW_EVENT_INFO_ID, sync_event)
                 .await?;
         }
+    }
+    session.push_rpc_arg(PTC_HOLLOW_GRID_ID, grids).await?;
 
-        if let Some(trigger_battle_id) = trigger_battle_id {
-            let hollow_uid = *session
-                .ns_prop_mgr
-                .player_info
-                .read()
-                .scene_uid
-                .as_ref()
-                .unwrap();
-            let battle_scene_uid = session
-                .context
-                .dungeon_manager
-                .create_fight(trigger_battle_id, hollow_uid)
-                .send_changes(session)
-                .await?;
+    if party_wiped {
+        return finish_hollow(session, false, phashmap![]).await;
+    }
+    if hollow_finished {
+        finish_hollow(
+            session,
+            true,
+            phashmap![(QuestStatisticsType::ArrivedLevel, 1)],
+        )
+        .await?;
+    }
 
-            let ptc_position_in_hollow_changed = PtcPositionInHollowChangedArg {
-                player_uid: session.player_uid().raw(),
-                hollow_level: 1,
-                position: session
-                    .context
-                    .hollow_grid_manager
-                    .get_cur_position_in_hollow(),
-            };
+    if let Some(trigger_battle_id) = trigger_battle_id {
+        let hollow_uid = *session
+            .ns_prop_mgr
+            .player_info
+            .read()
+            .scene_uid
+            .as_ref()
+            .unwrap();
+        let battle_scene_uid = session
+            .context
+            .dungeon_manager
+            .create_fight(trigger_battle_id, hollow_uid);
 
-            session
-                .push_rpc_arg(
-                    PTC_POSITION_IN_HOLLOW_CHANGED_ID,
-                    ptc_position_in_hollow_changed,
-                )
-                .await?;
+        let ptc_position_in_hollow_changed = PtcPositionInHollowChangedArg {
+            player_uid: session.player_uid().raw(),
+            hollow_level: 1,
+            position: session
+                .context
+                .hollow_grid_manager
+                .get_cur_position_in_hollow(),
+        };
 
-            session
-                .push_rpc_arg(
-                    PTC_ENTER_SCENE_ID,
-                    session
-                        .context
-                        .dungeon_manager
-                        .enter_battle(battle_scene_uid)
-                        .send_changes(session)
-                        .await?,
-                )
-                .await?;
-        }
+        session
+            .push_rpc_arg(
+                PTC_POSITION_IN_HOLLOW_CHANGED_ID,
+                ptc_position_in_hollow_changed,
+            )
+            .await?;
+
+        session
+            .push_rpc_arg(
+                PTC_ENTER_SCENE_ID,
+                session
+                    .context
+                    .dungeon_manager
+                    .enter_battle(battle_scene_uid),
+            )
+            .await?;
     }
 
-    Ok(RpcRunHollowEventGraphRet::new())
+    Ok(())
 }
 
 pub async fn on_rpc_start_hollow_quest(
@@ -298,42 +421,35 @@ pub async fn on_rpc_start_hollow_quest(
         .sorted_by_key(|kv| kv.0)
         .map(|(_idx, uid)| *uid)
         .collect::<Vec<_>>();
-    let (dungeon_uid, scene_uid) = session
-        .context
-        .dungeon_manager
-        .create_hollow(10001, 10010001, &avatars)
-        .send_changes(session)
-        .await?;
-
-    session
-        .context
-        .quest_manager
-        .add_quest_to_collection(
-            dungeon_uid,
-            QuestInfo::DungeonInner {
-                id: 1001000101,
-                finished_count: 0,
-                collection_uid: 0,
-                progress: 0,
-                parent_quest_id: 10010001,
-                state: QuestState::InProgress,
-                finish_condition_progress: phashmap![],
-                progress_time: 2111605,
-                sort_id: 2000,
-            },
-        )
-        .send_changes(session)
-        .await?;
+    let (dungeon_uid, scene_uid) = session.context.dungeon_manager.create_hollow(
+        10001,
+        10010001,
+        &avatars,
+        arg.initiative_item,
+    );
+
+    session.context.quest_manager.add_quest_to_collection(
+        dungeon_uid,
+        QuestInfo::DungeonInner {
+            id: 1001000101,
+            finished_count: 0,
+            collection_uid: 0,
+            progress: 0,
+            parent_quest_id: 10010001,
+            state: QuestState::InProgress,
+            finish_condition_progress: phashmap![],
+            progress_time: 2111605,
+            sort_id: 2000,
+        },
+    );
 
     let ptc_enter_scene = session
         .context
         .dungeon_manager
         .enter_scene(scene_uid)?
-        .send_changes(session)
-        .await?
         .clone();
 
-    session.context.hollow_grid_manager.init_default_map();
+    let created = session.context.hollow_grid_manager.init_default_map();
 
     session
         .push_rpc_arg(
@@ -382,9 +498,74 @@ pub async fn on_rpc_start_hollow_quest(
     session
         .push_rpc_arg(PTC_SYNC_HOLLOW_EVENT_INFO_ID, ptc_sync_hollow_event_info)
         .await?;
+    send_event_graph_run(session, created).await?;
 
     session
         .push_rpc_arg(PTC_ENTER_SCENE_ID, ptc_enter_scene)
         .await?;
     Ok(RpcStartHollowQuestRet::new())
 }
+
+// Ends the hollow quest, the rewards collected are only granted when it succeeded.
+async fn finish_hollow(
+    session: &NetworkSession,
+    success: bool,
+    statistics: PropertyHashMap<QuestStatisticsType, u64>,
+) -> Result<()> {
+    let _ = session.context.dungeon_manager.hollow_finished(success);
+
+    let ptc_dungeon_quest_finished = PtcDungeonQuestFinishedArg {
+        player_uid: session.player_uid().raw(),
+        quest_id: 1001000101,
+        success,
+        reward_items: if success {
+            grant_hollow_rewards(session).await?
+        } else {
+            phashmap![]
+        },
+        statistics,
+    };
+
+    session
+        .push_rpc_arg(PTC_DUNGEON_QUEST_FINISHED_ID, ptc_dungeon_quest_finished)
+        .await
+}
+
+async fn send_hp_or_stress_changes(
+    session: &NetworkSession,
+    changes: Vec<PtcHpOrStressChangedArg>,
+) -> Result<()> {
+    for change in changes {
+        if change.avatar_uid != 0 {
+            let ptc_property_changed = PtcPropertyChangedArg {
+                scene_unit_uid: change.avatar_uid,
+                is_partial: true,
+                changed_properties: phashmap![(PropertyType::Hp as u16, change.hp)],
+            };
+
+            session
+                .push_rpc_arg(PTC_PROPERTY_CHANGED_ID, ptc_property_changed)
+                .await?;
+        }
+
+        session
+            .push_rpc_arg(PTC_HP_OR_STRESS_CHANGED_ID, change)
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Moves the rewards collected in the hollow to the player's items.
+async fn grant_hollow_rewards(
+    session: &NetworkSession,
+) -> Result<PropertyHashMap<u64, ItemIDCount>> {
+    let mut reward_items = phashmap![];
+    for (id, count) in session.context.hollow_grid_manager.take_rewards() {
+        session.context.item_manager.add_resource(id, count);
+
+        reward_items.insert(id as u64, ItemIDCount { id, count });
+    }
+
+    Ok(reward_items)
+}
@@ -2,6 +2,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::*;
 use crate::{
+    config::CONFIGURATION,
     game::util,
     net::session::{AccountUID, PlayerUID},
 };
@@ -9,19 +10,43 @@ use crate::{
 const DEFAULT_ACCOUNT_ID: u64 = 1;
 
 pub async fn on_rpc_login(session: &NetworkSession, arg: &RpcLoginArg) -> Result<RpcLoginRet> {
-    tracing::info!("Received rpc login arg: {}", arg.account_name);
+    tracing::info!(
+        "Received rpc login arg: {} (channel {:?})",
+        arg.account_name,
+        session.channel_id()
+    );
 
-    match session.logged_in(
-        AccountUID(DEFAULT_ACCOUNT_ID),
-        util::create_default_account(DEFAULT_ACCOUNT_ID),
-    ) {
+    let version = client_protocol_version(&arg.client_protocol_sign);
+    tracing::info!("Client uses protocol version {}", version.name);
+
+    match session
+        .logged_in(
+            AccountUID(DEFAULT_ACCOUNT_ID),
+            util::create_default_account(DEFAULT_ACCOUNT_ID),
+        )
+        .and_then(|()| session.set_protocol_version(version))
+    {
         Ok(()) => Ok(RpcLoginRet::new(
-            session.ns_prop_mgr.serialize_account_info(),
+            session
+                .ns_prop_mgr
+                .serialize_account_info(session.protocol_version()),
         )),
         Err(_) => Ok(RpcLoginRet::error(ErrorCode::RepeatedLogin, Vec::new())),
     }
 }
 
+fn client_protocol_version(protocol_sign: &str) -> &'static ProtocolVersion {
+    let name = CONFIGURATION
+        .client_versions
+        .get(protocol_sign)
+        .map_or(protocol_sign, String::as_str);
+
+    lookup_version(name).unwrap_or_else(|| {
+        tracing::warn!("Unknown client protocol sign {protocol_sign}, using the latest protocol");
+        ProtocolVersion::latest()
+    })
+}
+
 pub async fn on_rpc_create_player(
     session: &NetworkSession,
     _arg: &RpcCreatePlayerArg,
@@ -29,13 +29,22 @@ macro_rules! protocol_handlers {
     ($($name:ident;)*) => {
         pub trait ProtocolHandler {
             async fn on_message(session: &mut NetworkSession, protocol_id: u16, payload: Vec<u8>) -> Result<()> {
-                use ::qwer::OctData;
                 use ::tracing::Instrument;
+                if !session.request_middlewares().is_empty() {
+                    tracing::debug!("Message with protocol id {protocol_id} carries middlewares {:?}", session.request_middlewares());
+                }
+
                 paste! {
                     match protocol_id {
-                        $(::protocol::[<$name:snake:upper _ID>] => {
-                                let arg = ::protocol::[<$name Arg>]::unmarshal_from(&mut &payload[..], 0)?;
-                                let ret = [<on_$name:snake>](session, &arg)
+                        $(<::protocol::[<$name Arg>] as ::protocol::ProtocolArg>::ID => {
+                                let arg = match session.protocol_version().decode::<::protocol::[<$name Arg>]>(&payload) {
+                                    Ok(arg) => arg,
+                                    Err(err) => {
+                                        tracing::error!("Failed to decode {}: {err}", stringify!([<$name Arg>]));
+                                        return Err(err.into());
+                                    }
+                                };
+                                let ret: <::protocol::[<$name Arg>] as ::protocol::ProtocolRequest>::Ret = [<on_$name:snake>](session, &arg)
                                     .instrument(tracing::info_span!(stringify!([<on_$name:snake>]), protocol_id = protocol_id))
                                     .await?;
 
@@ -44,7 +53,10 @@ macro_rules! protocol_handlers {
                             }
                         )*
                         _ => {
-                            tracing::warn!("Message with protocol id {protocol_id} wasn't handled!");
+                            match ::protocol::lookup_protocol(protocol_id) {
+                                Some(entry) => tracing::warn!("Message {} ({protocol_id}) wasn't handled!", entry.name),
+                                None => tracing::warn!("Message with protocol id {protocol_id} wasn't handled!"),
+                            }
                             Ok(())
                         },
                     }
@@ -75,15 +87,20 @@ protocol_handlers! {
     RpcFinishEventGraphPerformShow;
     RpcGetPlayerMails;
     RpcHollowMove;
+    RpcHollowShopping;
     RpcInteractWithUnit;
     RpcKeepAlive;
     RpcLeaveCurDungeon;
+    RpcMakeChoiceOfEvent;
+    RpcMakeInitiativeItem;
     RpcModNickName;
     RpcPerformEnd;
     RpcPerformJump;
     RpcPerformTrigger;
+    RpcRemoveHollowCurse;
     RpcRunEventGraph;
     RpcRunHollowEventGraph;
     RpcSavePosInMainCity;
     RpcStartHollowQuest;
+    RpcUseInitiativeItem;
 }
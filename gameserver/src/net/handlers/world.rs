@@ -211,8 +211,7 @@ pub async fn enter_main_city(session: &NetworkSession) -> Result<()> {
             session
                 .context
                 .dungeon_manager
-                .enter_scene_section(hall_scene_uid, 2)
-                .take(),
+                .enter_scene_section(hall_scene_uid, 2),
         )
         .await?;
 
@@ -226,12 +225,7 @@ pub async fn enter_main_city(session: &NetworkSession) -> Result<()> {
     session
         .push_rpc_arg(
             PTC_ENTER_SCENE_ID,
-            session
-                .context
-                .dungeon_manager
-                .enter_main_city()?
-                .send_changes(session)
-                .await?,
+            session.context.dungeon_manager.enter_main_city()?,
         )
         .await
 }
@@ -248,9 +242,9 @@ pub async fn on_rpc_enter_world(
     }
 
     let item_manager = &session.context.item_manager;
-    item_manager.add_resource(501, 120);
-    item_manager.add_resource(10, 228);
-    item_manager.add_resource(100, 1337);
+    for (currency_id, amount) in [(501, 120), (10, 228), (100, 1337)] {
+        item_manager.add_resource(currency_id, amount);
+    }
 
     for avatar_id in data::iter_avatar_config_collection()
         .filter(|c| c.camp != 0)
@@ -305,16 +299,14 @@ pub async fn on_rpc_enter_world(
     if CONFIGURATION.skip_tutorial {
         Box::pin(enter_main_city(session)).await?;
     } else {
-        let fresh_scene_uid = session.context.dungeon_manager.create_fresh().take();
+        let fresh_scene_uid = session.context.dungeon_manager.create_fresh();
         session
             .push_rpc_arg(
                 PTC_ENTER_SCENE_ID,
                 session
                     .context
                     .dungeon_manager
-                    .enter_scene(fresh_scene_uid)
-                    .unwrap()
-                    .take(),
+                    .enter_scene(fresh_scene_uid)?,
             )
             .await?;
     }
@@ -329,8 +321,12 @@ pub async fn on_rpc_enter_world(
         )
         .await?;
 
+    // The ret carries the whole player, setting it up mustn't go out as a delta ahead of it.
+    session.discard_player_info_changes().await;
     Ok(RpcEnterWorldRet::new(
-        session.ns_prop_mgr.serialize_player_info(),
+        session
+            .ns_prop_mgr
+            .serialize_player_info(session.protocol_version()),
     ))
 }
 
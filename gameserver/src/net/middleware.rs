@@ -0,0 +1,99 @@
+use protocol::{PTC_GET_SERVER_TIMESTAMP_ID, RPC_KEEP_ALIVE_ID, RPC_LOGIN_ID};
+use qwer::{MiddlewareEntry, RequestBody};
+
+use super::NetworkSession;
+
+#[derive(Debug)]
+pub struct MiddlewareRejection {
+    pub middleware_id: u16,
+    pub error_code: u16,
+}
+
+pub trait Middleware: Send + Sync {
+    fn id(&self) -> u16;
+
+    /// Inspects a request before it reaches its handler.
+    /// Returning an error code rejects the request and reports it in the rpc ret, notifies are
+    /// dropped without one.
+    fn on_request(&self, _session: &NetworkSession, _request: &RequestBody) -> Result<(), u16> {
+        Ok(())
+    }
+
+    /// Attaches middleware entries to a protocol sent by the server.
+    fn on_outgoing(
+        &self,
+        _session: &NetworkSession,
+        _protocol_id: u16,
+        _middlewares: &mut Vec<MiddlewareEntry>,
+    ) {
+    }
+}
+
+#[derive(Default)]
+pub struct MiddlewareChain(Vec<Box<dyn Middleware>>);
+
+impl MiddlewareChain {
+    #[must_use]
+    pub fn with(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.0.push(Box::new(middleware));
+        self
+    }
+
+    pub fn process_request(
+        &self,
+        session: &NetworkSession,
+        request: &RequestBody,
+    ) -> Result<(), MiddlewareRejection> {
+        for middleware in &self.0 {
+            middleware
+                .on_request(session, request)
+                .map_err(|error_code| MiddlewareRejection {
+                    middleware_id: middleware.id(),
+                    error_code,
+                })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn process_outgoing(
+        &self,
+        session: &NetworkSession,
+        protocol_id: u16,
+    ) -> Vec<MiddlewareEntry> {
+        let mut middlewares = Vec::new();
+        for middleware in &self.0 {
+            middleware.on_outgoing(session, protocol_id, &mut middlewares);
+        }
+
+        middlewares
+    }
+}
+
+pub fn default_chain() -> MiddlewareChain {
+    MiddlewareChain::default().with(LoginRequired)
+}
+
+/// Rejects everything except the login handshake until the session is authenticated.
+pub struct LoginRequired;
+
+impl LoginRequired {
+    const ID: u16 = 1;
+    const ERROR_NOT_LOGGED_IN: u16 = 1;
+    const ALLOWED_BEFORE_LOGIN: [u16; 3] =
+        [RPC_LOGIN_ID, RPC_KEEP_ALIVE_ID, PTC_GET_SERVER_TIMESTAMP_ID];
+}
+
+impl Middleware for LoginRequired {
+    fn id(&self) -> u16 {
+        Self::ID
+    }
+
+    fn on_request(&self, session: &NetworkSession, request: &RequestBody) -> Result<(), u16> {
+        if session.is_logged_in() || Self::ALLOWED_BEFORE_LOGIN.contains(&request.protocol_id) {
+            Ok(())
+        } else {
+            Err(Self::ERROR_NOT_LOGGED_IN)
+        }
+    }
+}
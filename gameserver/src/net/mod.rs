@@ -1,9 +1,7 @@
+mod capture;
 pub mod gateway;
 mod handlers;
-mod packet;
+pub mod middleware;
 mod session;
 
-pub use packet::Packet;
-pub use packet::RequestBody;
-pub use packet::ResponseBody;
-pub use session::NetworkSession;
+pub use session::{ChannelID, NetworkSession};
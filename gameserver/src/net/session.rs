@@ -1,22 +1,37 @@
 use anyhow::Result;
-use protocol::{AccountInfo, PlayerInfo};
-use qwer::{OctData, ProtocolHeader};
+use futures_util::{SinkExt, StreamExt};
+use protocol::{
+    lookup_protocol, AccountInfo, PlayerInfo, ProtocolVersion, PtcKickPlayerArg,
+    PtcPlayerInfoChangedArg, PTC_KICK_PLAYER_ID, PTC_PLAYER_INFO_CHANGED_ID,
+};
+use qwer::{
+    CaptureDirection, Frame, FrameBody, FrameError, GatewayCodec, MiddlewareEntry, OctData,
+    PropertyDiff, ProtocolHeader, RequestBody, ResponseBody,
+};
 use std::collections::VecDeque;
-use std::io::Cursor;
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
-use tokio::sync::{Mutex, MutexGuard, OnceCell};
+use tokio::sync::{Mutex, OnceCell};
+use tokio_util::codec::{FramedRead, FramedWrite};
 
 use crate::game::manager::net_stream;
 use crate::game::GameContext;
 
+use super::capture::SessionCapture;
 use super::handlers::ProtocolHandler;
-use super::{Packet, RequestBody, ResponseBody};
+use super::middleware::MiddlewareChain;
+
+// Reason id shown by the client when the server drops a connection for sending malformed data.
+const KICK_REASON_MALFORMED_PACKET: i32 = 1;
 
 #[derive(Clone, Copy, Debug)]
 pub struct AccountUID(pub u64);
 
+/// Logical channel a client talks to, announced in the connection handshake.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChannelID(pub u16);
+
 #[derive(Clone, Copy, Debug)]
 pub struct PlayerUID(u64);
 
@@ -34,37 +49,59 @@ impl PlayerUID {
     }
 }
 
-struct QueueItem(pub u16, pub Vec<u8>);
+struct QueueItem(pub ChannelID, pub u16, pub Vec<u8>);
 
 pub struct NetworkSession {
-    client_socket: Arc<Mutex<TcpStream>>,
+    reader: FramedRead<OwnedReadHalf, GatewayCodec>,
+    // Frames are buffered here and written out once the current request is handled.
+    writer: Mutex<FramedWrite<OwnedWriteHalf, GatewayCodec>>,
+    channel_id: ChannelID,
     cur_rpc_uid: u64,
+    cur_protocol_id: u16,
+    cur_request_channels: (ChannelID, ChannelID),
+    cur_request_middlewares: Vec<MiddlewareEntry>,
+    middlewares: Arc<MiddlewareChain>,
+    capture: Option<SessionCapture>,
     outgoing_rpc_queue: Mutex<VecDeque<QueueItem>>,
+    player_info_snapshot: Mutex<Option<PlayerInfo>>,
     pub ns_prop_mgr: net_stream::PropertyManager,
     pub context: GameContext,
     account_uid: OnceCell<AccountUID>,
     player_uid: OnceCell<PlayerUID>,
+    protocol_version: OnceCell<&'static ProtocolVersion>,
 }
 
 impl NetworkSession {
-    pub fn new(client_socket: TcpStream) -> Self {
+    pub fn new(
+        client_socket: TcpStream,
+        codec: GatewayCodec,
+        channel_id: ChannelID,
+        middlewares: Arc<MiddlewareChain>,
+        capture: Option<SessionCapture>,
+    ) -> Self {
         let ns_prop_mgr = net_stream::PropertyManager::default();
+        let (reader, writer) = client_socket.into_split();
 
         Self {
-            client_socket: Arc::new(Mutex::new(client_socket)),
+            reader: FramedRead::new(reader, codec),
+            writer: Mutex::new(FramedWrite::new(writer, codec)),
+            channel_id,
             cur_rpc_uid: 0,
+            cur_protocol_id: 0,
+            cur_request_channels: (channel_id, channel_id),
+            cur_request_middlewares: Vec::new(),
+            middlewares,
+            capture,
             outgoing_rpc_queue: Mutex::new(VecDeque::new()),
+            player_info_snapshot: Mutex::new(None),
             context: GameContext::new(ns_prop_mgr.player_info.clone()),
             ns_prop_mgr,
             account_uid: OnceCell::new(),
             player_uid: OnceCell::new(),
+            protocol_version: OnceCell::new(),
         }
     }
 
-    async fn client_socket(&self) -> MutexGuard<'_, TcpStream> {
-        self.client_socket.lock().await
-    }
-
     pub fn logged_in(&self, uid: AccountUID, account: AccountInfo) -> Result<()> {
         self.account_uid.set(uid)?;
         *self.ns_prop_mgr.account_info.write() = account;
@@ -79,6 +116,27 @@ impl NetworkSession {
         Ok(())
     }
 
+    pub fn set_protocol_version(&self, version: &'static ProtocolVersion) -> Result<()> {
+        self.protocol_version.set(version)?;
+        Ok(())
+    }
+
+    /// Protocol version announced at login, the latest one until then.
+    pub fn protocol_version(&self) -> &'static ProtocolVersion {
+        self.protocol_version
+            .get()
+            .copied()
+            .unwrap_or_else(ProtocolVersion::latest)
+    }
+
+    pub fn is_logged_in(&self) -> bool {
+        self.account_uid.initialized()
+    }
+
+    pub const fn channel_id(&self) -> ChannelID {
+        self.channel_id
+    }
+
     pub fn account_uid(&self) -> AccountUID {
         *self.account_uid.get().unwrap()
     }
@@ -87,106 +145,314 @@ impl NetworkSession {
         *self.player_uid.get().unwrap()
     }
 
-    pub async fn run(&mut self) -> Result<()> {
-        let Some(_channel_id) = self.read_handshake().await? else {
-            return Ok(());
-        };
+    /// Middleware entries the client attached to the request being handled.
+    pub fn request_middlewares(&self) -> &[MiddlewareEntry] {
+        &self.cur_request_middlewares
+    }
 
+    pub async fn run(&mut self) -> Result<()> {
         loop {
-            let packet = match Packet::read(&mut *self.client_socket().await).await {
-                Ok(packet) => packet,
-                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(()),
-                Err(e) => return Err(e.into()),
+            let frame = match self.reader.next().await {
+                Some(Ok(frame)) => frame,
+                None => return Ok(()),
+                Some(Err(FrameError::Io(e))) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    return Ok(())
+                }
+                Some(Err(FrameError::Protocol(e))) => return self.kick_malformed(e).await,
+                Some(Err(e)) => return Err(e.into()),
+            };
+
+            let FrameBody::Request(mut request) = frame.body else {
+                tracing::warn!("Ignoring rpc ret sent by the client");
+                continue;
             };
-            let request: RequestBody = packet.body.into();
 
-            self.cur_rpc_uid = packet.header.rpc_arg_uid;
+            if let Some(capture) = &self.capture {
+                capture.record(
+                    CaptureDirection::Inbound,
+                    &frame.header,
+                    request.protocol_id,
+                    &request.payload,
+                );
+            }
+
+            request.protocol_id = self.protocol_version().protocol_id(request.protocol_id);
+            self.cur_rpc_uid = frame.header.rpc_arg_uid;
+            self.cur_protocol_id = request.protocol_id;
+            // Replies go back to the channel the request came from.
+            self.cur_request_channels = (
+                ChannelID(frame.to_channel),
+                ChannelID(frame.header.from_channel),
+            );
+
+            let middlewares = self.middlewares.clone();
+            if let Err(rejection) = middlewares.process_request(self, &request) {
+                tracing::warn!(
+                    "Message with protocol id {} rejected by middleware: {rejection:?}",
+                    request.protocol_id
+                );
+
+                // Notifies have no ret to report the rejection in.
+                if lookup_protocol(request.protocol_id)
+                    .is_some_and(|entry| entry.decode_ret.is_some())
+                {
+                    self.send_response(ResponseBody {
+                        middleware_id: rejection.middleware_id,
+                        middleware_error_code: rejection.error_code,
+                        payload: Vec::new(),
+                    })
+                    .await?;
+                    self.flush_writes().await?;
+                }
+                continue;
+            }
+
+            self.cur_request_middlewares = std::mem::take(&mut request.middlewares);
+            self.track_player_info_changes().await;
             Box::pin(Self::on_message(self, request.protocol_id, request.payload)).await?;
+            self.flush_writes().await?;
         }
     }
 
-    async fn read_handshake(&mut self) -> Result<Option<u16>> {
-        match self.client_socket().await.read_u16_le().await {
-            Ok(channel_id) => Ok(Some(channel_id)),
-            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
-            Err(e) => return Err(e.into()),
-        }
+    async fn kick_malformed(&self, reason: impl std::fmt::Display) -> Result<()> {
+        tracing::warn!("Kicking session: {reason}");
+
+        let payload = self.protocol_version().marshal(
+            &PtcKickPlayerArg {
+                reason_id: KICK_REASON_MALFORMED_PACKET,
+                reason_str: reason.to_string(),
+            },
+            0,
+        )?;
+
+        self.send_rpc_arg(self.channel_id, PTC_KICK_PLAYER_ID, payload)
+            .await?;
+        self.flush_writes().await
     }
 
+    // Pushes go to the channel the current request came from, which is the handshake channel
+    // until the first request arrives.
     pub async fn push_rpc_arg(&self, protocol_id: u16, data: impl OctData) -> Result<()> {
-        let mut payload = Vec::new();
-        let mut cursor = Cursor::new(&mut payload);
-        data.marshal_to(&mut cursor, 0)?;
+        let (_, request_from) = self.cur_request_channels;
+        self.push_rpc_arg_to(request_from, protocol_id, data).await
+    }
+
+    pub async fn push_rpc_arg_to(
+        &self,
+        channel_id: ChannelID,
+        protocol_id: u16,
+        data: impl OctData,
+    ) -> Result<()> {
+        let payload = self.protocol_version().marshal(&data, 0)?;
 
         self.outgoing_rpc_queue
             .lock()
             .await
-            .push_back(QueueItem(protocol_id, payload));
+            .push_back(QueueItem(channel_id, protocol_id, payload));
 
         Ok(())
     }
 
+    // The player is snapshotted once per request, what the handler changed is diffed against
+    // it when the rpc queue is flushed.
+    async fn track_player_info_changes(&self) {
+        let snapshot = self
+            .player_uid
+            .initialized()
+            .then(|| self.ns_prop_mgr.player_info.read().clone());
+
+        *self.player_info_snapshot.lock().await = snapshot;
+    }
+
+    /// Stops tracking the current request's changes, for rets that already carry the whole
+    /// `PlayerInfo`.
+    pub async fn discard_player_info_changes(&self) {
+        self.player_info_snapshot.lock().await.take();
+    }
+
+    // All changes made while handling a request go out as one notify, ahead of the other
+    // queued ptcs so they already see the updated state.
+    async fn flush_player_info_changes(&self) -> Result<()> {
+        let Some(snapshot) = self.player_info_snapshot.lock().await.take() else {
+            return Ok(());
+        };
+        let Some(player_info) = PlayerInfo::diff(&snapshot, &self.ns_prop_mgr.player_info.read())
+        else {
+            return Ok(());
+        };
+
+        let payload = self.protocol_version().marshal(
+            &PtcPlayerInfoChangedArg {
+                player_uid: self.player_uid().raw(),
+                player_info,
+            },
+            0,
+        )?;
+
+        let (_, request_from) = self.cur_request_channels;
+        self.send_rpc_arg(request_from, PTC_PLAYER_INFO_CHANGED_ID, payload)
+            .await
+    }
+
     pub async fn flush_rpc_queue(&self) -> Result<()> {
+        self.flush_player_info_changes().await?;
+
         let mut queue = self.outgoing_rpc_queue.lock().await;
 
-        while let Some(QueueItem(protocol_id, payload)) = queue.pop_front() {
-            self.send_rpc_arg(protocol_id, payload).await?;
+        while let Some(QueueItem(channel_id, protocol_id, payload)) = queue.pop_front() {
+            self.send_rpc_arg(channel_id, protocol_id, payload).await?;
         }
 
         Ok(())
     }
 
-    async fn send_rpc_arg(&self, protocol_id: u16, payload: Vec<u8>) -> Result<()> {
-        let header: Vec<u8> = ProtocolHeader::default().into();
+    async fn send_rpc_arg(
+        &self,
+        channel_id: ChannelID,
+        protocol_id: u16,
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        let header = ProtocolHeader {
+            to_channel: channel_id.0,
+            from_channel: self.channel_id.0,
+            ..Default::default()
+        };
+
+        let wire_id = self.protocol_version().wire_id(protocol_id);
+        if let Some(capture) = &self.capture {
+            capture.record(CaptureDirection::Outbound, &header, wire_id, &payload);
+        }
 
-        let body: Vec<u8> = RequestBody {
-            protocol_id,
+        let body = RequestBody {
+            protocol_id: wire_id,
             payload,
-        }
-        .into();
-
-        let mut packet = Vec::new();
-        packet.extend(0_u16.to_le_bytes());
-        packet.extend(((body.len() + 2) as u32).to_le_bytes());
-        packet.extend((header.len() as u16).to_le_bytes());
-        packet.extend(header);
-        packet.extend(body);
-        packet.extend(0_u16.to_le_bytes()); // middleware count
-
-        self.client_socket().await.write_all(&packet).await?;
-        tracing::info!("Ptc with protocol id {protocol_id} sent");
+            middlewares: self.middlewares.process_outgoing(self, protocol_id),
+        };
+
+        self.writer
+            .lock()
+            .await
+            .feed(Frame::request(header, body))
+            .await?;
+        tracing::info!("Ptc with protocol id {protocol_id} sent to channel {channel_id:?}");
         Ok(())
     }
-}
 
-impl ProtocolHandler for NetworkSession {
-    async fn send_rpc_ret(&self, data: impl OctData) -> Result<()> {
+    async fn send_response(&self, response: ResponseBody) -> Result<()> {
+        let (request_to, request_from) = self.cur_request_channels;
         let header = ProtocolHeader {
+            to_channel: request_from.0,
+            from_channel: request_to.0,
             is_rpc_ret: true,
             rpc_arg_uid: self.cur_rpc_uid,
-            ..Default::default()
         };
 
-        let mut payload = Vec::new();
-        let mut cursor = Cursor::new(&mut payload);
-        data.marshal_to(&mut cursor, 0)?;
+        if let Some(capture) = &self.capture {
+            capture.record(
+                CaptureDirection::Outbound,
+                &header,
+                self.protocol_version().wire_id(self.cur_protocol_id),
+                &response.payload,
+            );
+        }
+
+        self.writer
+            .lock()
+            .await
+            .feed(Frame::response(header, response))
+            .await?;
+        Ok(())
+    }
 
-        let body: Vec<u8> = ResponseBody {
+    async fn flush_writes(&self) -> Result<()> {
+        self.writer.lock().await.flush().await?;
+        Ok(())
+    }
+}
+
+impl ProtocolHandler for NetworkSession {
+    async fn send_rpc_ret(&self, data: impl OctData) -> Result<()> {
+        let payload = self.protocol_version().marshal(&data, 0)?;
+
+        self.send_response(ResponseBody {
             middleware_id: 0,
             middleware_error_code: 0,
             payload,
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn connect() -> (NetworkSession, FramedRead<TcpStream, GatewayCodec>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+
+        let session = NetworkSession::new(
+            server,
+            GatewayCodec::default(),
+            ChannelID(1),
+            Arc::new(MiddlewareChain::default()),
+            None,
+        );
+        (session, FramedRead::new(client, GatewayCodec::default()))
+    }
+
+    fn kick() -> PtcKickPlayerArg {
+        PtcKickPlayerArg {
+            reason_id: 0,
+            reason_str: String::new(),
         }
-        .into();
+    }
 
-        let header_buf: Vec<u8> = header.into();
-        let mut packet = Vec::new();
-        packet.extend(0_u16.to_le_bytes());
-        packet.extend((body.len() as u32).to_le_bytes());
-        packet.extend((header_buf.len() as u16).to_le_bytes());
-        packet.extend(header_buf);
-        packet.extend(body);
+    async fn next_kick_channels(
+        session: &NetworkSession,
+        reader: &mut FramedRead<TcpStream, GatewayCodec>,
+    ) -> (u16, u16) {
+        let frame = reader.next().await.unwrap().unwrap();
+        assert_eq!(frame.to_channel, frame.header.to_channel);
+        assert!(matches!(frame.body, FrameBody::Request(body)
+            if body.protocol_id == session.protocol_version().wire_id(PTC_KICK_PLAYER_ID)));
+        (frame.header.to_channel, frame.header.from_channel)
+    }
 
-        self.client_socket().await.write_all(&packet).await?;
-        Ok(())
+    #[tokio::test]
+    async fn test_push_rpc_arg_to_channel() {
+        let (session, mut reader) = connect().await;
+
+        session
+            .push_rpc_arg_to(ChannelID(2), PTC_KICK_PLAYER_ID, kick())
+            .await
+            .unwrap();
+        session.flush_rpc_queue().await.unwrap();
+        session.flush_writes().await.unwrap();
+
+        assert_eq!(next_kick_channels(&session, &mut reader).await, (2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_push_follows_request_channel() {
+        let (mut session, mut reader) = connect().await;
+
+        session
+            .push_rpc_arg(PTC_KICK_PLAYER_ID, kick())
+            .await
+            .unwrap();
+        session.cur_request_channels = (ChannelID(1), ChannelID(3));
+        session
+            .push_rpc_arg(PTC_KICK_PLAYER_ID, kick())
+            .await
+            .unwrap();
+        session.flush_rpc_queue().await.unwrap();
+        session.flush_writes().await.unwrap();
+
+        assert_eq!(next_kick_channels(&session, &mut reader).await, (1, 1));
+        assert_eq!(next_kick_channels(&session, &mut reader).await, (3, 1));
     }
 }
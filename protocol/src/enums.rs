@@ -3,6 +3,7 @@ use serde::Deserialize;
 use super::*;
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i32)]
 pub enum ErrorCode {
     Fail = -1,
@@ -102,6 +103,7 @@ pub enum ErrorCode {
 }
 
 #[derive(OctData, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum HollowQuestType {
     Common = 0,
@@ -115,6 +117,7 @@ pub enum HollowQuestType {
 }
 
 #[derive(OctData, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum UnlockIDType {
     HollowCard = 1,
@@ -125,6 +128,7 @@ pub enum UnlockIDType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u8)]
 pub enum FairyState {
     Unlock = 0,
@@ -132,6 +136,7 @@ pub enum FairyState {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum FightRanking {
     None = 0,
@@ -143,6 +148,7 @@ pub enum FightRanking {
 }
 
 #[derive(OctData, Hash, Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum BattleRewardType {
     Client = 1,
@@ -153,6 +159,7 @@ pub enum BattleRewardType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum MailState {
     New = 0,
@@ -163,6 +170,7 @@ pub enum MailState {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum HollowBattleEventType {
     Default = 0,
@@ -174,6 +182,7 @@ pub enum HollowBattleEventType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum QuestType {
     ArchiveFile = 1,
@@ -187,6 +196,7 @@ pub enum QuestType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum EventState {
     Initing = 0,
@@ -199,6 +209,7 @@ pub enum EventState {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum ActionState {
     Init = 0,
@@ -208,6 +219,7 @@ pub enum ActionState {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u8)]
 pub enum DungeonContentDropPoolType {
     Card = 0,
@@ -220,6 +232,7 @@ pub enum DungeonContentDropPoolType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum ReportType {
     Fairy = 0,
@@ -229,6 +242,7 @@ pub enum ReportType {
 }
 
 #[derive(OctData, Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u16)]
 pub enum UIType {
     Default = 0,
@@ -238,6 +252,7 @@ pub enum UIType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum ACTPerformShowMoment {
     Begin = 0,
@@ -245,6 +260,7 @@ pub enum ACTPerformShowMoment {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum HollowSystemType {
     Card = 1,
@@ -257,6 +273,7 @@ pub enum HollowSystemType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum HollowSystemUIState {
     Normal = 0,
@@ -265,6 +282,7 @@ pub enum HollowSystemUIState {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum HollowShopType {
     All = 0,
@@ -277,6 +295,7 @@ pub enum HollowShopType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum TimePeriodType {
     Random = 0,
@@ -286,6 +305,7 @@ pub enum TimePeriodType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum WeatherType {
     None = -1,
@@ -298,6 +318,7 @@ pub enum WeatherType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum PropertyType {
     Hp = 1,
@@ -428,6 +449,7 @@ pub enum PropertyType {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum ScenePropertyType {
     Stamina = 1001,
@@ -525,7 +547,8 @@ macro_rules! flag {
 
 flag! {
     u32,
-    #[derive(OctData, Clone, Debug, Copy)]
+    #[derive(OctData, Clone, Debug, PartialEq, Copy)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     HollowGridFlag {
         Core = 1,
         CanMove = 2,
@@ -551,7 +574,8 @@ flag! {
 
 flag! {
     u8,
-    #[derive(OctData, Clone, Debug, Copy)]
+    #[derive(OctData, Clone, Debug, PartialEq, Copy)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     HollowGridLink {
         None = 0,
         Up = 1,
@@ -563,6 +587,7 @@ flag! {
 }
 
 #[derive(OctData, Clone, Debug, Hash, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 #[repr(i16)]
 pub enum NodeState {
     All = 0,
@@ -580,6 +605,7 @@ pub enum NodeState {
 }
 
 #[derive(OctData, Clone, Debug, Hash, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 #[repr(i16)]
 pub enum NodeVisible {
     All = 0,
@@ -591,6 +617,7 @@ pub enum NodeVisible {
 }
 
 #[derive(OctData, Clone, Debug, Hash, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 #[repr(i16)]
 pub enum HollowEventType {
     None = 0,
@@ -621,7 +648,8 @@ pub enum HollowEventType {
     DialogSpecial = 43,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum HollowShopCurrency {
     Coin = 1,
@@ -629,7 +657,8 @@ pub enum HollowShopCurrency {
     Random = 3,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum QuestState {
     Unlocked = 0,
@@ -640,6 +669,7 @@ pub enum QuestState {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u8)]
 pub enum QuestStatisticsType {
     ArrivedLevel = 1,
@@ -654,7 +684,8 @@ pub enum QuestStatisticsType {
     FinishedEventIDCount = 10,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum System {
     HollowQuestUI = 0,
@@ -676,14 +707,16 @@ pub enum System {
     UseManualQTEMode = 16,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum InteractTarget {
     NPC = 0,
     TriggerBox = 1,
 }
 
-#[derive(OctData, Clone, Copy, Debug)]
+#[derive(OctData, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum EventGraphOwnerType {
     Scene = 0,
@@ -692,7 +725,8 @@ pub enum EventGraphOwnerType {
     Hollow = 3,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i16)]
 pub enum Operator {
     Enter = 0,
@@ -1,15 +1,26 @@
+#![allow(incomplete_features)]
+#![feature(specialization)]
+
 use std::collections::{HashMap, HashSet};
 
 use qwer::{OctData, PropertyDoubleKeyHashMap, PropertyHashMap, PropertyHashSet};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 mod enums;
 mod polymorphic;
 mod protocol_id;
+mod registry;
 mod rpc_ptc;
 mod structs;
+#[cfg(test)]
+mod test_support;
+mod version;
 
 pub use enums::*;
 pub use polymorphic::*;
 pub use protocol_id::*;
+pub use registry::*;
 pub use rpc_ptc::*;
 pub use structs::*;
+pub use version::*;
@@ -2,7 +2,8 @@ use super::*;
 
 macro_rules! polymorphic_scene_unit_protocol_info {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 2]
         pub enum $name {
@@ -55,7 +56,8 @@ macro_rules! polymorphic_scene_unit_protocol_info {
 
 macro_rules! polymorphic_scene_info {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 11]
         pub enum $name {
@@ -270,7 +272,8 @@ macro_rules! polymorphic_scene_info {
 
 macro_rules! polymorphic_item_info {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 5]
         pub enum $name {
@@ -377,7 +380,8 @@ macro_rules! polymorphic_item_info {
 
 macro_rules! polymorphic_dungeon_table_ext {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 0]
         pub enum $name {
@@ -392,7 +396,8 @@ macro_rules! polymorphic_dungeon_table_ext {
 
 macro_rules! polymorphic_scene_table_ext {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 1]
         pub enum $name {
@@ -427,7 +432,8 @@ macro_rules! polymorphic_scene_table_ext {
 
 macro_rules! polymorphic_section_info_ext {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 1]
         pub enum $name {
@@ -462,7 +468,8 @@ macro_rules! polymorphic_section_info_ext {
 
 macro_rules! polymorphic_action_info {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 0]
         pub enum $name {
@@ -479,7 +486,8 @@ macro_rules! polymorphic_action_info {
 
 macro_rules! polymorphic_event_graph_info {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 6]
         pub enum $name {
@@ -604,7 +612,8 @@ macro_rules! polymorphic_event_graph_info {
 
 macro_rules! polymorphic_quest_info {
     (enum $name:ident { $($variant:ident { $($field:ident: $ty:ty),* $(,)? } = $tag:expr,)* }) => {
-        #[derive(OctData, Clone, Debug)]
+        #[derive(OctData, Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u16)]
         #[base = 9]
         pub enum $name {
@@ -0,0 +1,146 @@
+use std::fmt::Display;
+
+use paste::paste;
+use qwer::{OctSchema, TypeSchema};
+
+use super::*;
+
+pub trait ProtocolArg: OctData + OctSchema {
+    const ID: u16;
+    const NAME: &'static str;
+}
+
+/// Protocols the client sends and expects an rpc ret for.
+pub trait ProtocolRequest: ProtocolArg {
+    type Ret: OctData + OctSchema;
+}
+
+pub type ProtocolDecoder = fn(&[u8]) -> std::io::Result<Box<dyn Display>>;
+pub type ProtocolSchema = fn() -> &'static TypeSchema;
+
+pub struct ProtocolEntry {
+    pub id: u16,
+    pub name: &'static str,
+    pub decode_arg: ProtocolDecoder,
+    pub decode_ret: Option<ProtocolDecoder>,
+    pub arg_schema: ProtocolSchema,
+    pub ret_schema: Option<ProtocolSchema>,
+}
+
+fn decode<T: OctData + Display + 'static>(payload: &[u8]) -> std::io::Result<Box<dyn Display>> {
+    Ok(Box::new(qwer::decode::<T>(payload)?))
+}
+
+macro_rules! protocol_registry {
+    (requests { $($request:ident;)* } notifies { $($notify:ident;)* }) => {
+        paste! {
+            $(
+                impl ProtocolArg for [<$request Arg>] {
+                    const ID: u16 = [<$request:snake:upper _ID>];
+                    const NAME: &'static str = stringify!($request);
+                }
+
+                impl ProtocolRequest for [<$request Arg>] {
+                    type Ret = [<$request Ret>];
+                }
+            )*
+
+            $(
+                impl ProtocolArg for [<$notify Arg>] {
+                    const ID: u16 = [<$notify:snake:upper _ID>];
+                    const NAME: &'static str = stringify!($notify);
+                }
+            )*
+
+            pub static PROTOCOL_REGISTRY: &[ProtocolEntry] = &[
+                $(ProtocolEntry {
+                    id: [<$request:snake:upper _ID>],
+                    name: stringify!($request),
+                    decode_arg: decode::<[<$request Arg>]>,
+                    decode_ret: Some(decode::<[<$request Ret>]>),
+                    arg_schema: [<$request Arg>]::schema,
+                    ret_schema: Some([<$request Ret>]::schema),
+                },)*
+                $(ProtocolEntry {
+                    id: [<$notify:snake:upper _ID>],
+                    name: stringify!($notify),
+                    decode_arg: decode::<[<$notify Arg>]>,
+                    decode_ret: None,
+                    arg_schema: [<$notify Arg>]::schema,
+                    ret_schema: None,
+                },)*
+            ];
+
+            #[cfg(test)]
+            pub(crate) fn visit_protocols(visitor: &mut impl crate::test_support::ProtocolVisitor) {
+                $(visitor.request::<[<$request Arg>]>();)*
+                $(visitor.notify::<[<$notify Arg>]>();)*
+            }
+        }
+    };
+}
+
+protocol_registry! {
+    requests {
+        RpcLogin;
+        RpcCreatePlayer;
+        RpcEnterWorld;
+        RpcReenterWorld;
+        RpcGetPlayerMails;
+        PtcGetServerTimestamp;
+        RpcAdvanceBeginnerProcedure;
+        RpcPerformTrigger;
+        RpcPerformEnd;
+        RpcModNickName;
+        RpcFinishActPerformShow;
+        RpcKeepAlive;
+        RpcPerformJump;
+        RpcBeginnerbattleBegin;
+        RpcBattleReport;
+        RpcBeginnerbattleEnd;
+        RpcLeaveCurDungeon;
+        RpcSavePosInMainCity;
+        RpcCloseLevelChgTips;
+        PtcPlayerOperation;
+        RpcRunEventGraph;
+        RpcInteractWithUnit;
+        RpcCheckYorozuyaInfoRefresh;
+        RpcStartHollowQuest;
+        RpcRunHollowEventGraph;
+        RpcMakeChoiceOfEvent;
+        RpcRemoveHollowCurse;
+        RpcHollowShopping;
+        RpcUseInitiativeItem;
+        RpcMakeInitiativeItem;
+        RpcHollowMove;
+        RpcEndBattle;
+        RpcFinishEventGraphPerformShow;
+        RpcDelNewMap;
+    }
+    notifies {
+        PtcEnterScene;
+        PtcUnlock;
+        PtcPlayerInfoChanged;
+        PtcScenePropertyChanged;
+        PtcPropertyChanged;
+        PtcSyncSceneUnit;
+        PtcEnterSection;
+        PtcSyncEventInfo;
+        PtcSyncHollowGridMaps;
+        PtcPositionInHollowChanged;
+        PtcSyncHollowEventInfo;
+        PtcHollowGrid;
+        PtcCardDisable;
+        PtcShowCardGenreTips;
+        PtcHpOrStressChanged;
+        PtcAvatarMapChanged;
+        PtcDungeonQuestFinished;
+        PtcSyncSceneTime;
+        PtcKickPlayer;
+    }
+}
+
+#[must_use]
+pub fn lookup_protocol(id: u16) -> Option<&'static ProtocolEntry> {
+    PROTOCOL_REGISTRY.iter().find(|entry| entry.id == id)
+}
@@ -8,7 +8,8 @@ macro_rules! ret {
         $field:ident: $ty:ty,
     )*
     }) => {
-        #[derive(OctData)]
+        #[derive(OctData, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         pub struct $name $(< $lt >)? {
             pub error_code: ErrorCode,
             pub error_code_params: Vec<String>,
@@ -53,7 +54,8 @@ macro_rules! ret {
     };
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcLoginArg {
     pub account_name: String,
     pub token: String,
@@ -61,10 +63,12 @@ pub struct RpcLoginArg {
     pub config_sign: String,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcCreatePlayerArg {}
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcEnterSceneArg {
     pub player_uid: u64,
     pub scene_uid: u64,
@@ -79,126 +83,149 @@ pub struct PtcEnterSceneArg {
     pub camera_y: u32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcReenterWorldArg {}
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcEnterWorldArg {}
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcGetPlayerMailsArg {}
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcUnlockArg {
     pub unlock_id: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcGetServerTimestampArg {}
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcAdvanceBeginnerProcedureArg {
     pub player_uid: u64,
     pub procedure_id: i32,
     pub params: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcPerformTriggerArg {
     pub perform_id: i32,
     pub perform_type: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcPerformEndArg {
     pub perform_id: i32,
     pub perform_type: i32,
     pub perform_uid: String,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcModNickNameArg {
     pub nick_name: String,
     pub avatar_id: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcFinishActPerformShowArg {
     pub moment: ACTPerformShowMoment,
     pub step: u8,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcKeepAliveArg {}
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcPerformJumpArg {
     pub perform_id: i32,
     pub perform_type: i32,
     pub perform_uid: String,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcBeginnerbattleBeginArg {
     pub battle_id: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcBattleReportArg {
     pub battle_reports: Vec<BattleReport>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcBeginnerbattleEndArg {
     pub battle_id: i32,
     pub battle_uid: String,
     pub battle_statistics: LogBattleStatistics,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcLeaveCurDungeonArg {
     pub player_uid: u64,
     pub dungeon_uid: u64,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcSavePosInMainCityArg {
     pub position: Vector3f,
     pub rotation: Vector3f,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcCloseLevelChgTipsArg {}
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcPlayerInfoChangedArg {
     pub player_uid: u64,
     #[property_blob]
     pub player_info: PlayerInfo,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcPlayerOperationArg {
     pub system: System,
     pub operator: Operator,
     pub param: i32,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcScenePropertyChangedArg {
     pub player_uid: u64,
     pub is_partial: bool,
     pub changed_properties: PropertyHashMap<u16, i32>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcPropertyChangedArg {
     pub scene_unit_uid: u64,
     pub is_partial: bool,
     pub changed_properties: PropertyHashMap<u16, i32>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcSyncSceneUnitArg {
     pub scene_uid: u64,
     pub section_id: i32,
@@ -207,12 +234,14 @@ pub struct PtcSyncSceneUnitArg {
     pub scene_units: Vec<SceneUnitProtocolInfo>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcEnterSectionArg {
     pub section_id: i32,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcRunEventGraphArg {
     pub owner_type: EventGraphOwnerType,
     pub owner_uid: u64,
@@ -221,7 +250,8 @@ pub struct RpcRunEventGraphArg {
     pub move_path: Vec<i32>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcInteractWithUnitArg {
     pub unit_uid: u64,
     pub unit_type: InteractTarget,
@@ -229,22 +259,26 @@ pub struct RpcInteractWithUnitArg {
     pub interaction: u16,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcSyncEventInfoArg {
     pub owner_type: EventGraphOwnerType,
     pub owner_uid: u64,
     pub updated_events: PropertyDoubleKeyHashMap<i32, i32, EventInfo>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcCheckYorozuyaInfoRefreshArg {}
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcHollowQuestUnlockedByMainCityQuest {
     pub quest_id: i32,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcStartHollowQuestArg {
     pub hollow_quest_id: i32,
     pub buddy: u64,
@@ -253,7 +287,8 @@ pub struct RpcStartHollowQuestArg {
     pub is_story: bool,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcSyncHollowGridMapsArg {
     pub player_uid: u64,
     pub scene_uid: u64,
@@ -263,14 +298,16 @@ pub struct PtcSyncHollowGridMapsArg {
     pub weather: WeatherType,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcPositionInHollowChangedArg {
     pub player_uid: u64,
     pub hollow_level: i32,
     pub position: u16,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcSyncHollowEventInfoArg {
     pub event_graph_uid: u64,
     pub hollow_event_template_id: i32,
@@ -279,14 +316,80 @@ pub struct PtcSyncHollowEventInfoArg {
     pub specials: PropertyHashMap<String, i32>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcRunHollowEventGraphArg {
     pub event_graph_uid: u64,
     pub event_id: i32,
     pub move_path: Vec<i32>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RpcMakeChoiceOfEventArg {
+    pub event_graph_uid: u64,
+    pub event_id: i32,
+    pub choice_id: i32,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RpcRemoveHollowCurseArg {
+    pub curse_uids: Vec<u64>,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RpcHollowShoppingArg {
+    pub event_graph_uid: u64,
+    pub shop_type: HollowShopType,
+    pub goods_uid: i32,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RpcUseInitiativeItemArg {
+    pub initiative_item: i32,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RpcMakeInitiativeItemArg {
+    pub initiative_item: i32,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PtcCardDisableArg {
+    pub player_uid: u64,
+    pub card_uids: Vec<u64>,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PtcShowCardGenreTipsArg {
+    pub genre: i32,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PtcHpOrStressChangedArg {
+    pub avatar_uid: u64,
+    pub hp: i32,
+    pub hp_changed: i32,
+    pub stress: i32,
+    pub stress_changed: i32,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PtcAvatarMapChangedArg {
+    pub player_uid: u64,
+    pub avatar_map: PropertyHashMap<i8, AvatarUnitInfo>,
+}
+
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcHollowGridArg {
     pub player_uid: u64,
     pub is_partial: bool,
@@ -295,7 +398,8 @@ pub struct PtcHollowGridArg {
     pub grids: HashMap<u16, HollowGridProtocolInfo>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcHollowMoveArg {
     pub player_uid: u64,
     pub scene_uid: u64,
@@ -303,7 +407,8 @@ pub struct RpcHollowMoveArg {
     pub positions: Vec<u16>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcEndBattleArg {
     pub player_uid: u64,
     pub fight_ranking: FightRanking,
@@ -318,7 +423,8 @@ pub struct RpcEndBattleArg {
     pub battle_statistics: LogBattleStatistics,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcFinishEventGraphPerformShowArg {
     pub owner_type: EventGraphOwnerType,
     pub owner_uid: u64,
@@ -328,13 +434,15 @@ pub struct RpcFinishEventGraphPerformShowArg {
     pub return_map: HashMap<String, i32>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RpcDelNewMapArg {
     pub map_type: UnlockIDType,
     pub ids: PropertyHashSet<i32>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcDungeonQuestFinishedArg {
     pub player_uid: u64,
     pub quest_id: i32,
@@ -343,13 +451,15 @@ pub struct PtcDungeonQuestFinishedArg {
     pub statistics: PropertyHashMap<QuestStatisticsType, u64>,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcSyncSceneTimeArg {
     pub timestamp: u64,
     pub last_timestamp: u64,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PtcKickPlayerArg {
     pub reason_id: i32,
     pub reason_str: String,
@@ -442,6 +552,21 @@ ret! {
     struct RpcRunHollowEventGraphRet {
     }
 
+    struct RpcMakeChoiceOfEventRet {
+    }
+
+    struct RpcRemoveHollowCurseRet {
+    }
+
+    struct RpcHollowShoppingRet {
+    }
+
+    struct RpcUseInitiativeItemRet {
+    }
+
+    struct RpcMakeInitiativeItemRet {
+    }
+
     struct RpcHollowMoveRet {
         hollow_level: i32,
         position: u16,
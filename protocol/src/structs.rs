@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PropertyBlob {
     pub stream: Vec<u8>,
 }
@@ -31,45 +32,66 @@ impl OctData for PropertyBlob {
         bt_property_tag: u16,
     ) -> Result<Self, std::io::Error> {
         let len = i32::unmarshal_from(r, bt_property_tag)?;
-        assert!(len >= 0, "PropertyBlob stream length can't be negative");
+        if len < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "PropertyBlob stream length can't be negative",
+            ));
+        }
 
-        let mut stream = vec![0; len as usize];
-        r.read_exact(&mut stream)?;
-        Ok(Self { stream })
+        Ok(Self {
+            stream: qwer::read_bytes(r, len as usize)?,
+        })
     }
 }
 
-#[derive(OctData, Copy, Clone, Debug, Default)]
+qwer::oct_arbitrary! {
+    impl qwer::OctArbitrary for PropertyBlob {
+        fn arbitrary(g: &mut qwer::Gen) -> Self {
+            Self {
+                stream: qwer::OctArbitrary::arbitrary(g),
+            }
+        }
+    }
+}
+
+#[derive(OctData, Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vector3f {
     pub x: f64,
     pub y: f64,
     pub z: f64,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Transform {
     pub position: Vector3f,
     pub rotation: Vector3f,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FightDropInfo {
     pub drop_pack_id: i32,
     pub param_1: i32,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ChallengeResultInfo {
     pub param_1: i32,
 }
 
-#[derive(OctData, Debug)]
+#[derive(OctData, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ItemIDCount {
     pub id: i32,
     pub count: i32,
 }
 
 #[derive(OctData, Clone, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object(u16, 0x01)]
 #[root]
 pub struct AccountInfo {
@@ -86,12 +108,14 @@ pub struct AccountInfo {
     pub register_cps: Option<String>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TimeEventInfo {
     pub executed_count: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TimeEventGroupInfo {
     pub group_id: i32,
     pub executing_scripts: PropertyHashSet<i32>,
@@ -101,7 +125,8 @@ pub struct TimeEventGroupInfo {
     pub executing_time_event: PropertyHashSet<i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MainCityTimeInfo {
     pub initial_time: u32,
     pub passed_milliseconds: u64,
@@ -117,13 +142,15 @@ pub struct MainCityTimeInfo {
     pub leave_time: u64,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AvatarPropertyChgInHollow {
     pub hp_lost: i32,
     pub hp_add: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AvatarUnitInfo {
     pub uid: u64,
     pub properties_uid: u64,
@@ -134,25 +161,29 @@ pub struct AvatarUnitInfo {
     pub layer_property_change: PropertyHashMap<i32, AvatarPropertyChgInHollow>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BuddyUnitInfo {
     pub uid: u64,
     pub properties: u64,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DungeonDropPollInfo {
     pub action_card_mask: PropertyHashMap<i32, i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BattleReport {
     pub index: i32,
     pub report_type: ReportType,
     pub id: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DungeonInfo {
     pub uid: u64,
     pub id: i32,
@@ -177,7 +208,8 @@ pub struct DungeonInfo {
     pub hollow_event_version: i32,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct DungeonCollection {
     #[tag = 1]
@@ -194,7 +226,8 @@ pub struct DungeonCollection {
     pub used_manual_qte_mode: Option<bool>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct QuestData {
     #[tag = 1]
@@ -212,27 +245,31 @@ pub struct QuestData {
     pub world_quest_for_cur_dungeon_afk: Option<i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct VideotapeInfo {
     pub star_count: PropertyHashMap<u8, u16>,
     pub finished: bool,
     pub awarded_star: PropertyHashMap<u8, HashSet<u16>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct ArchiveInfo {
     #[tag = 1]
     pub videotapes_info: Option<PropertyHashMap<i32, VideotapeInfo>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AutoRecoveryInfo {
     pub last_recovery_timestamp: u64,
     pub buy_times: u32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct UnlockInfo {
     #[tag = 1]
@@ -242,7 +279,8 @@ pub struct UnlockInfo {
     pub condition_progress: Option<PropertyDoubleKeyHashMap<i32, i32, i32>>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct YorozuyaInfo {
     #[tag = 1]
@@ -274,7 +312,8 @@ pub struct YorozuyaInfo {
     pub unlock_hollow_id_progress: Option<PropertyDoubleKeyHashMap<i32, i32, i32>>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct EquipGachaInfo {
     #[tag = 1]
@@ -292,14 +331,16 @@ pub struct EquipGachaInfo {
     pub avatar_level_advance_times: Option<i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct BeginnerProcedureInfo {
     #[tag = 1]
     pub procedure_info: Option<i32>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct PlayerPosInMainCity {
     #[tag = 1]
@@ -310,7 +351,8 @@ pub struct PlayerPosInMainCity {
     pub initial_pos_id: Option<i32>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct FairyInfo {
     #[tag = 1]
@@ -320,7 +362,8 @@ pub struct FairyInfo {
     pub condition_progress: Option<PropertyDoubleKeyHashMap<i32, i32, i32>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct PopupWindowInfo {
     #[tag = 1]
@@ -330,7 +373,8 @@ pub struct PopupWindowInfo {
     pub condition_progress: Option<PropertyDoubleKeyHashMap<i32, i32, i32>>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct TipsInfo {
     #[tag = 1]
@@ -345,7 +389,8 @@ pub struct TipsInfo {
     pub tips_group_condition_progress: Option<PropertyDoubleKeyHashMap<i32, i32, i32>>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct MainCityQuestData {
     #[tag = 1]
@@ -354,27 +399,31 @@ pub struct MainCityQuestData {
     pub in_progress_quests: Option<Vec<i32>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EmbattleInfo {
     pub avatars: Vec<i32>,
     pub buddy: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct Embattles {
     #[tag = 1]
     pub last_embattles: Option<PropertyHashMap<QuestType, EmbattleInfo>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct DayChangeInfo {
     #[tag = 1]
     pub last_daily_refresh_timing: Option<u64>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InteractInfo {
     pub interact_id: i32,
     pub interact_shape: u16,
@@ -387,13 +436,15 @@ pub struct InteractInfo {
     pub scale_r: f64,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EventGraphsInfo {
     pub event_graphs_info: PropertyHashMap<i32, EventGraphInfo>,
     pub default_event_graph_id: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PlayerNPCInfo {
     pub interact_info: InteractInfo,
     pub npc_uid: u64,
@@ -405,7 +456,8 @@ pub struct PlayerNPCInfo {
     pub look_ik: bool,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct PlayerNPCsInfo {
     #[tag = 1]
@@ -414,14 +466,16 @@ pub struct PlayerNPCsInfo {
     pub destroy_npc_when_leave_section: Option<PropertyHashSet<u64>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ToExecuteScriptInfo {
     pub remove_after_finish: bool,
     pub specials: PropertyHashMap<String, i64>,
     pub event_graphs: PropertyHashSet<i32>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct MUIPData {
     #[tag = 1]
@@ -446,7 +500,8 @@ pub struct MUIPData {
     pub language_type: Option<u16>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct RamenData {
     #[tag = 1]
@@ -494,7 +549,8 @@ pub struct RamenData {
     pub new_unlock_initiative_item: Option<PropertyHashSet<i32>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GoodsInfo {
     pub id: i32,
     pub purchased_num: u32,
@@ -502,14 +558,16 @@ pub struct GoodsInfo {
     pub discount: u16,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ShelfInfo {
     pub id: i32,
     pub custom_goods_in_shelf: PropertyHashSet<i32>,
     pub goods_info: PropertyHashMap<i32, GoodsInfo>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ShopInfo {
     pub id: i32,
     pub shelf_info: PropertyHashMap<i32, ShelfInfo>,
@@ -517,7 +575,8 @@ pub struct ShopInfo {
     pub last_refresh_time: u64,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct ShopsInfo {
     #[tag = 1]
@@ -530,7 +589,8 @@ pub struct ShopsInfo {
     pub shop_buy_times: Option<i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct VHSTrendingInfo {
     pub trend_id: i32,
     pub state: u16,
@@ -538,21 +598,24 @@ pub struct VHSTrendingInfo {
     pub is_accept: bool,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct VHSTrendingCfgInfo {
     pub trend_id: i32,
     pub complete_level: i16,
     pub know_state: i16,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct VHSNpcInfo {
     pub npc_id: i32,
     pub state: i16,
     pub new_know: bool,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct VHSStoreData {
     #[tag = 1]
@@ -649,7 +712,8 @@ pub struct VHSStoreData {
     pub total_received_reward_times: Option<i32>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct OperationMailReceiveInfo {
     #[tag = 1]
@@ -658,20 +722,23 @@ pub struct OperationMailReceiveInfo {
     pub condition_progress: Option<PropertyDoubleKeyHashMap<i32, i32, i32>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct PayInfo {
     #[tag = 1]
     pub month_total_pay: Option<i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NpcSceneData {
     pub section_id: i32,
     pub transform: Transform,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NpcInfo {
     pub uid: u64,
     pub id: i32,
@@ -683,7 +750,8 @@ pub struct NpcInfo {
     pub references: PropertyHashSet<u64>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct BattleEventInfo {
     #[tag = 1]
@@ -702,7 +770,8 @@ pub struct BattleEventInfo {
     pub alread_battle_stage: Option<Vec<String>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct GMData {
     #[tag = 1]
@@ -716,20 +785,23 @@ pub struct GMData {
     pub register_conditions: Option<PropertyHashSet<String>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PlayerMailExtInfo {
     pub timestamp: u64,
     pub mail_state: MailState,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct PlayerMailExtInfos {
     #[tag = 1]
     pub player_mail_ext_info: Option<PropertyHashMap<String, PlayerMailExtInfo>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DungeonTable {
     pub uid: u64,
     pub id: i32,
@@ -738,7 +810,8 @@ pub struct DungeonTable {
     pub to_be_destroyed: bool,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SceneTable {
     pub uid: u64,
     pub id: i32,
@@ -747,7 +820,8 @@ pub struct SceneTable {
     pub to_be_destroyed: bool,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SectionInfo {
     pub id: i32,
     pub scene_uid: u64,
@@ -755,7 +829,8 @@ pub struct SectionInfo {
     pub section_info_ext: SectionInfoExt,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct SingleDungeonGroup {
     #[tag = 1]
@@ -768,7 +843,8 @@ pub struct SingleDungeonGroup {
     pub npcs: Option<PropertyDoubleKeyHashMap<u64, u64, NpcInfo>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct NewbieInfo {
     #[tag = 1]
@@ -778,7 +854,8 @@ pub struct NewbieInfo {
     pub condition_progress: Option<PropertyDoubleKeyHashMap<i32, i32, i32>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct LoadingPageTipsInfo {
     #[tag = 1]
@@ -788,7 +865,8 @@ pub struct LoadingPageTipsInfo {
     pub condition_progress: Option<PropertyDoubleKeyHashMap<i32, i32, i32>>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct CollectMap {
     #[tag = 1]
@@ -829,13 +907,15 @@ pub struct CollectMap {
     pub new_event_icon_map: Option<PropertyHashSet<i32>>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AreaNPCInfo {
     pub tag_id: i32,
     pub interacts: PropertyHashSet<i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AreaOwnerInfo {
     pub owner_type: u16,
     pub owner_id: i32,
@@ -843,7 +923,8 @@ pub struct AreaOwnerInfo {
     pub sequence: u32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct AreasInfo {
     #[tag = 1]
@@ -852,21 +933,24 @@ pub struct AreasInfo {
     pub sequence: Option<u32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct BGMInfo {
     #[tag = 1]
     pub bgm_id: Option<i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object]
 pub struct HollowInfo {
     #[tag = 1]
     pub banned_hollow_event: Option<PropertyHashSet<i32>>,
 }
 
-#[derive(OctData, Clone, Debug, Default)]
+#[derive(OctData, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[property_object(u16, 0x01)]
 #[root]
 pub struct PlayerInfo {
@@ -995,25 +1079,29 @@ pub struct PlayerInfo {
 }
 
 #[derive(OctData, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PropertyKeyValue {
     pub key: PropertyType,
     pub value: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowShopModification {
     pub ability_modified_num: PropertyDoubleKeyHashMap<HollowShopType, String, i32>,
     pub action_modified_num: PropertyHashMap<HollowShopType, i32>,
     pub overwrite_price: PropertyHashMap<HollowShopType, i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowInitialStateOfPlayer {
     pub rogue_like_items: Vec<ItemInfo>,
     pub properties: PropertyDoubleKeyHashMap<u64, u16, i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PlayerHollowSectionInfo {
     pub prev_grid_index: u16,
     pub cur_grid_index: u16,
@@ -1024,13 +1112,15 @@ pub struct PlayerHollowSectionInfo {
     pub pos_before_move: u16,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EventStackFrame {
     pub action_info: ActionInfo,
     pub action_id: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EventInfo {
     pub id: i32,
     pub cur_action_id: i32,
@@ -1043,7 +1133,8 @@ pub struct EventInfo {
     pub stack_frames: Vec<EventStackFrame>, // CPLinkedList?
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowEventGraphInfo {
     // abstract EventGraphInfo part
     pub config_id: i32,
@@ -1101,7 +1192,7 @@ impl OctData for HollowEventGraphInfo {
         Ok(Self {
             config_id: {
                 if bt_property_tag == 0 {
-                    assert!(u16::unmarshal_from(r, bt_property_tag)? == 6);
+                    expect_field_count(r, 6)?;
                 }
 
                 OctData::unmarshal_from(r, bt_property_tag)?
@@ -1114,7 +1205,7 @@ impl OctData for HollowEventGraphInfo {
 
             fired_count: {
                 if bt_property_tag == 0 {
-                    assert!(u16::unmarshal_from(r, bt_property_tag)? == 4);
+                    expect_field_count(r, 4)?;
                 }
 
                 OctData::unmarshal_from(r, bt_property_tag)?
@@ -1126,7 +1217,43 @@ impl OctData for HollowEventGraphInfo {
     }
 }
 
-#[derive(OctData, Clone, Debug)]
+qwer::oct_arbitrary! {
+    impl qwer::OctArbitrary for HollowEventGraphInfo {
+        fn arbitrary(g: &mut qwer::Gen) -> Self {
+            use qwer::OctArbitrary;
+
+            Self {
+                config_id: OctArbitrary::arbitrary(g),
+                events_info: OctArbitrary::arbitrary(g),
+                specials: OctArbitrary::arbitrary(g),
+                is_new: OctArbitrary::arbitrary(g),
+                finished: OctArbitrary::arbitrary(g),
+                list_specials: OctArbitrary::arbitrary(g),
+                fired_count: OctArbitrary::arbitrary(g),
+                hollow_event_template_id: OctArbitrary::arbitrary(g),
+                uid: OctArbitrary::arbitrary(g),
+                is_create_by_gm: OctArbitrary::arbitrary(g),
+            }
+        }
+    }
+}
+
+fn expect_field_count<R: std::io::Read>(r: &mut R, expected: u16) -> std::io::Result<()> {
+    let found = u16::unmarshal_from(r, 0)?;
+    if found != expected {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "Unexpected field count in HollowEventGraphInfo: expected {expected}, got {found}"
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PrepareSection {
     pub section_id: i32,
     pub initial_pos: u16,
@@ -1134,13 +1261,15 @@ pub struct PrepareSection {
     pub battle_end_goto_next_hollow: bool,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AbilityModifierInfo {
     pub uid: u64,
     pub added_scene_property: PropertyHashMap<ScenePropertyType, i32>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AbilityInfo {
     pub id: String,
     pub specials: PropertyHashMap<String, i64>,
@@ -1150,25 +1279,29 @@ pub struct AbilityInfo {
     pub sequence: u16,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AbilitiesInfo {
     pub abilities: PropertyHashMap<u64, AbilityInfo>,
     pub sequence_no: u16,
 }
 
-#[derive(OctData, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(OctData, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowDungeonAvatarInfo {
     pub uid: u64,
     pub properties_uid: u64,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowDungeonBuddyInfo {
     pub uid: u64,
     pub properties_uid: u64,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowLevelInfo {
     pub id: i32,
     pub chessboard_id: i32,
@@ -1176,14 +1309,16 @@ pub struct HollowLevelInfo {
     pub layer: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ToDoEventInfo {
     pub event_graph_uid: u64,
     pub start_node: String,
     pub event_id: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowGridMapInfo {
     pub grids: PropertyHashMap<u16, HollowGridInfo>,
     pub row_num: u8,
@@ -1197,14 +1332,16 @@ pub struct HollowGridMapInfo {
     pub end_grid: u16,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ChoiceInfo {
     pub id: i32,
     pub hide_info: bool,
     pub forbidden: bool,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowGridMapProtocolInfo {
     pub row: u8,
     pub col: u8,
@@ -1213,14 +1350,16 @@ pub struct HollowGridMapProtocolInfo {
     pub chessboard_id: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowGridProtocolInfo {
     pub grid: HollowGridInfo,
     pub event_type: HollowEventType,
     pub use_perform: bool,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HollowGridInfo {
     pub flag: i32,   // HollowGridFlag
     pub link_to: i8, // HollowGridLink
@@ -1230,13 +1369,15 @@ pub struct HollowGridInfo {
     pub node_visible: NodeVisible,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ConfigShopInfo {
     pub goods: Vec<ConfigItem>,
     pub currency: HollowShopCurrency,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ConfigItem {
     pub uid: i32,
     pub item_id: i32,
@@ -1246,20 +1387,23 @@ pub struct ConfigItem {
     pub discount: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EventListenerInfo {
     pub event_graph_id: i32,
     pub events_to_trigger: Vec<String>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BoundNPCAndInteractInfo {
     pub is_bound_npc: bool,
     pub interacts: PropertyHashSet<i32>,
     pub npc_reference_uid: u64,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LogSkillUseInfo {
     pub skill_name: String,
     pub damage: i32,
@@ -1268,7 +1412,8 @@ pub struct LogSkillUseInfo {
     pub hit_times: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LogBattleAvatarInfo {
     pub avatar_id: i32,
     pub avatar_uid: i64,
@@ -1287,7 +1432,8 @@ pub struct LogBattleAvatarInfo {
     pub skill_use: Vec<LogSkillUseInfo>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LogMonsterSkillUseInfo {
     pub skill_name: String,
     pub damage: i32,
@@ -1295,7 +1441,8 @@ pub struct LogMonsterSkillUseInfo {
     pub hit_times: i32,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LogMonsterInfo {
     pub monster_id: i32,
     pub monster_uid: i64,
@@ -1305,7 +1452,8 @@ pub struct LogMonsterInfo {
     pub skill_use: Vec<LogMonsterSkillUseInfo>,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LogTrapInfo {
     pub trap_id: i32,
     pub trap_uid: i64,
@@ -1314,7 +1462,8 @@ pub struct LogTrapInfo {
     pub is_trigger: u8,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LogBrokeItemInfo {
     pub broke_id: i32,
     pub broke_uid: i64,
@@ -1323,7 +1472,8 @@ pub struct LogBrokeItemInfo {
     pub is_broke: u8,
 }
 
-#[derive(OctData, Clone, Debug)]
+#[derive(OctData, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LogBattleStatistics {
     pub battle_uid: i64,
     pub battle_id: i32,
@@ -1337,3 +1487,224 @@ pub struct LogBattleStatistics {
     pub broke_item_list: Vec<LogBrokeItemInfo>,
     pub star: u8,
 }
+
+#[test]
+fn test_player_info_apply_delta() {
+    use qwer::{phashset, PropertyApply};
+
+    let mut player_info = PlayerInfo {
+        uid: Some(1),
+        unlock_info: Some(UnlockInfo {
+            unlocked_list: Some(phashset![1, 2]),
+            condition_progress: None,
+        }),
+        ..Default::default()
+    };
+
+    player_info.apply(PlayerInfo {
+        account_name: Some(String::from("ReversedRooms")),
+        unlock_info: Some(UnlockInfo {
+            unlocked_list: Some(PropertyHashSet::Modify {
+                to_add: vec![3],
+                to_remove: vec![1],
+            }),
+            condition_progress: None,
+        }),
+        ..Default::default()
+    });
+
+    assert_eq!(player_info.uid, Some(1));
+    assert_eq!(player_info.account_name.as_deref(), Some("ReversedRooms"));
+    assert_eq!(
+        player_info.unlock_info.unwrap().unlocked_list,
+        Some(phashset![2, 3])
+    );
+}
+
+#[test]
+fn test_player_info_diff_only_changed_fields() {
+    use qwer::{phashset, PropertyApply, PropertyDiff};
+
+    let old = PlayerInfo {
+        uid: Some(1),
+        account_name: Some(String::from("ReversedRooms")),
+        unlock_info: Some(UnlockInfo {
+            unlocked_list: Some(phashset![1, 2]),
+            condition_progress: None,
+        }),
+        ..Default::default()
+    };
+
+    let mut new = old.clone();
+    new.unlock_info
+        .as_mut()
+        .unwrap()
+        .unlocked_list
+        .as_mut()
+        .unwrap()
+        .insert(3);
+
+    let delta = PlayerInfo::diff(&old, &new).unwrap();
+    assert!(delta.uid.is_none());
+    assert!(delta.account_name.is_none());
+    assert_eq!(
+        delta.unlock_info.as_ref().unwrap().unlocked_list,
+        Some(PropertyHashSet::Modify {
+            to_add: vec![3],
+            to_remove: Vec::new(),
+        })
+    );
+
+    let mut applied = old;
+    applied.apply(delta);
+    assert_eq!(
+        applied.unlock_info.unwrap().unlocked_list,
+        Some(phashset![1, 2, 3])
+    );
+    assert!(PlayerInfo::diff(&new, &new).is_none());
+}
+
+#[test]
+fn test_player_info_schema() {
+    use qwer::{OctSchema, TypeSchema};
+
+    let TypeSchema::Struct(schema) = PlayerInfo::schema() else {
+        panic!("PlayerInfo should be described as a struct");
+    };
+    assert!(schema.property_object && schema.root);
+
+    let items = schema.fields.iter().find(|f| f.name == "items").unwrap();
+    assert_eq!(items.tag, Some(4));
+    assert_eq!(items.ty, "Option<PropertyHashMap<u64, ItemInfo>>");
+
+    let Some(TypeSchema::Enum(item_info)) = PlayerInfo::schema()
+        .walk()
+        .into_iter()
+        .find(|schema| schema.name() == "ItemInfo")
+    else {
+        panic!("ItemInfo should be reachable from PlayerInfo");
+    };
+    let avatar = item_info
+        .variants
+        .iter()
+        .find(|v| v.name == "Avatar")
+        .unwrap();
+    assert_eq!(avatar.discriminant, 3);
+}
+
+#[test]
+fn test_decode_error_field_path() {
+    let mut payload = Vec::new();
+    RpcLoginRet::error(
+        ErrorCode::Fail,
+        vec![String::from("first"), String::from("second")],
+    )
+    .marshal_to(&mut payload, 0)
+    .unwrap();
+
+    // Cut through the empty trailing `account_info` blob into the last param.
+    let err = qwer::decode::<RpcLoginRet>(&payload[..payload.len() - 7])
+        .err()
+        .unwrap();
+    assert_eq!(err.path(), "RpcLoginRet.error_code_params[1]");
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    payload[..4].copy_from_slice(&12345i32.to_le_bytes());
+    let err = qwer::decode::<RpcLoginRet>(&payload).err().unwrap();
+    assert_eq!(err.path(), "RpcLoginRet.error_code");
+    assert_eq!(err.expected_found().unwrap().found, "12345");
+    assert_eq!(err.offset(), Some(4));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_player_info_serde_round_trip() {
+    use qwer::{pdkhashmap, phashmap, phashset};
+
+    let player_info = PlayerInfo {
+        uid: Some(1),
+        items: Some(phashmap![(
+            2,
+            ItemInfo::Avatar {
+                uid: 2,
+                id: 1011,
+                count: 1,
+                package: 3,
+                first_get_time: 0,
+                star: 1,
+                exp: 0,
+                level: 1,
+                rank: 1,
+                unlocked_talent_num: 0,
+                skills: phashmap![(0, 1)],
+                is_custom_by_dungeon: true,
+                robot_id: 0,
+            }
+        )]),
+        unlock_info: Some(UnlockInfo {
+            unlocked_list: Some(phashset![1]),
+            condition_progress: None,
+        }),
+        scene_properties: Some(pdkhashmap![(3, 1001, 100)]),
+        ..Default::default()
+    };
+
+    let json = serde_json::to_string(&player_info).unwrap();
+    let restored: PlayerInfo = serde_json::from_str(&json).unwrap();
+
+    assert!(matches!(
+        restored.items.as_ref().unwrap().get(&2),
+        Some(ItemInfo::Avatar { id: 1011, .. })
+    ));
+
+    let (mut expected, mut actual) = (Vec::new(), Vec::new());
+    player_info.marshal_to(&mut expected, 0).unwrap();
+    restored.marshal_to(&mut actual, 0).unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_property_visibility() {
+    use qwer::{CLIENT_PROP_FLAG, PERSIST_PROP_FLAG, SERVER_PROP_FLAG};
+
+    #[derive(OctData, Debug, PartialEq, Default)]
+    #[property_object]
+    struct Filtered {
+        #[tag = 1]
+        shared: Option<u32>,
+        #[tag = 2]
+        #[visibility(server, persist)]
+        server_only: Option<u32>,
+        #[tag = 3]
+        #[visibility(persist)]
+        persist_only: Option<u32>,
+    }
+
+    let value = Filtered {
+        shared: Some(1),
+        server_only: Some(2),
+        persist_only: Some(3),
+    };
+    let round_trip = |bt_property_tag| {
+        let mut payload = Vec::new();
+        value.marshal_to(&mut payload, bt_property_tag).unwrap();
+        Filtered::unmarshal_from(&mut &payload[..], bt_property_tag).unwrap()
+    };
+
+    assert_eq!(round_trip(0), value);
+    assert_eq!(round_trip(PERSIST_PROP_FLAG), value);
+    assert_eq!(
+        round_trip(SERVER_PROP_FLAG),
+        Filtered {
+            persist_only: None,
+            ..value
+        }
+    );
+    assert_eq!(
+        round_trip(CLIENT_PROP_FLAG),
+        Filtered {
+            shared: Some(1),
+            ..Default::default()
+        }
+    );
+}
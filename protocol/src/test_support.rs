@@ -0,0 +1,205 @@
+use std::any::type_name;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufReader;
+
+use qwer::{with_wire_revision, CaptureReader, CaptureRecord, Gen, OctArbitrary, CLIENT_PROP_FLAG};
+
+use super::*;
+
+const ROUND_TRIP_CASES: u64 = 64;
+// Holds one directory per client version with `.cap` files recorded from client sessions through
+// `capture_dir`, see `golden/README.md`.
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/golden");
+
+pub trait Testable: OctData + OctArbitrary + PartialEq + Debug {}
+
+impl<T: OctData + OctArbitrary + PartialEq + Debug> Testable for T {}
+
+/// Called with every type in the protocol registry, see `visit_protocols`.
+pub trait ProtocolVisitor {
+    fn request<T: ProtocolRequest + Testable>(&mut self)
+    where
+        T::Ret: Testable;
+
+    fn notify<T: ProtocolArg + Testable>(&mut self);
+}
+
+fn unmarshal_exact<T: OctData>(payload: &[u8], bt_property_tag: u16) -> Result<T, String> {
+    let mut reader = payload;
+    let value = T::unmarshal_from(&mut reader, bt_property_tag).map_err(|err| err.to_string())?;
+    if !reader.is_empty() {
+        return Err(format!("{} trailing bytes", reader.len()));
+    }
+
+    Ok(value)
+}
+
+/// Asserts that generated values of `T` come back unchanged from a marshal/unmarshal round trip.
+pub fn check_round_trip<T: Testable>(bt_property_tag: u16) {
+    for seed in 0..ROUND_TRIP_CASES {
+        let value = T::arbitrary(&mut Gen::new(seed));
+        let mut payload = Vec::new();
+        value.marshal_to(&mut payload, bt_property_tag).unwrap();
+
+        let decoded = unmarshal_exact::<T>(&payload, bt_property_tag)
+            .unwrap_or_else(|err| panic!("{} (seed {seed}): {err}", type_name::<T>()));
+        assert_eq!(decoded, value, "{} (seed {seed})", type_name::<T>());
+    }
+}
+
+fn remarshal<T: OctData>(value: &T, bt_property_tag: u16) -> Result<Vec<u8>, String> {
+    let mut payload = Vec::new();
+    value
+        .marshal_to(&mut payload, bt_property_tag)
+        .map_err(|err| err.to_string())?;
+    Ok(payload)
+}
+
+/// Decodes a captured payload and checks that marshaling it back gives the same bytes.
+pub fn check_payload<T: OctData + PartialEq + Debug>(
+    payload: &[u8],
+    bt_property_tag: u16,
+) -> Result<T, String> {
+    let value = unmarshal_exact::<T>(payload, bt_property_tag)?;
+    let remarshaled = remarshal(&value, bt_property_tag)?;
+
+    if remarshaled != payload {
+        let offset = remarshaled
+            .iter()
+            .zip(payload)
+            .position(|(a, b)| a != b)
+            .unwrap_or(remarshaled.len().min(payload.len()));
+        return Err(format!(
+            "marshaled back to {} bytes instead of {}, first difference at byte {offset}",
+            remarshaled.len(),
+            payload.len()
+        ));
+    }
+
+    Ok(value)
+}
+
+struct RoundTrip;
+
+impl ProtocolVisitor for RoundTrip {
+    fn request<T: ProtocolRequest + Testable>(&mut self)
+    where
+        T::Ret: Testable,
+    {
+        check_round_trip::<T>(0);
+        check_round_trip::<T::Ret>(0);
+    }
+
+    fn notify<T: ProtocolArg + Testable>(&mut self) {
+        check_round_trip::<T>(0);
+    }
+}
+
+struct GoldenCheck<'a> {
+    protocol_id: u16,
+    record: &'a CaptureRecord,
+    result: Option<Result<(), String>>,
+}
+
+impl ProtocolVisitor for GoldenCheck<'_> {
+    fn request<T: ProtocolRequest + Testable>(&mut self)
+    where
+        T::Ret: Testable,
+    {
+        if T::ID != self.protocol_id {
+            return;
+        }
+
+        let payload = &self.record.payload;
+        self.result = Some(if self.record.header.is_rpc_ret {
+            check_payload::<T::Ret>(payload, 0).and_then(|_| check_property_blobs(T::ID, payload))
+        } else {
+            check_payload::<T>(payload, 0).map(drop)
+        });
+    }
+
+    fn notify<T: ProtocolArg + Testable>(&mut self) {
+        if T::ID != self.protocol_id {
+            return;
+        }
+
+        self.result = Some(if self.record.header.is_rpc_ret {
+            Err(String::from("rpc ret for a notify"))
+        } else {
+            check_payload::<T>(&self.record.payload, 0).map(drop)
+        });
+    }
+}
+
+// Property blobs are opaque to the rets carrying them, so their contents are checked here.
+fn check_property_blobs(protocol_id: u16, payload: &[u8]) -> Result<(), String> {
+    match protocol_id {
+        RPC_LOGIN_ID => {
+            let ret = unmarshal_exact::<RpcLoginRet>(payload, 0)?;
+            check_payload::<AccountInfo>(&ret.account_info.stream, CLIENT_PROP_FLAG).map(drop)
+        }
+        RPC_ENTER_WORLD_ID => {
+            let ret = unmarshal_exact::<RpcEnterWorldRet>(payload, 0)?;
+            check_payload::<PlayerInfo>(&ret.player_info.stream, CLIENT_PROP_FLAG).map(drop)
+        }
+        RPC_REENTER_WORLD_ID => {
+            let ret = unmarshal_exact::<RpcReenterWorldRet>(payload, 0)?;
+            check_payload::<PlayerInfo>(&ret.player_info.stream, CLIENT_PROP_FLAG).map(drop)
+        }
+        _ => Ok(()),
+    }
+}
+
+#[test]
+fn test_protocol_round_trip() {
+    visit_protocols(&mut RoundTrip);
+}
+
+#[test]
+fn test_property_blob_round_trip() {
+    check_round_trip::<AccountInfo>(0);
+    check_round_trip::<AccountInfo>(CLIENT_PROP_FLAG);
+    check_round_trip::<PlayerInfo>(0);
+    check_round_trip::<PlayerInfo>(CLIENT_PROP_FLAG);
+}
+
+#[test]
+fn test_golden_captures() {
+    for version in PROTOCOL_VERSIONS {
+        let dir = format!("{GOLDEN_DIR}/{}", version.name);
+        let captures = std::fs::read_dir(&dir)
+            .unwrap_or_else(|err| panic!("no golden captures for {}: {err}", version.name))
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "cap"))
+            .collect::<Vec<_>>();
+        assert!(!captures.is_empty(), "no golden captures in {dir}");
+
+        for path in captures {
+            let mut checked = 0;
+            let reader = CaptureReader::new(BufReader::new(File::open(&path).unwrap())).unwrap();
+            for (i, record) in reader.enumerate() {
+                let record = record.unwrap();
+                let mut check = GoldenCheck {
+                    protocol_id: version.protocol_id(record.protocol_id),
+                    record: &record,
+                    result: None,
+                };
+                with_wire_revision(version.revision, || visit_protocols(&mut check));
+
+                match check.result {
+                    Some(Ok(())) => checked += 1,
+                    Some(Err(err)) => panic!(
+                        "{} record {i} (protocol {}): {err}",
+                        path.display(),
+                        check.protocol_id
+                    ),
+                    // Protocols without definitions yet are left to the decoder.
+                    None => (),
+                }
+            }
+
+            assert!(checked > 0, "no known payloads in {}", path.display());
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use qwer::{with_wire_revision, DecodeError, OctData};
+
+/// Wire differences of one client build relative to the ids in `protocol_id` and the current
+/// struct layouts.
+///
+/// `RpcLogin` must keep its id in every version: the client announces its build in it, so it
+/// is always read with the latest version.
+#[derive(Debug)]
+pub struct ProtocolVersion {
+    pub name: &'static str,
+    /// Layout revision for `#[since = N]` fields.
+    pub revision: u32,
+    /// Protocols whose id differs from `protocol_id`, as `(id, id on the wire)`.
+    pub remapped_ids: &'static [(u16, u16)],
+}
+
+impl ProtocolVersion {
+    #[must_use]
+    pub fn wire_id(&self, protocol_id: u16) -> u16 {
+        self.remapped_ids
+            .iter()
+            .find(|(id, _)| *id == protocol_id)
+            .map_or(protocol_id, |(_, wire_id)| *wire_id)
+    }
+
+    #[must_use]
+    pub fn protocol_id(&self, wire_id: u16) -> u16 {
+        self.remapped_ids
+            .iter()
+            .find(|(_, id)| *id == wire_id)
+            .map_or(wire_id, |(protocol_id, _)| *protocol_id)
+    }
+
+    pub fn marshal(&self, data: &impl OctData, bt_property_tag: u16) -> std::io::Result<Vec<u8>> {
+        let mut payload = Vec::new();
+        with_wire_revision(self.revision, || {
+            data.marshal_to(&mut payload, bt_property_tag)
+        })?;
+        Ok(payload)
+    }
+
+    pub fn decode<T: OctData>(&self, payload: &[u8]) -> Result<T, DecodeError> {
+        with_wire_revision(self.revision, || qwer::decode(payload))
+    }
+
+    /// The build the protocol definitions are written against.
+    #[must_use]
+    pub fn latest() -> &'static Self {
+        PROTOCOL_VERSIONS.last().unwrap()
+    }
+}
+
+// Oldest first; add an entry with its remapped ids and a new revision for each supported build.
+pub static PROTOCOL_VERSIONS: &[ProtocolVersion] = &[ProtocolVersion {
+    name: "0.1.0",
+    revision: 0,
+    remapped_ids: &[],
+}];
+
+#[must_use]
+pub fn lookup_version(name: &str) -> Option<&'static ProtocolVersion> {
+    PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| version.name == name)
+}
+
+#[test]
+fn test_version_remapped_ids() {
+    let version = ProtocolVersion {
+        name: "test",
+        revision: 0,
+        remapped_ids: &[(crate::RPC_ENTER_WORLD_ID, 2000)],
+    };
+
+    assert_eq!(version.wire_id(crate::RPC_ENTER_WORLD_ID), 2000);
+    assert_eq!(version.protocol_id(2000), crate::RPC_ENTER_WORLD_ID);
+    assert_eq!(version.wire_id(crate::RPC_LOGIN_ID), crate::RPC_LOGIN_ID);
+    assert_eq!(
+        version.protocol_id(crate::RPC_LOGIN_ID),
+        crate::RPC_LOGIN_ID
+    );
+}
+
+#[test]
+fn test_version_since_field() {
+    #[derive(OctData, Clone, Debug, PartialEq, Default)]
+    #[property_object]
+    struct Versioned {
+        #[tag = 1]
+        old: Option<u32>,
+        #[tag = 2]
+        #[since = 1]
+        new: Option<u32>,
+    }
+
+    let value = Versioned {
+        old: Some(1),
+        new: Some(2),
+    };
+    let old_version = ProtocolVersion {
+        name: "old",
+        revision: 0,
+        remapped_ids: &[],
+    };
+    let new_version = ProtocolVersion {
+        revision: 1,
+        ..old_version
+    };
+
+    let decoded: Versioned = old_version
+        .decode(&old_version.marshal(&value, 0).unwrap())
+        .unwrap();
+    assert_eq!((decoded.old, decoded.new), (Some(1), None));
+
+    let decoded: Versioned = new_version
+        .decode(&new_version.marshal(&value, 0).unwrap())
+        .unwrap();
+    assert_eq!((decoded.old, decoded.new), (Some(1), Some(2)));
+}
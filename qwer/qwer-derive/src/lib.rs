@@ -14,10 +14,18 @@ mod oct_data;
 ///
 /// In the presence of these property objects, all fields must be Optional, e.g. `Option<T>`,
 /// and must also have a tag attribute attached to them for marshalling and unmarshalling, of the
-/// form `#[tag = <number>]`.
+/// form `#[tag = <number>]`. Property objects also implement `PropertyApply` and `PropertyDiff`,
+/// which apply and compute deltas field by field. A property field marked `#[since = <revision>]`
+/// is only written while `qwer::wire_revision()` is at least that revision, for clients built
+/// before it existed, and one marked e.g. `#[visibility(server, persist)]` only while
+/// `bt_property_tag` is 0 or has one of those flags set (`qwer::CLIENT_PROP_FLAG` and friends), so
+/// one type can be written in full for persistence and filtered for the client.
 ///
 /// For enums, the structure starts with a discriminant with the type specified in the `#[repr]` of
 /// the enum, followed by the fields of the enum one by one.
+///
+/// Every derived type also implements `OctSchema`, a static description of the layout above
+/// (field names, types and tags, variant discriminants) that tools can walk without per-type code.
 #[proc_macro_derive(
     OctData,
     attributes(
@@ -25,6 +33,8 @@ mod oct_data;
         property_blob,
         skip_property,
         tag,
+        since,
+        visibility,
         root,
         base,
         polymorphic_none
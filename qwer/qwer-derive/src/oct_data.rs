@@ -86,10 +86,15 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
                         ))?,
                     });
 
+                    let check = expect_value(
+                        &quote!(#ty::unmarshal_from(r, bt_property_tag)?),
+                        &quote!(#value),
+                        &name,
+                    );
                     unmarshal_from.extend(match ty.to_string().as_str() {
                         "u8" | "u16" | "u32" | "u64" => {
                             quote! {
-                                assert!(#ty::unmarshal_from(r, bt_property_tag)? == #value, "Failed for {}", #name);
+                                #check
                             }
                         }
                         _ => Err(Error::new(
@@ -104,9 +109,14 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
                         }
                     });
 
+                    let check = expect_value(
+                        &quote!(u16::unmarshal_from(r, bt_property_tag)?),
+                        &quote!(#num_fields),
+                        &name,
+                    );
                     unmarshal_from.extend(quote! {
                         if bt_property_tag == 0 {
-                            assert!(u16::unmarshal_from(r, bt_property_tag)? == #num_fields, "Failed for {}", #name);
+                            #check
                         }
                     });
                 }
@@ -119,8 +129,10 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
 
                 for field in &data.fields {
                     let ident = &field.ident;
+                    let since = since_check(&field.attrs)?;
+                    let visible = visibility_check(&field.attrs)?;
                     loop_count_fields.extend(quote! {
-                        if self.#ident.is_some() {
+                        if self.#ident.is_some() #since #visible {
                             #generated_ident += 1;
                         }
                     });
@@ -136,21 +148,34 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
                 unmarshal_from.extend(quote! {
                     let num_fields: u16 = ::qwer::OctData::unmarshal_from(r, bt_property_tag)?;
                     if num_fields > #num_fields {
-                        return Err(::std::io::Error::new(
-                            ::std::io::ErrorKind::InvalidData,
-                            format!("Unexpected number of fields, expected at most {}, got {}", #num_fields, num_fields),
-                        ));
+                        return Err(::qwer::DecodeError::mismatch(
+                            format!("number of fields in {}", #name),
+                            format!("at most {}", #num_fields),
+                            num_fields,
+                        ).into());
                     }
                 });
+            } else if let Some(attr) = data.fields.iter().find_map(|field| {
+                find_attr(&field.attrs, "since").or_else(|| find_attr(&field.attrs, "visibility"))
+            }) {
+                return Err(Error::new(
+                    attr.span(),
+                    "#[since] and #[visibility] are only supported on property object fields",
+                ));
             } else if !is_packet {
                 marshal_to.extend(quote! {
                     if bt_property_tag == 0 {
                         #num_fields.marshal_to(w, bt_property_tag)?;
                     }
                 });
+                let check = expect_value(
+                    &quote!(u16::unmarshal_from(r, bt_property_tag)?),
+                    &quote!(#num_fields),
+                    &name,
+                );
                 unmarshal_from.extend(quote! {
                     if bt_property_tag == 0 {
-                        assert!(u16::unmarshal_from(r, bt_property_tag)? == #num_fields, "Failed for {}", #name);
+                        #check
                     }
                 });
             }
@@ -177,6 +202,7 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
                 let mut fields = quote!();
                 let mut nones = quote!();
                 let mut cases = quote!();
+                let mut tags = Vec::new();
 
                 for field in &data.fields {
                     let ident = &field.ident;
@@ -204,23 +230,35 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
                         .to_string()
                         .parse::<u16>()
                         .unwrap();
+                    tags.push(case.to_string());
 
+                    // Skipped properties come back present but empty, so they're written back
+                    // the way they were read.
                     if find_attr(&field.attrs, "skip_property").is_some() {
                         if is_root_object {
                             cases.extend(quote! {
-                                #case => { u32::unmarshal_from(r, bt_property_tag)?; #ident = None }
+                                #case => {
+                                    let len = u32::unmarshal_from(r, bt_property_tag)?;
+                                    ::qwer::read_bytes(r, len as usize)?;
+                                    #ident = Some(::std::default::Default::default())
+                                }
                             });
                         } else {
                             cases.extend(quote! {
-                                #case => { #ident = None }
+                                #case => { #ident = Some(::std::default::Default::default()) }
                             });
                         }
                     } else {
                         let read_case =
                             read_property_field(&field.ty, &field.attrs, is_root_object, None);
 
+                        let field_name = ident.as_ref().unwrap().to_string();
+
                         cases.extend(quote! {
-                            #case => { #ident = Some(#read_case) }
+                            #case => {
+                                let read = (|| ::std::io::Result::Ok(#read_case))();
+                                #ident = Some(::qwer::DecodeError::in_field(read, #name, #field_name)?);
+                            }
                         });
                     }
                 }
@@ -231,16 +269,18 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
 
                 unmarshal_from.extend(field_assign);
 
+                let known_tags = format!("one of {}", tags.join(", "));
                 let r#for = quote!(
                     for _ in 0..num_fields {
                         let tag: u16 = ::qwer::OctData::unmarshal_from(r, bt_property_tag)?;
                         match tag {
                             #cases
                             libnignhaccjpkd_so => {
-                                return Err(::std::io::Error::new(
-                                    ::std::io::ErrorKind::InvalidData,
-                                    format!("Unexpected tag {}", libnignhaccjpkd_so),
-                                ));
+                                return Err(::qwer::DecodeError::mismatch(
+                                    format!("tag in {}", #name),
+                                    #known_tags,
+                                    libnignhaccjpkd_so,
+                                ).into());
                             }
                         }
                     }
@@ -270,6 +310,7 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
                     },
                     is_root_object,
                     None,
+                    (&name, ""),
                 );
 
                 quote! {
@@ -371,7 +412,13 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
                     false,
                     base,
                 );
-                let fields_read = read_fields(&variant.fields, read_field, false, base);
+                let fields_read = read_fields(
+                    &variant.fields,
+                    read_field,
+                    false,
+                    base,
+                    (&item_name.to_string(), &var_name.to_string()),
+                );
                 let fields_print = print_fields(&variant.fields);
 
                 let marshal_header = quote! {
@@ -409,18 +456,34 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
             };
 
             let read_repr = quote!(#repr_ty);
+            let enum_name = item_name.to_string();
+            let known_discrims = format!(
+                "one of {}",
+                data.variants
+                    .iter()
+                    .filter_map(|variant| variant.discriminant.as_ref())
+                    .map(|(_, discrim)| discrim.to_token_stream().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
 
             let unmarshal_from = if base == Some(0) {
+                let check = expect_value(
+                    &quote!(u16::unmarshal_from(r, bt_property_tag)?),
+                    &quote!(0u16),
+                    &item_name.to_string(),
+                );
                 quote! {{
                     let id = #read_repr::unmarshal_from(r, bt_property_tag)?;
                     if id != 0xFFFF {
-                        assert!(u16::unmarshal_from(r, bt_property_tag)? == 0);
+                        #check
                     }
                     match id {
                         #(#unmarshal_vars,)*
-                        _ => Err(::std::io::Error::new(
-                            ::std::io::ErrorKind::InvalidData,
-                            format!("Unexpected discriminant {}", id),
+                        _ => Err(::qwer::DecodeError::mismatch(
+                            format!("discriminant of {}", #enum_name),
+                            #known_discrims,
+                            id,
                         ))?,
                     }
                 }}
@@ -429,9 +492,10 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
                     let id = #read_repr::unmarshal_from(r, bt_property_tag)?;
                     match id {
                         #(#unmarshal_vars,)*
-                        _ => Err(::std::io::Error::new(
-                            ::std::io::ErrorKind::InvalidData,
-                            format!("Unexpected discriminant {}", id),
+                        _ => Err(::qwer::DecodeError::mismatch(
+                            format!("discriminant of {}", #enum_name),
+                            #known_discrims,
+                            id,
                         ))?,
                     }
                 }}
@@ -449,7 +513,56 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
         ))?,
     };
 
+    // Property objects apply and diff deltas field by field, absent fields mean "unchanged".
+    let property_delta = match &item.data {
+        Data::Struct(data) if find_attr(&item.attrs, "property_object").is_some() => {
+            let idents = data
+                .fields
+                .iter()
+                .map(|field| &field.ident)
+                .collect::<Vec<_>>();
+            quote! {
+                #[automatically_derived]
+                impl #lifetimes ::qwer::PropertyApply for #item_name #lifetimes {
+                    fn apply(&mut self, delta: Self) {
+                        #(::qwer::PropertyApply::apply(&mut self.#idents, delta.#idents);)*
+                    }
+                }
+
+                #[automatically_derived]
+                impl #lifetimes ::qwer::PropertyDiff for #item_name #lifetimes {
+                    fn diff(old: &Self, new: &Self) -> Option<Self> {
+                        let delta = Self {
+                            #(#idents: ::qwer::PropertyDiff::diff(&old.#idents, &new.#idents).flatten(),)*
+                        };
+
+                        if [#(delta.#idents.is_none()),*].iter().all(|unchanged| *unchanged) {
+                            None
+                        } else {
+                            Some(delta)
+                        }
+                    }
+                }
+            }
+        }
+        _ => quote!(),
+    };
+
+    let schema = type_schema(item)?;
+    let arbitrary = arbitrary_impl(item);
+
     Ok(quote! {
+        #property_delta
+        #arbitrary
+
+        #[automatically_derived]
+        impl #lifetimes ::qwer::OctSchema for #item_name #lifetimes {
+            fn schema() -> &'static ::qwer::TypeSchema {
+                static SCHEMA: ::qwer::TypeSchema = #schema;
+                &SCHEMA
+            }
+        }
+
         #[automatically_derived]
         impl #lifetimes ::qwer::OctData for #item_name #lifetimes {
             fn marshal_to<W: ::std::io::Write>(&self, w: &mut W, mut bt_property_tag: u16) -> ::std::io::Result<()> {
@@ -474,6 +587,221 @@ pub fn imp(item: &DeriveInput) -> Result<TokenStream> {
     })
 }
 
+// Random values for round-trip tests, only expanded when qwer's `arbitrary` feature is on.
+fn arbitrary_impl(item: &DeriveInput) -> TokenStream {
+    if item.generics.lifetimes().next().is_some() {
+        return quote!();
+    }
+
+    let item_name = &item.ident;
+    let body = match &item.data {
+        Data::Struct(data) => {
+            let is_property_object = find_attr(&item.attrs, "property_object").is_some();
+            let fields = arbitrary_fields(&data.fields, is_property_object);
+            quote!(Self #fields)
+        }
+        Data::Enum(data) => {
+            let variants = data.variants.iter().enumerate().map(|(i, variant)| {
+                let var_name = &variant.ident;
+                if find_attr(&variant.attrs, "polymorphic_none").is_some() {
+                    quote!(#i => Self::#var_name {})
+                } else {
+                    let fields = arbitrary_fields(&variant.fields, false);
+                    quote!(#i => Self::#var_name #fields)
+                }
+            });
+            let count = data.variants.len();
+            quote! {
+                match g.below(#count) {
+                    #(#variants,)*
+                    _ => unreachable!(),
+                }
+            }
+        }
+        Data::Union(_) => return quote!(),
+    };
+
+    quote! {
+        ::qwer::oct_arbitrary! {
+            #[automatically_derived]
+            impl ::qwer::OctArbitrary for #item_name {
+                fn arbitrary(g: &mut ::qwer::Gen) -> Self {
+                    #body
+                }
+            }
+        }
+    }
+}
+
+fn arbitrary_fields(fields: &Fields, is_property_object: bool) -> TokenStream {
+    let values = fields.iter().map(|field| {
+        let value = if !is_property_object {
+            quote!(::qwer::OctArbitrary::arbitrary(g))
+        } else if find_attr(&field.attrs, "skip_property").is_some() {
+            // Skipped properties only write their tag and always read back empty.
+            quote!(g.gen_bool().then(::std::default::Default::default))
+        } else {
+            quote!(g.gen_bool().then(|| ::qwer::OctArbitrary::arbitrary(g)))
+        };
+
+        match &field.ident {
+            Some(ident) => quote!(#ident: #value),
+            None => value,
+        }
+    });
+
+    match fields {
+        Fields::Named(_) => quote!({ #(#values),* }),
+        Fields::Unnamed(_) => quote!(( #(#values),* )),
+        Fields::Unit => quote!(),
+    }
+}
+
+// Builds the `TypeSchema` expression describing the item's wire layout.
+fn type_schema(item: &DeriveInput) -> Result<TokenStream> {
+    let name = item.ident.to_string();
+    // Statics can't name the item's lifetimes, so nested schemas aren't linked for those.
+    let link_nested = item.generics.lifetimes().next().is_none();
+
+    match &item.data {
+        Data::Struct(data) => {
+            let property_object = find_attr(&item.attrs, "property_object");
+            let padding = match property_object.map(Attribute::parse_args::<PropertyObject>) {
+                Some(Ok(PropertyObject { ty, value })) => {
+                    let ty = ty.to_string();
+                    quote!(Some(::qwer::PaddingSchema { ty: #ty, value: #value as u64 }))
+                }
+                _ => quote!(None),
+            };
+            let is_property_object = property_object.is_some();
+            let is_root = find_attr(&item.attrs, "root").is_some();
+            let fields = field_schemas(&data.fields, link_nested)?;
+
+            Ok(quote! {
+                ::qwer::TypeSchema::Struct(::qwer::StructSchema {
+                    name: #name,
+                    property_object: #is_property_object,
+                    padding: #padding,
+                    root: #is_root,
+                    fields: &[#(#fields),*],
+                })
+            })
+        }
+        Data::Enum(data) => {
+            let repr = find_attr(&item.attrs, "repr")
+                .ok_or_else(|| Error::new(item.span(), "Enum packets must declare #[repr]"))?
+                .parse_args::<Ident>()?
+                .to_string();
+            let base = match find_attr(&item.attrs, "base").map(|attr| &attr.meta) {
+                Some(Meta::NameValue(MetaNameValue { value, .. })) => quote!(Some(#value)),
+                _ => quote!(None),
+            };
+
+            let mut variants = Vec::with_capacity(data.variants.len());
+            for variant in &data.variants {
+                let var_name = variant.ident.to_string();
+                let Some((_, discrim)) = &variant.discriminant else {
+                    return Err(Error::new(
+                        variant.span(),
+                        "All enum packet variants must have discriminants",
+                    ));
+                };
+                let polymorphic_none = find_attr(&variant.attrs, "polymorphic_none").is_some();
+                let fields = field_schemas(&variant.fields, link_nested)?;
+
+                variants.push(quote! {
+                    ::qwer::VariantSchema {
+                        name: #var_name,
+                        discriminant: (#discrim) as i64,
+                        polymorphic_none: #polymorphic_none,
+                        fields: &[#(#fields),*],
+                    }
+                });
+            }
+
+            Ok(quote! {
+                ::qwer::TypeSchema::Enum(::qwer::EnumSchema {
+                    name: #name,
+                    repr: #repr,
+                    base: #base,
+                    variants: &[#(#variants),*],
+                })
+            })
+        }
+        Data::Union(_) => Err(Error::new(
+            item.span(),
+            "Unions cannot be derived as Packet",
+        )),
+    }
+}
+
+fn field_schemas(fields: &Fields, link_nested: bool) -> Result<Vec<TokenStream>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = field
+                .ident
+                .as_ref()
+                .map_or_else(|| i.to_string(), ToString::to_string);
+            let ty = &field.ty;
+            let ty_name = type_name(ty);
+
+            let tag = match find_attr(&field.attrs, "tag").map(|attr| &attr.meta) {
+                Some(Meta::NameValue(MetaNameValue { value, .. })) => quote!(Some(#value)),
+                Some(attr) => {
+                    return Err(Error::new(
+                        attr.span(),
+                        format!("Expected #[tag = <value>] for field {name}"),
+                    ))
+                }
+                None => quote!(None),
+            };
+            let since = match find_attr(&field.attrs, "since").map(|attr| &attr.meta) {
+                Some(Meta::NameValue(MetaNameValue { value, .. })) => quote!(Some(#value)),
+                Some(attr) => {
+                    return Err(Error::new(attr.span(), "Expected #[since = <revision>]"))
+                }
+                None => quote!(None),
+            };
+            let visibility = match visibility_mask(&field.attrs)? {
+                Some(mask) => quote!(Some(#mask)),
+                None => quote!(None),
+            };
+            let skip_property = find_attr(&field.attrs, "skip_property").is_some();
+            let property_blob = find_attr(&field.attrs, "property_blob").is_some();
+            let nested = if link_nested {
+                quote!(<#ty as ::qwer::NestedSchemas>::nested_schemas)
+            } else {
+                quote!(|_| {})
+            };
+
+            Ok(quote! {
+                ::qwer::FieldSchema {
+                    name: #name,
+                    ty: #ty_name,
+                    tag: #tag,
+                    since: #since,
+                    visibility: #visibility,
+                    skip_property: #skip_property,
+                    property_blob: #property_blob,
+                    nested: #nested,
+                }
+            })
+        })
+        .collect()
+}
+
+// `Option < Vec < u8 > >` as printed by `quote` becomes `Option<Vec<u8>>`.
+fn type_name(ty: &Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+}
+
 fn find_attr<'a, I, S>(attr: I, name: S) -> Option<&'a Attribute>
 where
     I: IntoIterator<Item = &'a Attribute>,
@@ -482,6 +810,55 @@ where
     attr.into_iter().find(|attr| attr.path().is_ident(&name))
 }
 
+// `#[since = N]` leaves a property field out of the wire layout of revisions older than N.
+fn since_check(attrs: &[Attribute]) -> Result<TokenStream> {
+    match find_attr(attrs, "since").map(|attr| &attr.meta) {
+        Some(Meta::NameValue(MetaNameValue { value, .. })) => {
+            Ok(quote!(&& ::qwer::wire_revision() >= #value))
+        }
+        Some(meta) => Err(Error::new(meta.span(), "Expected #[since = <revision>]")),
+        None => Ok(quote!()),
+    }
+}
+
+// `#[visibility(client, server, persist)]` leaves a property field out unless one of its flags is
+// set in `bt_property_tag`.
+fn visibility_mask(attrs: &[Attribute]) -> Result<Option<TokenStream>> {
+    let Some(attr) = find_attr(attrs, "visibility") else {
+        return Ok(None);
+    };
+
+    let flags =
+        attr.parse_args_with(syn::punctuated::Punctuated::<Ident, Token![,]>::parse_terminated)?;
+    if flags.is_empty() {
+        return Err(Error::new(
+            attr.span(),
+            "#[visibility] needs at least one flag",
+        ));
+    }
+
+    let flags = flags
+        .iter()
+        .map(|flag| match flag.to_string().as_str() {
+            "client" => Ok(quote!(::qwer::CLIENT_PROP_FLAG)),
+            "server" => Ok(quote!(::qwer::SERVER_PROP_FLAG)),
+            "persist" => Ok(quote!(::qwer::PERSIST_PROP_FLAG)),
+            _ => Err(Error::new(
+                flag.span(),
+                "Expected one of client, server, persist",
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(quote!(#(#flags)|*)))
+}
+
+fn visibility_check(attrs: &[Attribute]) -> Result<TokenStream> {
+    Ok(visibility_mask(attrs)?
+        .map(|mask| quote!(&& ::qwer::is_visible(bt_property_tag, #mask)))
+        .unwrap_or_default())
+}
+
 fn write_fields<F, G, H>(
     fields: &Fields,
     access_named: F,
@@ -608,10 +985,34 @@ where
     operations
 }
 
-fn read_fields<R>(fields: &Fields, read: R, is_root: bool, base: Option<i16>) -> TokenStream
+// `path` is the type name and the prefix of field path segments (the variant, for enums), so
+// decode errors can point at the field that failed.
+fn read_fields<R>(
+    fields: &Fields,
+    read: R,
+    is_root: bool,
+    base: Option<i16>,
+    path: (&str, &str),
+) -> TokenStream
 where
     R: Fn(&Type, &[Attribute], bool, Option<i16>) -> TokenStream,
 {
+    let (ty_name, prefix) = path;
+    let in_field = |read_expr: TokenStream, field: String| {
+        let field = if prefix.is_empty() {
+            field
+        } else {
+            format!("{prefix}.{field}")
+        };
+        quote! {
+            ::qwer::DecodeError::in_field(
+                (|| ::std::io::Result::Ok(#read_expr))(),
+                #ty_name,
+                #field,
+            )?
+        }
+    };
+
     match fields {
         Fields::Named(fields) => {
             let fields = fields
@@ -624,10 +1025,15 @@ where
                         if i == 0 && base != 0 {
                             let read_op = read(&field.ty, &field.attrs, is_root, Some(base));
                             if base == 1 && fields.named.len() == base as usize {
+                                let check = expect_value(
+                                    &quote!(u16::unmarshal_from(r, bt_property_tag)?),
+                                    &quote!(0u16),
+                                    "base field count",
+                                );
                                 quote! {{
                                     let ret = #read_op;
                                     if bt_property_tag == 0 {
-                                        assert!(u16::unmarshal_from(r, bt_property_tag)? == 0);
+                                        #check
                                     }
                                     ret
                                 }}
@@ -654,6 +1060,7 @@ where
                     } else {
                         read(&field.ty, &field.attrs, is_root, None)
                     };
+                    let read_expr = in_field(read_expr, field_name.as_ref().unwrap().to_string());
                     quote!(#field_name: #read_expr)
                 })
                 .collect::<Vec<_>>();
@@ -688,7 +1095,7 @@ where
                     } else {
                         read(&field.ty, &field.attrs, is_root, None)
                     };
-                    quote!(#read_expr)
+                    in_field(read_expr, i.to_string())
                 })
                 .collect::<Vec<_>>();
             quote!(( #(#fields),* ))
@@ -890,10 +1297,14 @@ fn write_property_field(
         panic!("Failed to get tag id")
     };
 
+    // Attributes were validated by the field count above.
+    let since = since_check(attrs).unwrap();
+    let visible = visibility_check(attrs).unwrap();
+
     if find_attr(attrs, "skip_property").is_none() {
         if is_root {
             quote! {{
-                if #expr.is_some() {
+                if #expr.is_some() #since #visible {
                     let mut scratch = ::std::io::Cursor::new(Vec::new());
                     #property_quote
                     #expr.marshal_to(&mut scratch, bt_property_tag)?;
@@ -905,7 +1316,7 @@ fn write_property_field(
             }}
         } else {
             quote! {
-                if #expr.is_some() {
+                if #expr.is_some() #since #visible {
                     (#value as u16).marshal_to(w, bt_property_tag)?;
                     #property_quote
                     #expr.marshal_to(w, bt_property_tag)?;
@@ -914,7 +1325,7 @@ fn write_property_field(
         }
     } else if is_root {
         quote! {
-            if #expr.is_some() {
+            if #expr.is_some() #since #visible {
                 (#value as u16).marshal_to(w, bt_property_tag)?;
                 #property_quote
                 0u32.marshal_to(w, bt_property_tag)?;
@@ -922,7 +1333,7 @@ fn write_property_field(
         }
     } else {
         quote! {
-            if #expr.is_some() {
+            if #expr.is_some() #since #visible {
                 (#value as u16).marshal_to(w, bt_property_tag)?;
                 #property_quote
             }
@@ -940,27 +1351,30 @@ fn read_field(
         // read length, then read the field
         quote! {{
             let len = u32::unmarshal_from(r, bt_property_tag)?;
-            let mut buf = vec![0; len as usize];
-            r.read_exact(&mut buf)?;
+            let buf = ::qwer::read_bytes(r, len as usize)?;
             let mut scratch = ::std::io::Cursor::new(buf);
             ::qwer::OctData::unmarshal_from(&mut scratch, bt_property_tag)?
         }}
     } else if let Some(base_to_read) = base_to_read {
+        let base_to_read = base_to_read.unsigned_abs();
+        let check = expect_value(
+            &quote!(u16::unmarshal_from(r, bt_property_tag)?),
+            &quote!(#base_to_read),
+            "base field count",
+        );
         if base_to_read > 0 {
-            let base_to_read = base_to_read.unsigned_abs();
             quote! {{
                 if bt_property_tag == 0 {
-                    assert!(u16::unmarshal_from(r, bt_property_tag)? == #base_to_read);
+                    #check
                 }
                 ::qwer::OctData::unmarshal_from(r, bt_property_tag)?
             }}
         } else {
-            let base_to_read = base_to_read.unsigned_abs();
             let generated_ident = generate_ident(1, Span::call_site());
             quote! {{
                 let #generated_ident = ::qwer::OctData::unmarshal_from(r, bt_property_tag)?;
                 if bt_property_tag == 0 {
-                    assert!(u16::unmarshal_from(r, bt_property_tag)? == #base_to_read);
+                    #check
                 }
                 #generated_ident
             }}
@@ -1000,12 +1414,22 @@ fn read_property_field(
 
                 if is_root {
                     header = quote! {
-                        let len = u32::unmarshal_from(r, bt_property_tag)? - #pre_length;
+                        let len = u32::unmarshal_from(r, bt_property_tag)?
+                            .checked_sub(#pre_length)
+                            .ok_or_else(|| ::std::io::Error::new(
+                                ::std::io::ErrorKind::InvalidData,
+                                "Property field is shorter than its property object header",
+                            ))?;
                     };
                 }
+                let check = expect_value(
+                    &quote!(#ty::unmarshal_from(r, bt_property_tag)?),
+                    &quote!(#value),
+                    "property object header",
+                );
                 header = quote! {
                     #header
-                    assert!(#ty::unmarshal_from(r, bt_property_tag)? == #value);
+                    #check
                 };
             }
             _ => panic!("Only u8, u16, u32, and u64 are supported for #[property_object]"),
@@ -1015,8 +1439,7 @@ fn read_property_field(
     if is_root {
         quote! {{
             #header
-            let mut buf = vec![0; len as usize];
-            r.read_exact(&mut buf)?;
+            let buf = ::qwer::read_bytes(r, len as usize)?;
             let mut scratch = ::std::io::Cursor::new(buf);
             ::qwer::OctData::unmarshal_from(&mut scratch, bt_property_tag)?
         }}
@@ -1028,6 +1451,18 @@ fn read_property_field(
     }
 }
 
+// Reads a value that must match what the encoder always writes and bails out with
+// `InvalidData` instead of panicking when the peer sent something else.
+fn expect_value(read: &TokenStream, expected: &TokenStream, context: &str) -> TokenStream {
+    let what = format!("value in {context}");
+    quote! {{
+        let found = #read;
+        if found != #expected {
+            return Err(::qwer::DecodeError::mismatch(#what, #expected, found).into());
+        }
+    }}
+}
+
 fn generate_ident(i: usize, span: Span) -> Ident {
     Ident::new(&format!("generated_ident_{i}"), span)
 }
@@ -0,0 +1,24 @@
+/// Applies a property delta, as sent in `PtcPlayerInfoChanged`-like protocols, onto a value.
+///
+/// Plain values are replaced by the delta. Property collections apply `Modify` changes in
+/// place, and `#[property_object]` structs derived with `OctData` apply each present field.
+pub trait PropertyApply: Sized {
+    fn apply(&mut self, delta: Self);
+}
+
+impl<T> PropertyApply for T {
+    default fn apply(&mut self, delta: Self) {
+        *self = delta;
+    }
+}
+
+// Absent fields of a property object delta are left untouched.
+impl<T> PropertyApply for Option<T> {
+    fn apply(&mut self, delta: Self) {
+        match (self.as_mut(), delta) {
+            (Some(value), Some(delta)) => value.apply(delta),
+            (None, Some(delta)) => *self = Some(delta),
+            (_, None) => (),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+#[cfg(feature = "collection")]
+use crate::{
+    DoubleKeyHashMap, OctData, PropertyDoubleKeyHashMap, PropertyHashMap, PropertyHashSet,
+};
+
+// Collections get up to this many elements, enough to cover the length prefixes.
+const MAX_COLLECTION_LEN: usize = 3;
+
+/// Seeded generator for property-based round-trip tests.
+pub struct Gen(u64);
+
+impl Gen {
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    // splitmix64
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn gen_bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn collection_len(&mut self) -> usize {
+        self.below(MAX_COLLECTION_LEN + 1)
+    }
+}
+
+/// Values that survive a marshal/unmarshal round trip, implemented by `#[derive(OctData)]` when
+/// the `arbitrary` feature is enabled.
+pub trait OctArbitrary {
+    fn arbitrary(g: &mut Gen) -> Self;
+}
+
+/// Keeps `OctArbitrary` impls out of builds without the `arbitrary` feature, for types with
+/// hand-written `OctData` impls.
+#[cfg(feature = "arbitrary")]
+#[macro_export]
+macro_rules! oct_arbitrary {
+    ($($item:tt)*) => { $($item)* };
+}
+
+#[cfg(not(feature = "arbitrary"))]
+#[macro_export]
+macro_rules! oct_arbitrary {
+    ($($item:tt)*) => {};
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl OctArbitrary for $t {
+            fn arbitrary(g: &mut Gen) -> Self {
+                g.next_u64() as Self
+            }
+        })*
+    };
+}
+
+impl_integer!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+impl OctArbitrary for bool {
+    fn arbitrary(g: &mut Gen) -> Self {
+        g.gen_bool()
+    }
+}
+
+// NaN never compares equal, so floats stay finite.
+impl OctArbitrary for f32 {
+    fn arbitrary(g: &mut Gen) -> Self {
+        i16::arbitrary(g) as Self / 8.0
+    }
+}
+
+impl OctArbitrary for f64 {
+    fn arbitrary(g: &mut Gen) -> Self {
+        i32::arbitrary(g) as Self / 8.0
+    }
+}
+
+impl OctArbitrary for String {
+    fn arbitrary(g: &mut Gen) -> Self {
+        const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+        (0..g.below(8))
+            .map(|_| CHARSET[g.below(CHARSET.len())] as char)
+            .collect()
+    }
+}
+
+// A bare `None` writes nothing and reads back as `Some`; optional property fields are
+// generated by the derive instead.
+impl<T: OctArbitrary> OctArbitrary for Option<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Some(T::arbitrary(g))
+    }
+}
+
+impl<T: OctArbitrary> OctArbitrary for Vec<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        (0..g.collection_len()).map(|_| T::arbitrary(g)).collect()
+    }
+}
+
+impl<T: OctArbitrary + Eq + Hash> OctArbitrary for HashSet<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        (0..g.collection_len()).map(|_| T::arbitrary(g)).collect()
+    }
+}
+
+impl<K: OctArbitrary + Eq + Hash, V: OctArbitrary> OctArbitrary for HashMap<K, V> {
+    default fn arbitrary(g: &mut Gen) -> Self {
+        (0..g.collection_len())
+            .map(|_| (K::arbitrary(g), V::arbitrary(g)))
+            .collect()
+    }
+}
+
+// Deltas need at least one entry, an empty one reads back as an empty `Base`.
+#[cfg(feature = "collection")]
+fn delta_len(g: &mut Gen) -> usize {
+    g.below(MAX_COLLECTION_LEN) + 1
+}
+
+// Inner maps are flattened on the wire, so empty ones would be lost.
+#[cfg(feature = "collection")]
+fn double_key_map<K1, K2, V>(g: &mut Gen) -> DoubleKeyHashMap<K1, K2, V>
+where
+    K1: OctArbitrary + Eq + Hash,
+    K2: OctArbitrary + Eq + Hash,
+    V: OctArbitrary,
+{
+    let mut map = DoubleKeyHashMap::new();
+    for _ in 0..g.collection_len() {
+        map.entry(K1::arbitrary(g))
+            .or_insert_with(HashMap::new)
+            .insert(K2::arbitrary(g), V::arbitrary(g));
+    }
+    map
+}
+
+#[cfg(feature = "collection")]
+impl<K1, K2, V> OctArbitrary for DoubleKeyHashMap<K1, K2, V>
+where
+    K1: OctArbitrary + Eq + Hash,
+    K2: OctArbitrary + Eq + Hash,
+    V: OctArbitrary,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        double_key_map(g)
+    }
+}
+
+#[cfg(feature = "collection")]
+impl<K, V> OctArbitrary for PropertyHashMap<K, V>
+where
+    K: OctData + OctArbitrary + Eq + Hash,
+    V: OctData + OctArbitrary,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        if g.gen_bool() {
+            return Self::Base(HashMap::arbitrary(g));
+        }
+
+        let len = delta_len(g);
+        let to_add_len = g.below(len + 1);
+        Self::Modify {
+            to_add: (0..to_add_len)
+                .map(|_| (K::arbitrary(g), V::arbitrary(g)))
+                .collect(),
+            to_remove: (to_add_len..len).map(|_| K::arbitrary(g)).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "collection")]
+impl<T> OctArbitrary for PropertyHashSet<T>
+where
+    T: OctData + OctArbitrary + Eq + Hash,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        if g.gen_bool() {
+            return Self::Base(HashSet::arbitrary(g));
+        }
+
+        let len = delta_len(g);
+        let to_add_len = g.below(len + 1);
+        Self::Modify {
+            to_add: (0..to_add_len).map(|_| T::arbitrary(g)).collect(),
+            to_remove: (to_add_len..len).map(|_| T::arbitrary(g)).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "collection")]
+impl<K1, K2, V> OctArbitrary for PropertyDoubleKeyHashMap<K1, K2, V>
+where
+    K1: OctData + OctArbitrary + Eq + Hash,
+    K2: OctData + OctArbitrary + Eq + Hash,
+    V: OctData + OctArbitrary,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        if g.gen_bool() {
+            return Self::Base(double_key_map(g));
+        }
+
+        let len = delta_len(g);
+        let to_add_len = g.below(len + 1);
+        Self::Modify {
+            to_add: (0..to_add_len)
+                .map(|_| (K1::arbitrary(g), K2::arbitrary(g), V::arbitrary(g)))
+                .collect(),
+            to_remove: (to_add_len..len)
+                .map(|_| (K1::arbitrary(g), K2::arbitrary(g)))
+                .collect(),
+        }
+    }
+}
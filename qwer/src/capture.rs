@@ -0,0 +1,185 @@
+use std::io::{self, Read, Write};
+
+use crate::protocol::PROTOCOL_HEADER_SIZE;
+use crate::ProtocolHeader;
+
+const CAPTURE_MAGIC: &[u8; 6] = b"NAPCAP";
+const CAPTURE_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureDirection {
+    Inbound,
+    Outbound,
+}
+
+impl TryFrom<u8> for CaptureDirection {
+    type Error = io::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Inbound),
+            1 => Ok(Self::Outbound),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid capture direction {value}"),
+            )),
+        }
+    }
+}
+
+impl From<CaptureDirection> for u8 {
+    fn from(value: CaptureDirection) -> Self {
+        match value {
+            CaptureDirection::Inbound => 0,
+            CaptureDirection::Outbound => 1,
+        }
+    }
+}
+
+// For rpc rets `protocol_id` is the id of the request being answered.
+#[derive(Debug, Clone)]
+pub struct CaptureRecord {
+    pub direction: CaptureDirection,
+    pub timestamp_ms: u64,
+    pub header: ProtocolHeader,
+    pub protocol_id: u16,
+    pub payload: Vec<u8>,
+}
+
+// File layout: magic, version byte, LE u16 handshake channel, then records of
+// direction byte, LE u64 timestamp, encoded ProtocolHeader, LE u16 protocol id,
+// LE u32 payload length and the payload itself.
+pub struct CaptureWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    pub fn new(mut inner: W, channel_id: u16) -> io::Result<Self> {
+        inner.write_all(CAPTURE_MAGIC)?;
+        inner.write_all(&[CAPTURE_VERSION])?;
+        inner.write_all(&channel_id.to_le_bytes())?;
+
+        Ok(Self { inner })
+    }
+
+    pub fn write_record(&mut self, record: &CaptureRecord) -> io::Result<()> {
+        let header: Vec<u8> = record.header.clone().into();
+
+        self.inner.write_all(&[record.direction.into()])?;
+        self.inner.write_all(&record.timestamp_ms.to_le_bytes())?;
+        self.inner.write_all(&header)?;
+        self.inner.write_all(&record.protocol_id.to_le_bytes())?;
+        self.inner
+            .write_all(&(record.payload.len() as u32).to_le_bytes())?;
+        self.inner.write_all(&record.payload)?;
+        self.inner.flush()
+    }
+}
+
+pub struct CaptureReader<R: Read> {
+    inner: R,
+    channel_id: u16,
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut magic = [0; CAPTURE_MAGIC.len()];
+        inner.read_exact(&mut magic)?;
+        if &magic != CAPTURE_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a packet capture file",
+            ));
+        }
+
+        let mut version = [0; 1];
+        inner.read_exact(&mut version)?;
+        if version[0] != CAPTURE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported capture version {}", version[0]),
+            ));
+        }
+
+        let mut channel_id = [0; 2];
+        inner.read_exact(&mut channel_id)?;
+
+        Ok(Self {
+            inner,
+            channel_id: u16::from_le_bytes(channel_id),
+        })
+    }
+
+    pub const fn channel_id(&self) -> u16 {
+        self.channel_id
+    }
+
+    pub fn read_record(&mut self) -> io::Result<Option<CaptureRecord>> {
+        let mut direction = [0; 1];
+        match self.inner.read_exact(&mut direction) {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+
+        let mut prefix = [0; 8 + PROTOCOL_HEADER_SIZE + 2 + 4];
+        self.inner.read_exact(&mut prefix)?;
+
+        let timestamp_ms = u64::from_le_bytes(prefix[0..8].try_into().unwrap());
+        let header = ProtocolHeader::try_from(&prefix[8..8 + PROTOCOL_HEADER_SIZE])
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let rest = &prefix[8 + PROTOCOL_HEADER_SIZE..];
+        let protocol_id = u16::from_le_bytes(rest[0..2].try_into().unwrap());
+        let payload_length = u32::from_le_bytes(rest[2..6].try_into().unwrap()) as usize;
+
+        Ok(Some(CaptureRecord {
+            direction: CaptureDirection::try_from(direction[0])?,
+            timestamp_ms,
+            header,
+            protocol_id,
+            payload: crate::read_bytes(&mut self.inner, payload_length)?,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = io::Result<CaptureRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+#[test]
+fn test_capture_round_trip() {
+    let record = CaptureRecord {
+        direction: CaptureDirection::Outbound,
+        timestamp_ms: 1_700_000_000_000,
+        header: ProtocolHeader {
+            to_channel: 2,
+            from_channel: 1,
+            is_rpc_ret: true,
+            rpc_arg_uid: 42,
+        },
+        protocol_id: 100,
+        payload: vec![1, 2, 3],
+    };
+
+    let mut buf = Vec::new();
+    CaptureWriter::new(&mut buf, 7)
+        .unwrap()
+        .write_record(&record)
+        .unwrap();
+
+    let mut reader = CaptureReader::new(&buf[..]).unwrap();
+    assert_eq!(reader.channel_id(), 7);
+
+    let decoded = reader.read_record().unwrap().unwrap();
+    assert_eq!(decoded.direction, record.direction);
+    assert_eq!(decoded.timestamp_ms, record.timestamp_ms);
+    assert_eq!(decoded.header.rpc_arg_uid, 42);
+    assert!(decoded.header.is_rpc_ret);
+    assert_eq!(decoded.protocol_id, 100);
+    assert_eq!(decoded.payload, record.payload);
+    assert!(reader.read_record().unwrap().is_none());
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::io;
+
+use tokio_util::bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::protocol::PROTOCOL_HEADER_SIZE;
+use crate::{ProtocolError, ProtocolHeader, RequestBody, ResponseBody};
+
+// LE u16 channel id, LE u32 body size, LE u16 header size.
+const FRAME_PREFIX_SIZE: usize = 8;
+
+/// One gateway packet. Requests and ptcs carry a [`RequestBody`], rpc rets a [`ResponseBody`].
+#[derive(Debug)]
+pub struct Frame {
+    /// Channel from the packet prefix, normally the same as `header.to_channel`.
+    pub to_channel: u16,
+    pub header: ProtocolHeader,
+    pub body: FrameBody,
+}
+
+#[derive(Debug)]
+pub enum FrameBody {
+    Request(RequestBody),
+    Response(ResponseBody),
+}
+
+impl Frame {
+    #[must_use]
+    pub fn request(header: ProtocolHeader, body: RequestBody) -> Self {
+        Self {
+            to_channel: header.to_channel,
+            header,
+            body: FrameBody::Request(body),
+        }
+    }
+
+    #[must_use]
+    pub fn response(header: ProtocolHeader, body: ResponseBody) -> Self {
+        Self {
+            to_channel: header.to_channel,
+            header,
+            body: FrameBody::Response(body),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FrameError {
+    Io(io::Error),
+    Protocol(ProtocolError),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Protocol(err) => write!(f, "malformed packet: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+impl From<io::Error> for FrameError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ProtocolError> for FrameError {
+    fn from(value: ProtocolError) -> Self {
+        Self::Protocol(value)
+    }
+}
+
+/// Frames the gateway wire format for `FramedRead`/`FramedWrite`.
+/// Size limits are checked against the packet prefix before the rest of the packet is buffered.
+#[derive(Debug, Clone, Copy)]
+pub struct GatewayCodec {
+    max_header_size: usize,
+    max_body_size: usize,
+}
+
+impl GatewayCodec {
+    #[must_use]
+    pub const fn new(max_header_size: usize, max_body_size: usize) -> Self {
+        Self {
+            max_header_size,
+            max_body_size,
+        }
+    }
+}
+
+impl Default for GatewayCodec {
+    fn default() -> Self {
+        Self::new(usize::MAX, usize::MAX)
+    }
+}
+
+impl Decoder for GatewayCodec {
+    type Item = Frame;
+    type Error = FrameError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Frame>, FrameError> {
+        if src.len() < FRAME_PREFIX_SIZE {
+            return Ok(None);
+        }
+
+        let to_channel = u16::from_le_bytes(src[0..2].try_into().unwrap());
+        let body_size = u32::from_le_bytes(src[2..6].try_into().unwrap()) as usize;
+        let header_size = u16::from_le_bytes(src[6..8].try_into().unwrap()) as usize;
+
+        if header_size > self.max_header_size {
+            return Err(ProtocolError::HeaderTooLarge {
+                size: header_size,
+                limit: self.max_header_size,
+            }
+            .into());
+        }
+
+        if body_size > self.max_body_size {
+            return Err(ProtocolError::BodyTooLarge {
+                size: body_size,
+                limit: self.max_body_size,
+            }
+            .into());
+        }
+
+        let frame_size = FRAME_PREFIX_SIZE + header_size + body_size;
+        if src.len() < frame_size {
+            src.reserve(frame_size - src.len());
+            return Ok(None);
+        }
+
+        src.advance(FRAME_PREFIX_SIZE);
+        let header = src.split_to(header_size);
+        let body = src.split_to(body_size);
+
+        let header = ProtocolHeader::try_from(&header[..])?;
+        let body = if header.is_rpc_ret {
+            FrameBody::Response(ResponseBody::try_from(&body[..])?)
+        } else {
+            FrameBody::Request(RequestBody::try_from(&body[..])?)
+        };
+
+        Ok(Some(Frame {
+            to_channel,
+            header,
+            body,
+        }))
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Frame>, FrameError> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed in the middle of a packet",
+            )
+            .into()),
+        }
+    }
+}
+
+impl Encoder<Frame> for GatewayCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, frame: Frame, dst: &mut BytesMut) -> io::Result<()> {
+        // The body kind decides the direction, so the header can't disagree with it.
+        let header = ProtocolHeader {
+            is_rpc_ret: matches!(frame.body, FrameBody::Response(_)),
+            ..frame.header
+        };
+        let body: Vec<u8> = match frame.body {
+            FrameBody::Request(body) => Vec::try_from(body)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?,
+            FrameBody::Response(body) => body.into(),
+        };
+
+        let body_size = u32::try_from(body.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "packet body too large"))?;
+
+        dst.reserve(FRAME_PREFIX_SIZE + PROTOCOL_HEADER_SIZE + body.len());
+        dst.put_u16_le(frame.to_channel);
+        dst.put_u32_le(body_size);
+        dst.put_u16_le(PROTOCOL_HEADER_SIZE as u16);
+        dst.extend_from_slice(&Vec::from(header));
+        dst.extend_from_slice(&body);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_codec_round_trip() {
+    let mut codec = GatewayCodec::default();
+    let mut buf = BytesMut::new();
+
+    let header = ProtocolHeader {
+        to_channel: 1,
+        from_channel: 2,
+        is_rpc_ret: false,
+        rpc_arg_uid: 3,
+    };
+    let request = RequestBody {
+        protocol_id: 100,
+        payload: vec![1, 2, 3],
+        middlewares: Vec::new(),
+    };
+    let response = ResponseBody {
+        middleware_id: 0,
+        middleware_error_code: 0,
+        payload: vec![4, 5],
+    };
+    codec
+        .encode(Frame::request(header.clone(), request), &mut buf)
+        .unwrap();
+    codec
+        .encode(Frame::response(header, response), &mut buf)
+        .unwrap();
+
+    let frame = codec.decode(&mut buf).unwrap().unwrap();
+    assert!(!frame.header.is_rpc_ret);
+    assert!(matches!(frame.body, FrameBody::Request(body) if body.payload == [1, 2, 3]));
+
+    let frame = codec.decode(&mut buf).unwrap().unwrap();
+    assert_eq!(frame.header.rpc_arg_uid, 3);
+    assert!(matches!(frame.body, FrameBody::Response(body) if body.payload == [4, 5]));
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_codec_partial_frame() {
+    let mut codec = GatewayCodec::default();
+    let mut buf = BytesMut::new();
+    codec
+        .encode(
+            Frame::request(
+                ProtocolHeader::default(),
+                RequestBody {
+                    protocol_id: 100,
+                    payload: vec![0; 16],
+                    middlewares: Vec::new(),
+                },
+            ),
+            &mut buf,
+        )
+        .unwrap();
+
+    let mut partial = buf.split_to(buf.len() - 1);
+    assert!(codec.decode(&mut partial).unwrap().is_none());
+    assert!(matches!(
+        codec.decode_eof(&mut partial),
+        Err(FrameError::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof
+    ));
+
+    partial.unsplit(buf);
+    assert!(codec.decode(&mut partial).unwrap().is_some());
+}
+
+#[test]
+fn test_codec_body_limit() {
+    let mut buf = BytesMut::new();
+    buf.put_u16_le(0);
+    buf.put_u32_le(1024);
+    buf.put_u16_le(PROTOCOL_HEADER_SIZE as u16);
+
+    assert!(matches!(
+        GatewayCodec::new(64, 512).decode(&mut buf),
+        Err(FrameError::Protocol(ProtocolError::BodyTooLarge {
+            size: 1024,
+            limit: 512
+        }))
+    ));
+}
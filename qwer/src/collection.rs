@@ -3,7 +3,9 @@ use std::{
     io::Result,
 };
 
-use crate::OctData;
+use itertools::Itertools;
+
+use crate::{bounded_capacity, DecodeError, OctData, PropertyApply};
 
 pub type DoubleKeyHashMap<K1, K2, V> = HashMap<K1, HashMap<K2, V>>;
 
@@ -53,6 +55,7 @@ macro_rules! pdkhashmap {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropertyHashMap<K, V>
 where
     K: OctData + Eq + std::hash::Hash,
@@ -65,6 +68,16 @@ where
     },
 }
 
+impl<K, V> Default for PropertyHashMap<K, V>
+where
+    K: OctData + Eq + std::hash::Hash,
+    V: OctData,
+{
+    fn default() -> Self {
+        Self::Base(HashMap::new())
+    }
+}
+
 impl<K, V> PropertyHashMap<K, V>
 where
     K: OctData + Eq + std::hash::Hash,
@@ -94,17 +107,32 @@ where
     pub fn get(&self, key: &K) -> Option<&V> {
         match self {
             Self::Base(base) => base.get(key),
-            Self::Modify { .. } => None,
+            Self::Modify { to_add, .. } => to_add
+                .iter()
+                .rev()
+                .find(|(added, _)| added == key)
+                .map(|(_, value)| value),
         }
     }
 
     pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
         match self {
             Self::Base(base) => base.get_mut(key),
-            Self::Modify { .. } => None,
+            Self::Modify { to_add, .. } => to_add
+                .iter_mut()
+                .rev()
+                .find(|(added, _)| added == key)
+                .map(|(_, value)| value),
         }
     }
 
+    /// Combines two deltas (or a base and a delta) into one, see [`PropertyApply`].
+    #[must_use]
+    pub fn merge(mut self, later: Self) -> Self {
+        self.apply(later);
+        self
+    }
+
     #[must_use]
     pub fn len(&self) -> usize {
         match self {
@@ -137,6 +165,45 @@ where
     }
 }
 
+impl<K, V> PropertyApply for PropertyHashMap<K, V>
+where
+    K: OctData + Eq + std::hash::Hash,
+    V: OctData,
+{
+    // A `Modify` is read as additions followed by removals, so a later addition has to
+    // cancel an earlier removal of the same key when two deltas are merged.
+    fn apply(&mut self, delta: Self) {
+        match (self, delta) {
+            (this, delta @ Self::Base(_)) => *this = delta,
+            (Self::Base(base), Self::Modify { to_add, to_remove }) => {
+                base.extend(to_add);
+                for key in to_remove {
+                    base.remove(&key);
+                }
+            }
+            (
+                Self::Modify { to_add, to_remove },
+                Self::Modify {
+                    to_add: later_add,
+                    to_remove: later_remove,
+                },
+            ) => {
+                for (key, value) in later_add {
+                    to_remove.retain(|removed| removed != &key);
+                    to_add.retain(|(added, _)| added != &key);
+                    to_add.push((key, value));
+                }
+                for key in later_remove {
+                    to_add.retain(|(added, _)| added != &key);
+                    if !to_remove.contains(&key) {
+                        to_remove.push(key);
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl<K, V> IntoIterator for PropertyHashMap<K, V>
 where
     K: OctData + Eq + std::hash::Hash,
@@ -248,6 +315,50 @@ where
     pub fn iter_mut(&mut self) -> std::collections::hash_set::Iter<T> {
         self.into_iter()
     }
+
+    /// Combines two deltas (or a base and a delta) into one, see [`PropertyApply`].
+    #[must_use]
+    pub fn merge(mut self, later: Self) -> Self {
+        self.apply(later);
+        self
+    }
+}
+
+impl<T> PropertyApply for PropertyHashSet<T>
+where
+    T: OctData + Eq + std::hash::Hash,
+{
+    fn apply(&mut self, delta: Self) {
+        match (self, delta) {
+            (this, delta @ Self::Base(_)) => *this = delta,
+            (Self::Base(base), Self::Modify { to_add, to_remove }) => {
+                base.extend(to_add);
+                for value in to_remove {
+                    base.remove(&value);
+                }
+            }
+            (
+                Self::Modify { to_add, to_remove },
+                Self::Modify {
+                    to_add: later_add,
+                    to_remove: later_remove,
+                },
+            ) => {
+                for value in later_add {
+                    to_remove.retain(|removed| removed != &value);
+                    if !to_add.contains(&value) {
+                        to_add.push(value);
+                    }
+                }
+                for value in later_remove {
+                    to_add.retain(|added| added != &value);
+                    if !to_remove.contains(&value) {
+                        to_remove.push(value);
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl<T> IntoIterator for PropertyHashSet<T>
@@ -302,6 +413,7 @@ fn test_phashmap_macro() {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropertyHashSet<T>
 where
     T: OctData + Eq + std::hash::Hash,
@@ -310,7 +422,17 @@ where
     Modify { to_add: Vec<T>, to_remove: Vec<T> },
 }
 
+impl<T> Default for PropertyHashSet<T>
+where
+    T: OctData + Eq + std::hash::Hash,
+{
+    fn default() -> Self {
+        Self::Base(HashSet::new())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropertyDoubleKeyHashMap<K1, K2, V>
 where
     K1: OctData + Eq + std::hash::Hash,
@@ -324,19 +446,34 @@ where
     },
 }
 
+impl<K1, K2, V> Default for PropertyDoubleKeyHashMap<K1, K2, V>
+where
+    K1: OctData + Eq + std::hash::Hash,
+    K2: OctData + Eq + std::hash::Hash,
+    V: OctData,
+{
+    fn default() -> Self {
+        Self::Base(HashMap::new())
+    }
+}
+
+// Iterating a `Modify` yields its pending additions.
 pub struct PropertyDoubleKeyHashMapIterMut<'a, K1, K2, V> {
+    to_add_iter: std::slice::IterMut<'a, (K1, K2, V)>,
     outer_iter: std::collections::hash_map::IterMut<'a, K1, HashMap<K2, V>>,
     inner_iter: Option<std::collections::hash_map::IterMut<'a, K2, V>>,
     current_outer_key: Option<&'a K1>,
 }
 
 pub struct PropertyDoubleKeyHashMapIter<'a, K1, K2, V> {
+    to_add_iter: std::slice::Iter<'a, (K1, K2, V)>,
     outer_iter: std::collections::hash_map::Iter<'a, K1, HashMap<K2, V>>,
     inner_iter: Option<std::collections::hash_map::Iter<'a, K2, V>>,
     current_outer_key: Option<&'a K1>,
 }
 
 pub struct PropertyDoubleKeyHashMapIntoIter<K1, K2, V> {
+    to_add_iter: std::vec::IntoIter<(K1, K2, V)>,
     outer_iter: std::collections::hash_map::IntoIter<K1, HashMap<K2, V>>,
     inner_iter: Option<std::collections::hash_map::IntoIter<K2, V>>,
     current_outer_key: Option<K1>,
@@ -351,6 +488,10 @@ where
     type Item = (&'a K1, &'a K2, &'a mut V);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some((key1, key2, value)) = self.to_add_iter.next() {
+            return Some((key1, key2, value));
+        }
+
         loop {
             if self.inner_iter.is_none() {
                 if let Some((key1, sub_map)) = self.outer_iter.next() {
@@ -380,6 +521,10 @@ where
     type Item = (&'a K1, &'a K2, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some((key1, key2, value)) = self.to_add_iter.next() {
+            return Some((key1, key2, value));
+        }
+
         loop {
             if self.inner_iter.is_none() {
                 if let Some((key1, sub_map)) = self.outer_iter.next() {
@@ -409,6 +554,10 @@ where
     type Item = (K1, K2, V);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(entry) = self.to_add_iter.next() {
+            return Some(entry);
+        }
+
         loop {
             if self.inner_iter.is_none() {
                 if let Some((key1, sub_map)) = self.outer_iter.next() {
@@ -441,13 +590,17 @@ where
     fn into_iter(self) -> Self::IntoIter {
         match self {
             Self::Base(base) => PropertyDoubleKeyHashMapIntoIter {
+                to_add_iter: Default::default(),
                 outer_iter: base.into_iter(),
                 inner_iter: None,
                 current_outer_key: None,
             },
-            Self::Modify { .. } => {
-                unreachable!("PropertyDoubleKeyHashMap::Modify::into_iter() is not implemented")
-            }
+            Self::Modify { to_add, .. } => PropertyDoubleKeyHashMapIntoIter {
+                to_add_iter: to_add.into_iter(),
+                outer_iter: Default::default(),
+                inner_iter: None,
+                current_outer_key: None,
+            },
         }
     }
 }
@@ -464,13 +617,17 @@ where
     fn into_iter(self) -> Self::IntoIter {
         match self {
             PropertyDoubleKeyHashMap::Base(base) => PropertyDoubleKeyHashMapIter {
+                to_add_iter: Default::default(),
                 outer_iter: base.iter(),
                 inner_iter: None,
                 current_outer_key: None,
             },
-            PropertyDoubleKeyHashMap::Modify { .. } => {
-                unreachable!("PropertyDoubleKeyHashMap::Modify::into_iter() is not implemented")
-            }
+            PropertyDoubleKeyHashMap::Modify { to_add, .. } => PropertyDoubleKeyHashMapIter {
+                to_add_iter: to_add.iter(),
+                outer_iter: Default::default(),
+                inner_iter: None,
+                current_outer_key: None,
+            },
         }
     }
 }
@@ -487,13 +644,17 @@ where
     fn into_iter(self) -> Self::IntoIter {
         match self {
             PropertyDoubleKeyHashMap::Base(base) => PropertyDoubleKeyHashMapIterMut {
+                to_add_iter: Default::default(),
                 outer_iter: base.iter_mut(),
                 inner_iter: None,
                 current_outer_key: None,
             },
-            PropertyDoubleKeyHashMap::Modify { .. } => {
-                unreachable!("PropertyDoubleKeyHashMap::Modify::into_iter() is not implemented")
-            }
+            PropertyDoubleKeyHashMap::Modify { to_add, .. } => PropertyDoubleKeyHashMapIterMut {
+                to_add_iter: to_add.iter_mut(),
+                outer_iter: Default::default(),
+                inner_iter: None,
+                current_outer_key: None,
+            },
         }
     }
 }
@@ -520,9 +681,11 @@ where
     pub fn get(&self, key: &K1, sub_key: &K2) -> Option<&V> {
         match self {
             Self::Base(base) => base.get(key).and_then(|sub_map| sub_map.get(sub_key)),
-            Self::Modify { .. } => {
-                unreachable!("PropertyDoubleKeyHashMap::Modify::get() is not implemented")
-            }
+            Self::Modify { to_add, .. } => to_add
+                .iter()
+                .rev()
+                .find(|(added1, added2, _)| added1 == key && added2 == sub_key)
+                .map(|(_, _, value)| value),
         }
     }
 
@@ -531,9 +694,11 @@ where
             Self::Base(base) => base
                 .get_mut(key)
                 .and_then(|sub_map| sub_map.get_mut(sub_key)),
-            Self::Modify { .. } => {
-                unreachable!("PropertyDoubleKeyHashMap::Modify::get_mut() is not implemented")
-            }
+            Self::Modify { to_add, .. } => to_add
+                .iter_mut()
+                .rev()
+                .find(|(added1, added2, _)| added1 == key && added2 == sub_key)
+                .map(|(_, _, value)| value),
         }
     }
 
@@ -572,25 +737,92 @@ where
     pub fn iter(&self) -> PropertyDoubleKeyHashMapIter<K1, K2, V> {
         match self {
             Self::Base(base) => PropertyDoubleKeyHashMapIter {
+                to_add_iter: Default::default(),
                 outer_iter: base.iter(),
                 inner_iter: None,
                 current_outer_key: None,
             },
-            Self::Modify { .. } => {
-                unreachable!("PropertyDoubleKeyHashMap::Modify::iter() is not implemented")
-            }
+            Self::Modify { to_add, .. } => PropertyDoubleKeyHashMapIter {
+                to_add_iter: to_add.iter(),
+                outer_iter: Default::default(),
+                inner_iter: None,
+                current_outer_key: None,
+            },
         }
     }
 
     pub fn iter_mut(&mut self) -> PropertyDoubleKeyHashMapIterMut<K1, K2, V> {
         match self {
             Self::Base(base) => PropertyDoubleKeyHashMapIterMut {
+                to_add_iter: Default::default(),
                 outer_iter: base.iter_mut(),
                 inner_iter: None,
                 current_outer_key: None,
             },
-            Self::Modify { .. } => {
-                unreachable!("PropertyDoubleKeyHashMap::Modify::iter_mut() is not implemented")
+            Self::Modify { to_add, .. } => PropertyDoubleKeyHashMapIterMut {
+                to_add_iter: to_add.iter_mut(),
+                outer_iter: Default::default(),
+                inner_iter: None,
+                current_outer_key: None,
+            },
+        }
+    }
+}
+
+impl<K1, K2, V> PropertyDoubleKeyHashMap<K1, K2, V>
+where
+    K1: OctData + Eq + std::hash::Hash,
+    K2: OctData + Eq + std::hash::Hash,
+    V: OctData,
+{
+    /// Combines two deltas (or a base and a delta) into one, see [`PropertyApply`].
+    #[must_use]
+    pub fn merge(mut self, later: Self) -> Self {
+        self.apply(later);
+        self
+    }
+}
+
+impl<K1, K2, V> PropertyApply for PropertyDoubleKeyHashMap<K1, K2, V>
+where
+    K1: OctData + Eq + std::hash::Hash,
+    K2: OctData + Eq + std::hash::Hash,
+    V: OctData,
+{
+    fn apply(&mut self, delta: Self) {
+        match (self, delta) {
+            (this, delta @ Self::Base(_)) => *this = delta,
+            (Self::Base(base), Self::Modify { to_add, to_remove }) => {
+                for (key1, key2, value) in to_add {
+                    base.entry(key1)
+                        .or_insert_with(HashMap::new)
+                        .insert(key2, value);
+                }
+                for (key1, key2) in to_remove {
+                    if let Some(sub_map) = base.get_mut(&key1) {
+                        sub_map.remove(&key2);
+                    }
+                }
+            }
+            (
+                Self::Modify { to_add, to_remove },
+                Self::Modify {
+                    to_add: later_add,
+                    to_remove: later_remove,
+                },
+            ) => {
+                for (key1, key2, value) in later_add {
+                    to_remove.retain(|(removed1, removed2)| removed1 != &key1 || removed2 != &key2);
+                    to_add.retain(|(added1, added2, _)| added1 != &key1 || added2 != &key2);
+                    to_add.push((key1, key2, value));
+                }
+                for removed in later_remove {
+                    to_add
+                        .retain(|(added1, added2, _)| added1 != &removed.0 || added2 != &removed.1);
+                    if !to_remove.contains(&removed) {
+                        to_remove.push(removed);
+                    }
+                }
             }
         }
     }
@@ -617,8 +849,14 @@ where
 {
     fn marshal_to<W: std::io::Write>(&self, w: &mut W, bt_property_tag: u16) -> Result<()> {
         match self {
+            // Written pair by pair like `unmarshal_from` reads it, a map of maps as `V` would
+            // otherwise pick up the flattened `DoubleKeyHashMap` layout.
             Self::Base(map) => {
-                map.marshal_to(w, bt_property_tag)?;
+                (map.len() as i32).marshal_to(w, bt_property_tag)?;
+                for (key, value) in map.iter().sorted_by_key(|kv| kv.0) {
+                    key.marshal_to(w, bt_property_tag)?;
+                    value.marshal_to(w, bt_property_tag)?;
+                }
             }
             Self::Modify { to_add, to_remove } => {
                 let len = -(to_add.len() as i32 + to_remove.len() as i32);
@@ -641,24 +879,34 @@ where
         let len = i32::unmarshal_from(r, bt_property_tag)?;
 
         if len >= 0 {
-            let mut map = HashMap::with_capacity(len as usize);
-            for _ in 0..len {
-                map.insert(
-                    K::unmarshal_from(r, bt_property_tag)?,
-                    V::unmarshal_from(r, bt_property_tag)?,
-                );
+            let mut map = HashMap::with_capacity(bounded_capacity(len as usize));
+            for index in 0..len as usize {
+                let entry = (|| {
+                    Ok((
+                        K::unmarshal_from(r, bt_property_tag)?,
+                        V::unmarshal_from(r, bt_property_tag)?,
+                    ))
+                })();
+                let (key, value) = DecodeError::in_element(entry, index)?;
+                map.insert(key, value);
             }
             Ok(Self::Base(map))
         } else {
             let mut to_add = Vec::new();
             let mut to_remove = Vec::new();
 
-            for _ in 0..-len {
-                let key = K::unmarshal_from(r, bt_property_tag)?;
-                if !bool::unmarshal_from(r, bt_property_tag)? {
-                    to_add.push((key, V::unmarshal_from(r, bt_property_tag)?));
-                } else {
-                    to_remove.push(key);
+            for index in 0..len.unsigned_abs() as usize {
+                let entry = (|| {
+                    let key = K::unmarshal_from(r, bt_property_tag)?;
+                    if bool::unmarshal_from(r, bt_property_tag)? {
+                        Ok((key, None))
+                    } else {
+                        Ok((key, Some(V::unmarshal_from(r, bt_property_tag)?)))
+                    }
+                })();
+                match DecodeError::in_element(entry, index)? {
+                    (key, Some(value)) => to_add.push((key, value)),
+                    (key, None) => to_remove.push(key),
                 }
             }
 
@@ -669,7 +917,7 @@ where
 
 impl<K> OctData for PropertyHashSet<K>
 where
-    K: OctData + Eq + std::hash::Hash,
+    K: OctData + Eq + Ord + std::hash::Hash,
 {
     fn marshal_to<W: std::io::Write>(&self, w: &mut W, bt_property_tag: u16) -> Result<()> {
         match self {
@@ -696,21 +944,26 @@ where
         let len = i32::unmarshal_from(r, bt_property_tag)?;
 
         if len >= 0 {
-            let mut set = HashSet::with_capacity(len as usize);
-            for _ in 0..len {
-                set.insert(K::unmarshal_from(r, bt_property_tag)?);
+            let mut set = HashSet::with_capacity(bounded_capacity(len as usize));
+            for index in 0..len as usize {
+                let item = K::unmarshal_from(r, bt_property_tag);
+                set.insert(DecodeError::in_element(item, index)?);
             }
             Ok(Self::Base(set))
         } else {
             let mut to_add = Vec::new();
             let mut to_remove = Vec::new();
 
-            for _ in 0..-len {
-                let value = K::unmarshal_from(r, bt_property_tag)?;
-                if !bool::unmarshal_from(r, bt_property_tag)? {
-                    to_add.push(value);
-                } else {
-                    to_remove.push(value);
+            for index in 0..len.unsigned_abs() as usize {
+                let entry = (|| {
+                    Ok((
+                        K::unmarshal_from(r, bt_property_tag)?,
+                        bool::unmarshal_from(r, bt_property_tag)?,
+                    ))
+                })();
+                match DecodeError::in_element(entry, index)? {
+                    (value, false) => to_add.push(value),
+                    (value, true) => to_remove.push(value),
                 }
             }
 
@@ -753,10 +1006,15 @@ where
         let len = i32::unmarshal_from(r, bt_property_tag)?;
         if len >= 0 {
             let mut map = HashMap::new();
-            for _ in 0..len {
-                let key1 = K1::unmarshal_from(r, bt_property_tag)?;
-                let key2 = K2::unmarshal_from(r, bt_property_tag)?;
-                let value = V::unmarshal_from(r, bt_property_tag)?;
+            for index in 0..len as usize {
+                let entry = (|| {
+                    Ok((
+                        K1::unmarshal_from(r, bt_property_tag)?,
+                        K2::unmarshal_from(r, bt_property_tag)?,
+                        V::unmarshal_from(r, bt_property_tag)?,
+                    ))
+                })();
+                let (key1, key2, value) = DecodeError::in_element(entry, index)?;
                 map.entry(key1)
                     .or_insert_with(HashMap::new)
                     .insert(key2, value);
@@ -766,13 +1024,19 @@ where
             let mut to_add = Vec::new();
             let mut to_remove = Vec::new();
 
-            for _ in 0..-len {
-                let key1 = K1::unmarshal_from(r, bt_property_tag)?;
-                let key2 = K2::unmarshal_from(r, bt_property_tag)?;
-                if !bool::unmarshal_from(r, bt_property_tag)? {
-                    to_add.push((key1, key2, V::unmarshal_from(r, bt_property_tag)?));
-                } else {
-                    to_remove.push((key1, key2));
+            for index in 0..len.unsigned_abs() as usize {
+                let entry = (|| {
+                    let key1 = K1::unmarshal_from(r, bt_property_tag)?;
+                    let key2 = K2::unmarshal_from(r, bt_property_tag)?;
+                    if bool::unmarshal_from(r, bt_property_tag)? {
+                        Ok((key1, key2, None))
+                    } else {
+                        Ok((key1, key2, Some(V::unmarshal_from(r, bt_property_tag)?)))
+                    }
+                })();
+                match DecodeError::in_element(entry, index)? {
+                    (key1, key2, Some(value)) => to_add.push((key1, key2, value)),
+                    (key1, key2, None) => to_remove.push((key1, key2)),
                 }
             }
 
@@ -780,3 +1044,46 @@ where
         }
     }
 }
+
+#[test]
+fn test_hashmap_apply_and_merge() {
+    let mut map = phashmap![(1, 10), (2, 20)];
+    let first = PropertyHashMap::Modify {
+        to_add: vec![(3, 30)],
+        to_remove: vec![1],
+    };
+    let second = PropertyHashMap::Modify {
+        to_add: vec![(1, 11)],
+        to_remove: vec![3],
+    };
+
+    let mut applied = map.clone();
+    applied.apply(first.clone());
+    applied.apply(second.clone());
+
+    map.apply(first.merge(second));
+    assert_eq!(map, applied);
+    assert_eq!(map, phashmap![(1, 11), (2, 20)]);
+}
+
+#[test]
+fn test_dkhashmap_modify_access() {
+    let mut map = PropertyDoubleKeyHashMap::Modify {
+        to_add: vec![(1, 2, 3)],
+        to_remove: vec![(4, 5)],
+    };
+    assert_eq!(map.get(&1, &2), Some(&3));
+    assert_eq!(map.iter().count(), 1);
+
+    map.apply(PropertyDoubleKeyHashMap::Modify {
+        to_add: vec![(4, 5, 6)],
+        to_remove: vec![(1, 2)],
+    });
+    assert_eq!(
+        map,
+        PropertyDoubleKeyHashMap::Modify {
+            to_add: vec![(4, 5, 6)],
+            to_remove: vec![(1, 2)],
+        }
+    );
+}
@@ -0,0 +1,144 @@
+use std::hash::Hash;
+
+use crate::{OctData, PropertyDoubleKeyHashMap, PropertyHashMap, PropertyHashSet};
+
+/// Computes the delta that turns `old` into `new`, the inverse of [`crate::PropertyApply`].
+///
+/// Plain values are compared by their marshaled bytes and sent whole when they differ.
+/// Property collections produce `Modify` deltas, and `#[property_object]` structs derived with
+/// `OctData` only carry the fields that changed.
+pub trait PropertyDiff: Sized {
+    fn diff(old: &Self, new: &Self) -> Option<Self>;
+}
+
+impl<T: OctData + Clone> PropertyDiff for T {
+    // Collections without a stable iteration order may report a change for equal values,
+    // which only costs a redundant update.
+    default fn diff(old: &Self, new: &Self) -> Option<Self> {
+        (marshaled(old) != marshaled(new)).then(|| new.clone())
+    }
+}
+
+// Property object fields never go from `Some` back to `None`, so only additions and changes
+// are reported.
+impl<T: OctData + Clone> PropertyDiff for Option<T> {
+    fn diff(old: &Self, new: &Self) -> Option<Self> {
+        match (old, new) {
+            (Some(old), Some(new)) => T::diff(old, new).map(Some),
+            (None, Some(new)) => Some(Some(new.clone())),
+            (_, None) => None,
+        }
+    }
+}
+
+impl<K, V> PropertyDiff for PropertyHashMap<K, V>
+where
+    K: OctData + Eq + Ord + Hash + Clone,
+    V: OctData + Clone,
+{
+    fn diff(old: &Self, new: &Self) -> Option<Self> {
+        let (Self::Base(old_map), Self::Base(new_map)) = (old, new) else {
+            return (marshaled(old) != marshaled(new)).then(|| new.clone());
+        };
+
+        let mut to_add = new_map
+            .iter()
+            .filter(|(key, value)| {
+                old_map
+                    .get(key)
+                    .is_none_or(|old_value| V::diff(old_value, value).is_some())
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        let mut to_remove = old_map
+            .keys()
+            .filter(|key| !new_map.contains_key(key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        to_add.sort_by(|(a, _), (b, _)| a.cmp(b));
+        to_remove.sort();
+
+        (!to_add.is_empty() || !to_remove.is_empty()).then_some(Self::Modify { to_add, to_remove })
+    }
+}
+
+impl<T> PropertyDiff for PropertyHashSet<T>
+where
+    T: OctData + Eq + Ord + Hash + Clone,
+{
+    fn diff(old: &Self, new: &Self) -> Option<Self> {
+        let (Self::Base(old_set), Self::Base(new_set)) = (old, new) else {
+            return (marshaled(old) != marshaled(new)).then(|| new.clone());
+        };
+
+        let mut to_add = new_set.difference(old_set).cloned().collect::<Vec<_>>();
+        let mut to_remove = old_set.difference(new_set).cloned().collect::<Vec<_>>();
+
+        to_add.sort();
+        to_remove.sort();
+
+        (!to_add.is_empty() || !to_remove.is_empty()).then_some(Self::Modify { to_add, to_remove })
+    }
+}
+
+impl<K1, K2, V> PropertyDiff for PropertyDoubleKeyHashMap<K1, K2, V>
+where
+    K1: OctData + Eq + Ord + Hash + Clone,
+    K2: OctData + Eq + Ord + Hash + Clone,
+    V: OctData + Clone,
+{
+    fn diff(old: &Self, new: &Self) -> Option<Self> {
+        if !matches!((old, new), (Self::Base(_), Self::Base(_))) {
+            return (marshaled(old) != marshaled(new)).then(|| new.clone());
+        }
+
+        let mut to_add = new
+            .iter()
+            .filter(|(key1, key2, value)| {
+                old.get(key1, key2)
+                    .is_none_or(|old_value| V::diff(old_value, value).is_some())
+            })
+            .map(|(key1, key2, value)| (key1.clone(), key2.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        let mut to_remove = old
+            .iter()
+            .filter(|(key1, key2, _)| new.get(key1, key2).is_none())
+            .map(|(key1, key2, _)| (key1.clone(), key2.clone()))
+            .collect::<Vec<_>>();
+
+        to_add.sort_by(|(a1, a2, _), (b1, b2, _)| (a1, a2).cmp(&(b1, b2)));
+        to_remove.sort();
+
+        (!to_add.is_empty() || !to_remove.is_empty()).then_some(Self::Modify { to_add, to_remove })
+    }
+}
+
+fn marshaled<T: OctData>(value: &T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    // Writing into a Vec can't fail, errors would only come from invalid data.
+    let _ = value.marshal_to(&mut buf, 0);
+    buf
+}
+
+#[test]
+fn test_hashmap_diff_round_trip() {
+    use crate::{phashmap, PropertyApply};
+
+    let old = phashmap![(1, 10), (2, 20), (3, 30)];
+    let new = phashmap![(1, 10), (2, 21), (4, 40)];
+
+    let delta = PropertyHashMap::diff(&old, &new).unwrap();
+    assert_eq!(
+        delta,
+        PropertyHashMap::Modify {
+            to_add: vec![(2, 21), (4, 40)],
+            to_remove: vec![3],
+        }
+    );
+
+    let mut applied = old.clone();
+    applied.apply(delta);
+    assert_eq!(applied, new);
+    assert!(PropertyHashMap::diff(&new, &new).is_none());
+}
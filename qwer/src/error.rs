@@ -0,0 +1,155 @@
+use std::fmt::{self, Display};
+use std::io;
+
+#[cfg(feature = "fastoct")]
+use crate::OctData;
+
+/// Where and why decoding failed.
+///
+/// `OctData::unmarshal_from` keeps returning `io::Error`; this error travels inside it and
+/// collects the field path while it propagates up through derived types and collections.
+#[derive(Debug)]
+pub struct DecodeError {
+    kind: io::ErrorKind,
+    message: String,
+    root: Option<&'static str>,
+    // Innermost segment first, the path is built from the failing field outwards.
+    segments: Vec<String>,
+    offset: Option<usize>,
+    mismatch: Option<Box<Mismatch>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub what: String,
+    pub expected: String,
+    pub found: String,
+}
+
+impl DecodeError {
+    pub fn mismatch(what: impl Into<String>, expected: impl Display, found: impl Display) -> Self {
+        let what = what.into();
+
+        Self {
+            kind: io::ErrorKind::InvalidData,
+            message: format!("unexpected {what}"),
+            root: None,
+            segments: Vec::new(),
+            offset: None,
+            mismatch: Some(Box::new(Mismatch {
+                what,
+                expected: expected.to_string(),
+                found: found.to_string(),
+            })),
+        }
+    }
+
+    /// Prefixes the path of a failed read with `field` of type `ty`.
+    pub fn in_field<T>(result: io::Result<T>, ty: &'static str, field: &str) -> io::Result<T> {
+        result.map_err(|err| {
+            let mut err = Self::from(err);
+            err.root = Some(ty);
+            err.segments.push(format!(".{field}"));
+            err.into()
+        })
+    }
+
+    /// Prefixes the path of a failed read with the collection element index.
+    pub fn in_element<T>(result: io::Result<T>, index: usize) -> io::Result<T> {
+        result.map_err(|err| {
+            let mut err = Self::from(err);
+            err.segments.push(format!("[{index}]"));
+            err.into()
+        })
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> io::ErrorKind {
+        self.kind
+    }
+
+    /// Dotted path to the failing field, e.g. `PlayerInfo.yorozuya_info.hollow_quests[3]`.
+    #[must_use]
+    pub fn path(&self) -> String {
+        let mut path = self.root.unwrap_or_default().to_string();
+        path.extend(self.segments.iter().rev().map(String::as_str));
+        path
+    }
+
+    /// Position in the payload where reading stopped, set by [`decode`].
+    #[must_use]
+    pub const fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    #[must_use]
+    pub fn expected_found(&self) -> Option<&Mismatch> {
+        self.mismatch.as_deref()
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path();
+        if !path.is_empty() {
+            write!(f, "{path}: ")?;
+        }
+
+        write!(f, "{}", self.message)?;
+        if let Some(Mismatch {
+            expected, found, ..
+        }) = self.mismatch.as_deref()
+        {
+            write!(f, " (expected {expected}, found {found})")?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " at byte {offset}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> Self {
+        let kind = err.kind();
+        match err.into_inner().map(|inner| inner.downcast::<Self>()) {
+            Some(Ok(decode_error)) => *decode_error,
+            Some(Err(inner)) => Self {
+                kind,
+                message: inner.to_string(),
+                root: None,
+                segments: Vec::new(),
+                offset: None,
+                mismatch: None,
+            },
+            None => Self {
+                kind,
+                message: io::Error::from(kind).to_string(),
+                root: None,
+                segments: Vec::new(),
+                offset: None,
+                mismatch: None,
+            },
+        }
+    }
+}
+
+impl From<DecodeError> for io::Error {
+    fn from(err: DecodeError) -> Self {
+        Self::new(err.kind, err)
+    }
+}
+
+/// Decodes a whole payload, recording the byte offset reading stopped at on failure.
+#[cfg(feature = "fastoct")]
+pub fn decode<T: OctData>(payload: &[u8]) -> Result<T, DecodeError> {
+    let mut reader = payload;
+    T::unmarshal_from(&mut reader, 0).map_err(|err| {
+        let mut err = DecodeError::from(err);
+        err.offset = Some(payload.len() - reader.len());
+        err
+    })
+}
@@ -10,7 +10,37 @@ use itertools::Itertools;
 
 pub use qwer_derive::OctData;
 
-use crate::DoubleKeyHashMap;
+use crate::{DecodeError, DoubleKeyHashMap};
+
+// Upper bound for capacity reserved up front from an untrusted length prefix.
+const MAX_PREALLOCATED_ITEMS: usize = 1024;
+
+#[must_use]
+pub fn bounded_capacity(len: usize) -> usize {
+    len.min(MAX_PREALLOCATED_ITEMS)
+}
+
+/// Reads exactly `len` bytes without trusting `len` for the initial allocation.
+pub fn read_bytes<R: Read>(r: &mut R, len: usize) -> Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(bounded_capacity(len));
+    r.take(len as u64).read_to_end(&mut buf)?;
+
+    if buf.len() != len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!("Expected {len} bytes, got {}", buf.len()),
+        ));
+    }
+
+    Ok(buf)
+}
+
+fn invalid_length(len: i32) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Invalid collection length {len}"),
+    )
+}
 
 // LE encoded data
 pub trait OctData: Sized + Send + Sync {
@@ -115,17 +145,21 @@ where
     fn unmarshal_from<R: Read>(r: &mut R, bt_property_tag: u16) -> Result<Self> {
         let len = i32::unmarshal_from(r, bt_property_tag)?;
         if len < 0 {
-            let real_len = -len;
-            let mut vec = Self::with_capacity(real_len as usize);
-            for _ in 0..real_len {
-                bool::unmarshal_from(r, bt_property_tag)?;
-                vec.push(T::unmarshal_from(r, bt_property_tag)?);
+            let real_len = len.unsigned_abs() as usize;
+            let mut vec = Self::with_capacity(bounded_capacity(real_len));
+            for index in 0..real_len {
+                let item = (|| {
+                    bool::unmarshal_from(r, bt_property_tag)?;
+                    T::unmarshal_from(r, bt_property_tag)
+                })();
+                vec.push(DecodeError::in_element(item, index)?);
             }
             Ok(vec)
         } else {
-            let mut vec = Self::with_capacity(len as usize);
-            for _ in 0..len {
-                vec.push(T::unmarshal_from(r, bt_property_tag)?);
+            let mut vec = Self::with_capacity(bounded_capacity(len as usize));
+            for index in 0..len as usize {
+                let item = T::unmarshal_from(r, bt_property_tag);
+                vec.push(DecodeError::in_element(item, index)?);
             }
             Ok(vec)
         }
@@ -151,12 +185,19 @@ where
         if len == -1 {
             return Ok(Self::new());
         }
-        let mut map = Self::with_capacity(len as usize);
-        for _ in 0..len {
-            map.insert(
-                K::unmarshal_from(r, bt_property_tag)?,
-                V::unmarshal_from(r, bt_property_tag)?,
-            );
+        if len < 0 {
+            return Err(invalid_length(len));
+        }
+        let mut map = Self::with_capacity(bounded_capacity(len as usize));
+        for index in 0..len as usize {
+            let entry = (|| {
+                Ok((
+                    K::unmarshal_from(r, bt_property_tag)?,
+                    V::unmarshal_from(r, bt_property_tag)?,
+                ))
+            })();
+            let (key, value) = DecodeError::in_element(entry, index)?;
+            map.insert(key, value);
         }
         Ok(map)
     }
@@ -170,12 +211,12 @@ where
     V: OctData,
 {
     fn marshal_to<W: Write>(&self, w: &mut W, bt_property_tag: u16) -> Result<()> {
-        self.iter()
-            .map(|(_, inner_map)| inner_map.len() as i32)
+        self.values()
+            .map(|inner_map| inner_map.len() as i32)
             .sum::<i32>()
             .marshal_to(w, bt_property_tag)?;
-        for (key1, inner_map) in self {
-            for (key2, value) in inner_map {
+        for (key1, inner_map) in self.iter().sorted_by_key(|kv| kv.0) {
+            for (key2, value) in inner_map.iter().sorted_by_key(|kv| kv.0) {
                 key1.marshal_to(w, bt_property_tag)?;
                 key2.marshal_to(w, bt_property_tag)?;
                 value.marshal_to(w, bt_property_tag)?;
@@ -189,11 +230,19 @@ where
         if len == -1 {
             return Ok(Self::new());
         }
+        if len < 0 {
+            return Err(invalid_length(len));
+        }
         let mut map = Self::new();
-        for _ in 0..len {
-            let key1 = K1::unmarshal_from(r, bt_property_tag)?;
-            let key2 = K2::unmarshal_from(r, bt_property_tag)?;
-            let value = V::unmarshal_from(r, bt_property_tag)?;
+        for index in 0..len as usize {
+            let entry = (|| {
+                Ok((
+                    K1::unmarshal_from(r, bt_property_tag)?,
+                    K2::unmarshal_from(r, bt_property_tag)?,
+                    V::unmarshal_from(r, bt_property_tag)?,
+                ))
+            })();
+            let (key1, key2, value) = DecodeError::in_element(entry, index)?;
             map.entry(key1)
                 .or_insert_with(HashMap::new)
                 .insert(key2, value);
@@ -204,11 +253,11 @@ where
 
 impl<T> OctData for HashSet<T>
 where
-    T: OctData + Eq + Hash,
+    T: OctData + Eq + Hash + Ord,
 {
     fn marshal_to<W: Write>(&self, w: &mut W, bt_property_tag: u16) -> Result<()> {
         (self.len() as i32).marshal_to(w, bt_property_tag)?;
-        for item in self {
+        for item in self.iter().sorted() {
             item.marshal_to(w, bt_property_tag)?;
         }
         Ok(())
@@ -219,9 +268,13 @@ where
         if len == -1 {
             return Ok(Self::new());
         }
-        let mut set = Self::with_capacity(len as usize);
-        for _ in 0..len {
-            set.insert(T::unmarshal_from(r, bt_property_tag)?);
+        if len < 0 {
+            return Err(invalid_length(len));
+        }
+        let mut set = Self::with_capacity(bounded_capacity(len as usize));
+        for index in 0..len as usize {
+            let item = T::unmarshal_from(r, bt_property_tag);
+            set.insert(DecodeError::in_element(item, index)?);
         }
         Ok(set)
     }
@@ -259,9 +312,12 @@ impl OctData for String {
         if len == -1 {
             return Ok(Self::new());
         }
-        let mut buf = vec![0; len as usize];
-        r.read_exact(&mut buf)?;
-        Ok(Self::from_utf8(buf).unwrap())
+        if len < 0 {
+            return Err(invalid_length(len));
+        }
+        let buf = read_bytes(r, len as usize)?;
+        Self::from_utf8(buf)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
     }
 }
 
@@ -1,16 +1,40 @@
 #![allow(incomplete_features)]
 #![feature(specialization)]
 
+mod apply;
+mod arbitrary;
+#[cfg(feature = "protocol")]
+mod capture;
+#[cfg(feature = "codec")]
+mod codec;
 #[cfg(feature = "collection")]
 mod collection;
+#[cfg(feature = "collection")]
+mod diff;
+mod error;
 #[cfg(feature = "fastoct")]
 mod fastoct;
 #[cfg(feature = "protocol")]
 mod protocol;
+mod revision;
+mod schema;
+mod visibility;
 
+pub use apply::*;
+pub use arbitrary::*;
+#[cfg(feature = "protocol")]
+pub use capture::*;
+#[cfg(feature = "codec")]
+pub use codec::*;
 #[cfg(feature = "collection")]
 pub use collection::*;
+#[cfg(feature = "collection")]
+pub use diff::*;
+pub use error::*;
 #[cfg(feature = "fastoct")]
 pub use fastoct::*;
 #[cfg(feature = "protocol")]
 pub use protocol::*;
+pub use revision::*;
+pub use schema::*;
+pub use visibility::*;
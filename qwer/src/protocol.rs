@@ -1,4 +1,68 @@
-#[derive(Debug, Default)]
+use std::fmt;
+
+pub(crate) const PROTOCOL_HEADER_SIZE: usize = 13;
+const REQUEST_BODY_PREFIX_SIZE: usize = 6;
+const RESPONSE_BODY_PREFIX_SIZE: usize = 4;
+const MIDDLEWARE_COUNT_SIZE: usize = 2;
+const MIDDLEWARE_ENTRY_PREFIX_SIZE: usize = 6;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProtocolError {
+    Truncated {
+        expected: usize,
+        actual: usize,
+    },
+    HeaderTooLarge {
+        size: usize,
+        limit: usize,
+    },
+    BodyTooLarge {
+        size: usize,
+        limit: usize,
+    },
+    /// A length or count doesn't fit the prefix it's encoded in.
+    LengthOverflow {
+        field: &'static str,
+        len: usize,
+    },
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated { expected, actual } => {
+                write!(
+                    f,
+                    "truncated frame: expected {expected} bytes, got {actual}"
+                )
+            }
+            Self::HeaderTooLarge { size, limit } => {
+                write!(f, "header size {size} exceeds limit of {limit} bytes")
+            }
+            Self::BodyTooLarge { size, limit } => {
+                write!(f, "body size {size} exceeds limit of {limit} bytes")
+            }
+            Self::LengthOverflow { field, len } => {
+                write!(f, "{field} length {len} doesn't fit its prefix")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+fn ensure_len(value: &[u8], expected: usize) -> Result<(), ProtocolError> {
+    if value.len() < expected {
+        return Err(ProtocolError::Truncated {
+            expected,
+            actual: value.len(),
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct ProtocolHeader {
     pub to_channel: u16,
     pub from_channel: u16,
@@ -6,25 +70,29 @@ pub struct ProtocolHeader {
     pub rpc_arg_uid: u64,
 }
 
-impl From<Vec<u8>> for ProtocolHeader {
-    fn from(value: Vec<u8>) -> Self {
+impl TryFrom<&[u8]> for ProtocolHeader {
+    type Error = ProtocolError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        ensure_len(value, PROTOCOL_HEADER_SIZE)?;
+
         let to_channel = u16::from_le_bytes(value[0..2].try_into().unwrap());
         let from_channel = u16::from_le_bytes(value[2..4].try_into().unwrap());
         let is_rpc_ret = value[4] != 100;
         let rpc_arg_uid = u64::from_le_bytes(value[5..13].try_into().unwrap());
 
-        Self {
+        Ok(Self {
             to_channel,
             from_channel,
             is_rpc_ret,
             rpc_arg_uid,
-        }
+        })
     }
 }
 
 impl From<ProtocolHeader> for Vec<u8> {
     fn from(value: ProtocolHeader) -> Self {
-        let mut out = Self::with_capacity(13);
+        let mut out = Self::with_capacity(PROTOCOL_HEADER_SIZE);
 
         out.extend(value.to_channel.to_le_bytes());
         out.extend(value.from_channel.to_le_bytes());
@@ -34,3 +102,200 @@ impl From<ProtocolHeader> for Vec<u8> {
         out
     }
 }
+
+// Middleware entries trail the request payload: a LE u16 count followed by entries that
+// mirror the payload prefix (LE u16 id, BE u32 length, data).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MiddlewareEntry {
+    pub id: u16,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct RequestBody {
+    pub protocol_id: u16,
+    pub payload: Vec<u8>,
+    pub middlewares: Vec<MiddlewareEntry>,
+}
+
+impl TryFrom<&[u8]> for RequestBody {
+    type Error = ProtocolError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        ensure_len(value, REQUEST_BODY_PREFIX_SIZE)?;
+
+        let protocol_id = u16::from_le_bytes(value[0..2].try_into().unwrap());
+        let payload_length = u32::from_be_bytes(value[2..6].try_into().unwrap()) as usize;
+
+        let payload_end = REQUEST_BODY_PREFIX_SIZE.saturating_add(payload_length);
+        ensure_len(value, payload_end)?;
+
+        Ok(Self {
+            protocol_id,
+            payload: value[REQUEST_BODY_PREFIX_SIZE..payload_end].to_vec(),
+            middlewares: read_middlewares(&value[payload_end..])?,
+        })
+    }
+}
+
+fn read_middlewares(value: &[u8]) -> Result<Vec<MiddlewareEntry>, ProtocolError> {
+    // Older clients omit the middleware section entirely.
+    if value.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    ensure_len(value, MIDDLEWARE_COUNT_SIZE)?;
+    let count = u16::from_le_bytes(value[0..2].try_into().unwrap()) as usize;
+
+    let mut middlewares = Vec::with_capacity(count.min(value.len() / MIDDLEWARE_ENTRY_PREFIX_SIZE));
+    let mut rest = &value[MIDDLEWARE_COUNT_SIZE..];
+    for _ in 0..count {
+        ensure_len(rest, MIDDLEWARE_ENTRY_PREFIX_SIZE)?;
+        let id = u16::from_le_bytes(rest[0..2].try_into().unwrap());
+        let data_length = u32::from_be_bytes(rest[2..6].try_into().unwrap()) as usize;
+
+        let data_end = MIDDLEWARE_ENTRY_PREFIX_SIZE.saturating_add(data_length);
+        ensure_len(rest, data_end)?;
+
+        middlewares.push(MiddlewareEntry {
+            id,
+            data: rest[MIDDLEWARE_ENTRY_PREFIX_SIZE..data_end].to_vec(),
+        });
+        rest = &rest[data_end..];
+    }
+
+    Ok(middlewares)
+}
+
+fn length_prefix<T: TryFrom<usize>>(field: &'static str, len: usize) -> Result<T, ProtocolError> {
+    T::try_from(len).map_err(|_| ProtocolError::LengthOverflow { field, len })
+}
+
+impl TryFrom<RequestBody> for Vec<u8> {
+    type Error = ProtocolError;
+
+    fn try_from(value: RequestBody) -> Result<Self, Self::Error> {
+        let mut out = Self::with_capacity(
+            REQUEST_BODY_PREFIX_SIZE + value.payload.len() + MIDDLEWARE_COUNT_SIZE,
+        );
+
+        out.extend(value.protocol_id.to_le_bytes());
+        out.extend(length_prefix::<u32>("payload", value.payload.len())?.to_be_bytes());
+        out.extend(value.payload);
+
+        out.extend(
+            length_prefix::<u16>("middleware section", value.middlewares.len())?.to_le_bytes(),
+        );
+        for middleware in value.middlewares {
+            out.extend(middleware.id.to_le_bytes());
+            out.extend(
+                length_prefix::<u32>("middleware data", middleware.data.len())?.to_be_bytes(),
+            );
+            out.extend(middleware.data);
+        }
+
+        Ok(out)
+    }
+}
+
+#[derive(Debug)]
+pub struct ResponseBody {
+    pub middleware_id: u16,
+    pub middleware_error_code: u16,
+    pub payload: Vec<u8>,
+}
+
+impl TryFrom<&[u8]> for ResponseBody {
+    type Error = ProtocolError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        ensure_len(value, RESPONSE_BODY_PREFIX_SIZE)?;
+
+        Ok(Self {
+            middleware_id: u16::from_le_bytes(value[0..2].try_into().unwrap()),
+            middleware_error_code: u16::from_le_bytes(value[2..4].try_into().unwrap()),
+            payload: value[RESPONSE_BODY_PREFIX_SIZE..].to_vec(),
+        })
+    }
+}
+
+impl From<ResponseBody> for Vec<u8> {
+    fn from(value: ResponseBody) -> Self {
+        let mut out = Self::with_capacity(RESPONSE_BODY_PREFIX_SIZE + value.payload.len());
+        out.extend(value.middleware_id.to_le_bytes());
+        out.extend(value.middleware_error_code.to_le_bytes());
+        out.extend(value.payload);
+
+        out
+    }
+}
+
+#[test]
+fn test_truncated_header() {
+    assert_eq!(
+        ProtocolHeader::try_from(&[0u8; 5][..]).unwrap_err(),
+        ProtocolError::Truncated {
+            expected: PROTOCOL_HEADER_SIZE,
+            actual: 5
+        }
+    );
+}
+
+#[test]
+fn test_request_body_payload_length_overflow() {
+    let mut body = vec![0x64, 0x00];
+    body.extend(u32::MAX.to_be_bytes());
+
+    assert!(matches!(
+        RequestBody::try_from(&body[..]),
+        Err(ProtocolError::Truncated { .. })
+    ));
+}
+
+#[test]
+fn test_request_body_round_trip() {
+    let middlewares = vec![MiddlewareEntry {
+        id: 7,
+        data: vec![4, 5],
+    }];
+    let encoded = Vec::try_from(RequestBody {
+        protocol_id: 100,
+        payload: vec![1, 2, 3],
+        middlewares: middlewares.clone(),
+    })
+    .unwrap();
+
+    let decoded = RequestBody::try_from(&encoded[..]).unwrap();
+    assert_eq!(decoded.protocol_id, 100);
+    assert_eq!(decoded.payload, vec![1, 2, 3]);
+    assert_eq!(decoded.middlewares, middlewares);
+}
+
+#[test]
+fn test_request_body_without_middleware_section() {
+    let body = [0x64, 0x00, 0x00, 0x00, 0x00, 0x01, 0xAA];
+    let decoded = RequestBody::try_from(&body[..]).unwrap();
+
+    assert_eq!(decoded.payload, vec![0xAA]);
+    assert!(decoded.middlewares.is_empty());
+}
+
+#[test]
+fn test_request_body_middleware_count_overflow() {
+    let request = RequestBody {
+        protocol_id: 100,
+        payload: Vec::new(),
+        middlewares: vec![
+            MiddlewareEntry {
+                id: 1,
+                data: Vec::new(),
+            };
+            usize::from(u16::MAX) + 1
+        ],
+    };
+
+    assert!(matches!(
+        Vec::try_from(request),
+        Err(ProtocolError::LengthOverflow { len: 65536, .. })
+    ));
+}
@@ -0,0 +1,20 @@
+use std::cell::Cell;
+
+thread_local! {
+    static WIRE_REVISION: Cell<u32> = const { Cell::new(u32::MAX) };
+}
+
+/// Layout revision that `#[since = N]` fields are checked against. Everything is written when
+/// no revision is set.
+#[must_use]
+pub fn wire_revision() -> u32 {
+    WIRE_REVISION.get()
+}
+
+/// Runs `f` with the wire layout of `revision`, e.g. to marshal for an older client build.
+pub fn with_wire_revision<R>(revision: u32, f: impl FnOnce() -> R) -> R {
+    let previous = WIRE_REVISION.replace(revision);
+    let result = f();
+    WIRE_REVISION.set(previous);
+    result
+}
@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "collection")]
+use crate::{PropertyDoubleKeyHashMap, PropertyHashMap, PropertyHashSet};
+
+/// Static description of a type's wire layout, emitted by `#[derive(OctData)]`.
+pub trait OctSchema {
+    fn schema() -> &'static TypeSchema;
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TypeSchema {
+    Struct(StructSchema),
+    Enum(EnumSchema),
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StructSchema {
+    pub name: &'static str,
+    pub property_object: bool,
+    /// Value written ahead of the fields by `#[property_object(ty, value)]`.
+    pub padding: Option<PaddingSchema>,
+    pub root: bool,
+    pub fields: &'static [FieldSchema],
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PaddingSchema {
+    pub ty: &'static str,
+    pub value: u64,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EnumSchema {
+    pub name: &'static str,
+    pub repr: &'static str,
+    pub base: Option<i16>,
+    pub variants: &'static [VariantSchema],
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VariantSchema {
+    pub name: &'static str,
+    pub discriminant: i64,
+    pub polymorphic_none: bool,
+    pub fields: &'static [FieldSchema],
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldSchema {
+    /// Field name, or its position for tuple fields.
+    pub name: &'static str,
+    /// Type as written in the declaration.
+    pub ty: &'static str,
+    pub tag: Option<u16>,
+    /// First wire revision the field is written in.
+    pub since: Option<u32>,
+    /// `bt_property_tag` flags the field is written under, if restricted.
+    pub visibility: Option<u16>,
+    pub skip_property: bool,
+    pub property_blob: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub nested: fn(&mut Vec<&'static TypeSchema>),
+}
+
+impl TypeSchema {
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Struct(schema) => schema.name,
+            Self::Enum(schema) => schema.name,
+        }
+    }
+
+    #[must_use]
+    pub fn fields(&self) -> Vec<&'static FieldSchema> {
+        match self {
+            Self::Struct(schema) => schema.fields.iter().collect(),
+            Self::Enum(schema) => schema
+                .variants
+                .iter()
+                .flat_map(|variant| variant.fields)
+                .collect(),
+        }
+    }
+
+    /// This schema followed by every derived type reachable from its fields, each listed once.
+    #[must_use]
+    pub fn walk(&'static self) -> Vec<&'static Self> {
+        let mut visited = vec![self];
+        let mut index = 0;
+
+        while let Some(schema) = visited.get(index) {
+            let mut nested = Vec::new();
+            for field in schema.fields() {
+                (field.nested)(&mut nested);
+            }
+
+            for schema in nested {
+                if !visited.iter().any(|seen| std::ptr::eq(*seen, schema)) {
+                    visited.push(schema);
+                }
+            }
+            index += 1;
+        }
+
+        visited
+    }
+}
+
+/// Collects the schemas of derived types found inside a field type, e.g. `ItemInfo` in
+/// `Option<PropertyHashMap<u64, ItemInfo>>`.
+pub trait NestedSchemas {
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>);
+}
+
+impl<T> NestedSchemas for T {
+    default fn nested_schemas(_out: &mut Vec<&'static TypeSchema>) {}
+}
+
+impl<T: OctSchema> NestedSchemas for T {
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>) {
+        out.push(T::schema());
+    }
+}
+
+impl<T> NestedSchemas for Option<T> {
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>) {
+        T::nested_schemas(out);
+    }
+}
+
+impl<T> NestedSchemas for Vec<T> {
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>) {
+        T::nested_schemas(out);
+    }
+}
+
+impl<T> NestedSchemas for HashSet<T> {
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>) {
+        T::nested_schemas(out);
+    }
+}
+
+impl<K, V> NestedSchemas for HashMap<K, V> {
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>) {
+        K::nested_schemas(out);
+        V::nested_schemas(out);
+    }
+}
+
+#[cfg(feature = "collection")]
+impl<T> NestedSchemas for PropertyHashSet<T>
+where
+    T: crate::OctData + Eq + std::hash::Hash,
+{
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>) {
+        T::nested_schemas(out);
+    }
+}
+
+#[cfg(feature = "collection")]
+impl<K, V> NestedSchemas for PropertyHashMap<K, V>
+where
+    K: crate::OctData + Eq + std::hash::Hash,
+    V: crate::OctData,
+{
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>) {
+        K::nested_schemas(out);
+        V::nested_schemas(out);
+    }
+}
+
+#[cfg(feature = "collection")]
+impl<K1, K2, V> NestedSchemas for PropertyDoubleKeyHashMap<K1, K2, V>
+where
+    K1: crate::OctData + Eq + std::hash::Hash,
+    K2: crate::OctData + Eq + std::hash::Hash,
+    V: crate::OctData,
+{
+    fn nested_schemas(out: &mut Vec<&'static TypeSchema>) {
+        K1::nested_schemas(out);
+        K2::nested_schemas(out);
+        V::nested_schemas(out);
+    }
+}
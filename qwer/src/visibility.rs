@@ -0,0 +1,10 @@
+// `bt_property_tag` bits for `#[visibility(..)]` property fields. Tag 0 writes every field.
+pub const CLIENT_PROP_FLAG: u16 = 1;
+pub const SERVER_PROP_FLAG: u16 = 2;
+pub const PERSIST_PROP_FLAG: u16 = 4;
+
+/// Whether a property field with the `visibility` flags is written under `bt_property_tag`.
+#[must_use]
+pub const fn is_visible(bt_property_tag: u16, visibility: u16) -> bool {
+    bt_property_tag == 0 || bt_property_tag & visibility != 0
+}
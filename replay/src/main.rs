@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use qwer::{
+    CaptureDirection, CaptureReader, CaptureRecord, Frame, FrameBody, GatewayCodec, RequestBody,
+};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_util::codec::Framed;
+
+const DEFAULT_GATEWAY_ENDPOINT: &str = "127.0.0.1:10301";
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let Some(capture_path) = args.next() else {
+        bail!("usage: nap-replay <capture file> [gateway endpoint]");
+    };
+    let endpoint = args
+        .next()
+        .unwrap_or_else(|| DEFAULT_GATEWAY_ENDPOINT.to_string());
+
+    let reader = CaptureReader::new(BufReader::new(
+        File::open(&capture_path).with_context(|| format!("failed to open {capture_path}"))?,
+    ))?;
+    let channel_id = reader.channel_id();
+    let records = reader.collect::<Result<Vec<_>, _>>()?;
+
+    let mut stream = TcpStream::connect(&endpoint)
+        .await
+        .with_context(|| format!("failed to connect to {endpoint}"))?;
+    stream.write_u16_le(channel_id).await?;
+    let mut stream = Framed::new(stream, GatewayCodec::default());
+
+    let mut mismatches = 0;
+    let mut records = records.into_iter().peekable();
+    while let Some(request) = records.next() {
+        if request.direction != CaptureDirection::Inbound {
+            continue;
+        }
+
+        let mut expected = Vec::new();
+        while let Some(record) =
+            records.next_if(|record| record.direction == CaptureDirection::Outbound)
+        {
+            expected.push(record);
+        }
+
+        println!(
+            "-> protocol {} ({} bytes, {} expected responses)",
+            request.protocol_id,
+            request.payload.len(),
+            expected.len()
+        );
+        stream
+            .send(Frame::request(
+                request.header,
+                RequestBody {
+                    protocol_id: request.protocol_id,
+                    payload: request.payload,
+                    middlewares: Vec::new(),
+                },
+            ))
+            .await?;
+
+        for record in &expected {
+            let Ok(frame) = timeout(RESPONSE_TIMEOUT, stream.next()).await else {
+                println!("   missing response for protocol {}", record.protocol_id);
+                mismatches += 1;
+                continue;
+            };
+            let Some(frame) = frame else {
+                bail!("gateway closed the connection");
+            };
+
+            if !compare(record, &frame?) {
+                mismatches += 1;
+            }
+        }
+    }
+
+    if mismatches != 0 {
+        bail!("replay finished with {mismatches} mismatched responses");
+    }
+
+    println!("replay finished, all responses matched");
+    Ok(())
+}
+
+fn compare(expected: &CaptureRecord, actual: &Frame) -> bool {
+    let kind = if expected.header.is_rpc_ret {
+        "ret"
+    } else {
+        "ptc"
+    };
+
+    let (protocol_id, payload) = match &actual.body {
+        FrameBody::Request(request) => (Some(request.protocol_id), &request.payload),
+        FrameBody::Response(response) => (None, &response.payload),
+    };
+
+    if expected.header.is_rpc_ret != actual.header.is_rpc_ret
+        || (!actual.header.is_rpc_ret && protocol_id != Some(expected.protocol_id))
+    {
+        println!(
+            "   expected {kind} for protocol {}, got {}",
+            expected.protocol_id,
+            protocol_id.map_or_else(
+                || String::from("ret"),
+                |protocol_id| format!("ptc for protocol {protocol_id}")
+            )
+        );
+        return false;
+    }
+
+    if expected.payload != *payload {
+        println!(
+            "   {kind} for protocol {} differs: expected {} bytes, got {} bytes",
+            expected.protocol_id,
+            expected.payload.len(),
+            payload.len()
+        );
+        return false;
+    }
+
+    println!("   {kind} for protocol {} matched", expected.protocol_id);
+    true
+}
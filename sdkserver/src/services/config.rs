@@ -1,51 +1,111 @@
+use std::collections::HashMap;
 use std::sync::LazyLock;
 
-use axum::{body::Body, response::IntoResponse};
+use axum::{body::Body, extract::Path, http::StatusCode, response::IntoResponse};
 use rand::Rng;
 use serde::de::DeserializeOwned;
 
 use crate::crypto;
 use crate::data::config::*;
 
-pub const APP_CONFIG_ENDPOINT: &str = "/design_data/NAP_Publish_AppStore_0.1.0/oversea/config.bin";
-pub const SERVER_LIST_ENDPOINT: &str =
-    "/design_data/NAP_Publish_AppStore_0.1.0/oversea/serverlist.bin";
-pub const VERSIONS_BUNDLE_ENDPOINT: &str = "/game_res/NAP_Publish/output_147608_1361f678bc/client/StandaloneWindows64/oversea/versions.bundle";
+pub const APP_CONFIG_ENDPOINT: &str = "/design_data/:branch/oversea/config.bin";
+pub const SERVER_LIST_ENDPOINT: &str = "/design_data/:branch/oversea/serverlist.bin";
+pub const VERSIONS_BUNDLE_ENDPOINT: &str =
+    "/game_res/NAP_Publish/:build/client/StandaloneWindows64/oversea/versions.bundle";
 
-static APP_CONFIG: LazyLock<AppConfig> = LazyLock::new(|| read_config("config.json"));
-static SERVER_LIST: LazyLock<Vec<ServerListInfo>> =
-    LazyLock::new(|| read_config("serverlist.json"));
+const APPLICATION_DIR: &str = "assets/Application";
+// Design data branches are named after the client version, e.g. `NAP_Publish_AppStore_0.1.0`.
+const DESIGN_DATA_BRANCH_PREFIX: &str = "NAP_Publish_AppStore_";
 
-static VERSIONS_BUNDLE: LazyLock<Box<[u8]>> = LazyLock::new(|| read_binary_data("versions.bundle"));
+struct VersionAssets {
+    app_config: AppConfig,
+    server_list: Vec<ServerListInfo>,
+    versions_bundle: Box<[u8]>,
+}
+
+impl VersionAssets {
+    fn load(version: &str) -> Self {
+        Self {
+            app_config: read_config(version, "config.json"),
+            server_list: read_config(version, "serverlist.json"),
+            versions_bundle: read_binary_data(version, "versions.bundle"),
+        }
+    }
+
+    // Resource builds are only named in the `GameResUrl` of the version's config.
+    fn serves_build(&self, build: &str) -> bool {
+        let segment = format!("/{build}/");
+        self.app_config.info_groups.values().any(|group| {
+            group
+                .version_info_groups
+                .values()
+                .any(|info| info.game_res_url.contains(&segment))
+        })
+    }
+}
 
-pub async fn application() -> Vec<u8> {
-    crypto::encrypt_config(
-        serde_json::to_string(&*APP_CONFIG).unwrap().as_str(),
+/// Every directory in `assets/Application` holds the assets of the client version it's named after.
+static VERSIONS: LazyLock<HashMap<String, VersionAssets>> = LazyLock::new(|| {
+    std::fs::read_dir(APPLICATION_DIR)
+        .unwrap()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let version = entry.file_name().to_string_lossy().into_owned();
+            tracing::info!("Loaded application assets for client version {version}");
+            (version.clone(), VersionAssets::load(&version))
+        })
+        .collect()
+});
+
+pub async fn application(Path(branch): Path<String>) -> Result<Vec<u8>, StatusCode> {
+    let assets = version_for_branch(&branch)?;
+
+    Ok(crypto::encrypt_config(
+        serde_json::to_string(&assets.app_config).unwrap().as_str(),
         &random_xorpad(16),
-    )
+    ))
 }
 
-pub async fn server_list() -> Vec<u8> {
-    crypto::encrypt_config(
-        serde_json::to_string(&*SERVER_LIST).unwrap().as_str(),
+pub async fn server_list(Path(branch): Path<String>) -> Result<Vec<u8>, StatusCode> {
+    let assets = version_for_branch(&branch)?;
+
+    Ok(crypto::encrypt_config(
+        serde_json::to_string(&assets.server_list).unwrap().as_str(),
         &random_xorpad(16),
-    )
+    ))
 }
 
-pub async fn versions_bundle() -> impl IntoResponse {
-    Body::from(&**VERSIONS_BUNDLE)
+pub async fn versions_bundle(Path(build): Path<String>) -> Result<impl IntoResponse, StatusCode> {
+    let Some(assets) = VERSIONS.values().find(|assets| assets.serves_build(&build)) else {
+        tracing::warn!("No client version uses resource build {build}");
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    Ok(Body::from(&*assets.versions_bundle))
+}
+
+fn version_for_branch(branch: &str) -> Result<&'static VersionAssets, StatusCode> {
+    let version = branch
+        .strip_prefix(DESIGN_DATA_BRANCH_PREFIX)
+        .unwrap_or(branch);
+
+    VERSIONS.get(version).ok_or_else(|| {
+        tracing::warn!("No application assets for client version {version}");
+        StatusCode::NOT_FOUND
+    })
 }
 
-fn read_config<T>(file: &str) -> T
+fn read_config<T>(version: &str, file: &str) -> T
 where
     T: DeserializeOwned,
 {
-    let data = std::fs::read_to_string(format!("assets/Application/{file}")).unwrap();
+    let data = std::fs::read_to_string(format!("{APPLICATION_DIR}/{version}/{file}")).unwrap();
     serde_json::from_str::<T>(&data).unwrap()
 }
 
-fn read_binary_data(file: &str) -> Box<[u8]> {
-    std::fs::read(format!("assets/Application/{file}"))
+fn read_binary_data(version: &str, file: &str) -> Box<[u8]> {
+    std::fs::read(format!("{APPLICATION_DIR}/{version}/{file}"))
         .unwrap()
         .into()
 }